@@ -0,0 +1,56 @@
+// 実績：テーブルに1行足せば新しい実績が増える（判定は RunStats だけを見る）
+use crate::core::RunStats;
+use crate::i18n::Msg;
+use crate::storage::Storage;
+
+pub(crate) struct Achievement {
+    pub id: &'static str,
+    // 画面に出す名前（Lang::text で引く）
+    pub title: Msg,
+    check: fn(&RunStats) -> bool,
+}
+
+pub(crate) const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "survive_60", title: Msg::AchSurvivor, check: |s| s.elapsed >= 60.0 },
+    Achievement { id: "statue_1000", title: Msg::AchStatue, check: |s| !s.moved && s.score >= 1000.0 },
+    Achievement { id: "dodge_500", title: Msg::AchDodger, check: |s| s.dodged >= 500 },
+    Achievement { id: "close_10", title: Msg::AchDaredevil, check: |s| s.near_misses >= 10 },
+];
+
+const STORAGE_KEY: &str = "meteor_dodge.achievements";
+
+pub(crate) struct Achievements {
+    unlocked: Vec<&'static str>,
+}
+
+impl Achievements {
+    // 解除済みの実績を読み込む（使えなければ空から）
+    pub fn load(storage: &dyn Storage) -> Self {
+        let saved = storage.get(STORAGE_KEY).unwrap_or_default();
+        let unlocked = ACHIEVEMENTS.iter()
+            .filter(|a| saved.split(',').any(|id| id == a.id))
+            .map(|a| a.id)
+            .collect();
+        Self { unlocked }
+    }
+
+    fn save(&self, storage: &dyn Storage) {
+        storage.set(STORAGE_KEY, &self.unlocked.join(","));
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(&id)
+    }
+
+    // update() から毎フレーム呼ぶ。今回新しく解除された実績を返す
+    pub fn check(&mut self, stats: &RunStats, storage: &dyn Storage) -> Vec<&'static Achievement> {
+        let newly: Vec<&'static Achievement> = ACHIEVEMENTS.iter()
+            .filter(|a| !self.is_unlocked(a.id) && (a.check)(stats))
+            .collect();
+        if !newly.is_empty() {
+            self.unlocked.extend(newly.iter().map(|a| a.id));
+            self.save(storage);
+        }
+        newly
+    }
+}
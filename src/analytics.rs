@@ -0,0 +1,85 @@
+// 遊ばれ方の集計（任意、既定では何も送らない）。サイトの持ち主が config の analyticsEndpoint か GameCell::set_analytics で
+// 送り先を決めたときだけ、ソロのランの始まりと終わりを渡す。名前・シード・入力など個人やランを特定できるものは含めない。
+//   {"event":"runStart","mode"}
+//   {"event":"runEnd","mode","seconds","score","cause","assisted"}
+// cause は落ちた原因（hit イベントの cause：隕石 "meteor" / ブラックホール "well" / レーザー "laser"）か、
+// "restart"（終わる前にやり直した）。デモ・対戦・リプレイ・チュートリアルは数えない
+use crate::core::{Game, GameEvent};
+use crate::web::js_object;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Request, RequestInit};
+
+#[derive(Default)]
+pub(crate) struct Analytics {
+    // POST する URL（worker 版ではこちらだけ）
+    endpoint: Option<String>,
+    // ページの関数（メインスレッド版のみ）
+    callback: Option<js_sys::Function>,
+    // 続いているランの (モード, アシストか)
+    run: Option<(&'static str, bool)>,
+    // そのランで 1P が落ちた原因（Collision から）
+    cause: Option<&'static str>,
+    // 前のフレームの経過時間とスコア（やり直しで Start が来た時点では、もう次のランの値になっている）
+    last: (f64, f64),
+}
+
+impl Analytics {
+    pub fn new(endpoint: Option<String>) -> Self { Self { endpoint: endpoint.filter(|e| !e.is_empty()), ..Self::default() } }
+
+    pub fn set_callback(&mut self, callback: Option<js_sys::Function>) { self.callback = callback; }
+
+    fn enabled(&self) -> bool { self.endpoint.is_some() || self.callback.is_some() }
+
+    // drain_events から1つずつ。フレームの最後に finish を呼ぶ
+    pub fn observe(&mut self, game: &Game, e: &GameEvent) {
+        if !self.enabled() { return; }
+        match e {
+            GameEvent::Start => {
+                self.cause = None;
+                if let Some((mode, assisted)) = self.run.take() {
+                    let (seconds, score) = self.last;
+                    self.send(run_end(mode, seconds, score, "restart", assisted));
+                }
+                if !game.solo() { return; }
+                let mode = game.run_mode();
+                self.run = Some((mode, game.assisted()));
+                self.send(js_object(&[("event", "runStart".into()), ("mode", mode.into())]));
+            }
+            GameEvent::Collision { player: 1, cause, .. } => self.cause = Some(*cause),
+            GameEvent::GameOver { score, solo: true, assisted, .. } => {
+                let Some((mode, _)) = self.run.take() else { return };
+                let cause = self.cause.take().unwrap_or("meteor");
+                self.send(run_end(mode, game.stats.elapsed, *score, cause, *assisted));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn finish(&mut self, game: &Game) { self.last = (game.stats.elapsed, game.score.floor()); }
+
+    // 送れなくてもゲームには影響させない
+    fn send(&self, payload: JsValue) {
+        if let Some(f) = &self.callback { let _ = f.call1(&JsValue::NULL, &payload); }
+        let Some(endpoint) = &self.endpoint else { return };
+        let Ok(body) = js_sys::JSON::stringify(&payload) else { return };
+        let init = RequestInit::new();
+        init.set_method("POST");
+        init.set_body(&body.into());
+        let Ok(req) = Request::new_with_str_and_init(endpoint, &init) else { return };
+        let _ = req.headers().set("Content-Type", "application/json");
+        let promise = crate::web::fetch(&req);
+        spawn_local(async move { let _ = JsFuture::from(promise).await; });
+    }
+}
+
+fn run_end(mode: &str, seconds: f64, score: f64, cause: &str, assisted: bool) -> JsValue {
+    js_object(&[
+        ("event", "runEnd".into()),
+        ("mode", mode.into()),
+        ("seconds", ((seconds * 10.0).round() / 10.0).into()),
+        ("score", score.into()),
+        ("cause", cause.into()),
+        ("assisted", assisted.into()),
+    ])
+}
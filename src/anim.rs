@@ -0,0 +1,41 @@
+// スプライトシートのアニメーション：アトラス画像の中のコマ（x, y, w, h）を一定の速さで順に送る
+use serde::{Deserialize, Serialize};
+
+// config の assets.animations.player / meteor
+#[derive(Clone, Deserialize)]
+pub(crate) struct Clip {
+    // アトラス上の [x, y, w, h]（px）
+    pub frames: Vec<[f64; 4]>,
+    #[serde(default = "default_fps")]
+    pub fps: f64,
+}
+
+fn default_fps() -> f64 { 12.0 }
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct AnimationConfig { pub player: Option<Clip>, pub meteor: Option<Clip> }
+
+// 1体ぶんの再生位置（今のコマと、そのコマを表示している時間）
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Animation { pub frame: usize, pub time: f64 }
+
+impl Animation {
+    // 途中のコマから始める（同じ絵の隕石がそろって回らないように）
+    pub fn starting_at(frame: usize) -> Self { Self { frame, time: 0.0 } }
+
+    pub fn tick(&mut self, dt: f64, clip: &Clip) {
+        if clip.frames.len() < 2 || clip.fps <= 0.0 { return; }
+        self.time += dt;
+        let step = 1.0 / clip.fps;
+        while self.time >= step {
+            self.time -= step;
+            self.frame = (self.frame + 1) % clip.frames.len();
+        }
+    }
+
+    // 今のコマのアトラス上の位置（コマが無ければ None）
+    pub fn source(&self, clip: &Clip) -> Option<[f64; 4]> {
+        clip.frames.get(self.frame % clip.frames.len().max(1)).copied()
+    }
+}
@@ -0,0 +1,56 @@
+// スクリーンリーダー向けの読み上げ。canvas の直後に見えない aria-live の領域を置き、主なイベントを文で入れる
+use crate::core::GameEvent;
+use crate::i18n::{Lang, Msg};
+use web_sys::{window, Element, HtmlCanvasElement};
+
+// スコアは 500 ごとに読む（score イベントは 100 ごと）
+const SCORE_STEP: u64 = 500;
+
+// 読み上げる文（近接回避と隕石の出現は多すぎるので読まない。被弾はゲームオーバーの文で伝わる）
+pub(crate) fn message(e: &GameEvent, lang: Lang) -> Option<String> {
+    Some(match e {
+        GameEvent::Start => lang.text(Msg::SayStart).to_string(),
+        GameEvent::Score { score } => {
+            let score = *score as u64 / 100 * 100;
+            if score == 0 || !score.is_multiple_of(SCORE_STEP) { return None; }
+            lang.fill(Msg::SayScore, &[&score])
+        }
+        GameEvent::GameOver { winner: Some(w), .. } => lang.fill(Msg::SayWinner, &[w]),
+        GameEvent::GameOver { score, new_record: true, .. } => lang.fill(Msg::SayFinalRecord, &[&(*score as u64)]),
+        GameEvent::GameOver { score, .. } => lang.fill(Msg::SayFinal, &[&(*score as u64)]),
+        GameEvent::Wave { stage, name, .. } => lang.fill(Msg::SayStage, &[stage, name]),
+        GameEvent::Achievement { title, .. } => lang.fill(Msg::SayAchievement, &[title]),
+        // 罠は画面の警告と同じ文で知らせる（宝石は読まない）
+        GameEvent::PowerUp { kind: "invert", .. } => lang.text(Msg::TrapInvert).to_string(),
+        GameEvent::PowerUp { kind: "fog", .. } => lang.text(Msg::TrapFog).to_string(),
+        GameEvent::PowerUp { kind: "rush", .. } => lang.text(Msg::TrapRush).to_string(),
+        GameEvent::PowerUp { .. } => return None,
+        // 節目のスコアは score の読み上げで伝わる
+        GameEvent::NearMiss { .. } | GameEvent::Milestone { .. } | GameEvent::MeteorSpawned { .. } | GameEvent::Collision { .. } => return None,
+    })
+}
+
+pub(crate) struct Announcer { el: Element }
+
+impl Announcer {
+    // 置けなければ None（読み上げなしで動く）
+    pub fn new(canvas: &HtmlCanvasElement) -> Option<Self> {
+        let el = window()?.document()?.create_element("div").ok()?;
+        let _ = el.set_attribute("role", "status");
+        let _ = el.set_attribute("aria-live", "polite");
+        // 画面には出さずに読み上げだけさせる（display:none だと読まれない）
+        let _ = el.set_attribute("style", "position:absolute;width:1px;height:1px;margin:-1px;overflow:hidden;clip:rect(0 0 0 0);white-space:nowrap");
+        canvas.insert_adjacent_element("afterend", &el).ok()?;
+        Some(Self { el })
+    }
+
+    // 同じ文が続いても読まれるよう、いったん空にしてから入れる
+    pub fn say(&self, text: &str) {
+        self.el.set_text_content(None);
+        self.el.set_text_content(Some(text));
+    }
+}
+
+impl Drop for Announcer {
+    fn drop(&mut self) { self.el.remove(); }
+}
@@ -0,0 +1,119 @@
+// 画像アセット（自機・隕石・背景）。fetch して ImageBitmap にデコードし、そろうまでは今までの矩形で描く。
+// 文字用のフォントも FontFace で読み込み、document.fonts（worker では self.fonts）に足す
+use crate::anim::AnimationConfig;
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{FontFace, FontFaceSet, ImageBitmap, Request, Response};
+
+// 読み込んだフォントの名前（canvas の font ではこの名前を先頭に置く）
+pub(crate) const GAME_FONT: &str = "meteor-dodge-game";
+// フォントが届くまで文字を描かずに待つ時間（過ぎたらシステムのフォントで描き、届いたら切り替える）
+const FONT_WAIT_MS: f64 = 3000.0;
+
+// config の assets：それぞれ画像の URL（書かなかったものは矩形のまま）。
+// atlas と animations を書くと、自機と隕石はアトラスのコマ送りで描く（単体の画像より優先）
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct AssetConfig {
+    pub player: Option<String>,
+    pub meteor: Option<String>,
+    pub background: Option<String>,
+    pub atlas: Option<String>,
+    pub animations: AnimationConfig,
+    // 文字のフォント（woff2 などの URL）。書かなければシステムのフォント
+    pub font: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum AssetId { Player, Meteor, Background, Atlas }
+
+enum Slot { Unused, Loading, Ready(ImageBitmap), Failed }
+
+#[derive(Clone, Copy, PartialEq)]
+enum FontState { Unused, Loading, Ready, Failed }
+
+pub(crate) struct Assets {
+    // 読み込みの完了は非同期なので共有する（AssetId の順）
+    slots: Rc<RefCell<[Slot; 4]>>,
+    font: Rc<Cell<FontState>>,
+    // 読み込みを始めた時刻（ms）
+    started: f64,
+}
+
+impl Assets {
+    pub fn load(config: &AssetConfig) -> Self {
+        let slots = Rc::new(RefCell::new([Slot::Unused, Slot::Unused, Slot::Unused, Slot::Unused]));
+        let urls = [
+            (AssetId::Player, &config.player),
+            (AssetId::Meteor, &config.meteor),
+            (AssetId::Background, &config.background),
+            (AssetId::Atlas, &config.atlas),
+        ];
+        for (id, url) in urls {
+            let Some(url) = url.clone() else { continue };
+            slots.borrow_mut()[id as usize] = Slot::Loading;
+            let slots = slots.clone();
+            spawn_local(async move {
+                slots.borrow_mut()[id as usize] = match decode(&url).await {
+                    Ok(img) => Slot::Ready(img),
+                    Err(_) => Slot::Failed,
+                };
+            });
+        }
+        let font = Rc::new(Cell::new(FontState::Unused));
+        if let Some(url) = config.font.clone() {
+            font.set(FontState::Loading);
+            let font = font.clone();
+            spawn_local(async move {
+                font.set(if load_font(&url).await.is_ok() { FontState::Ready } else { FontState::Failed });
+            });
+        }
+        Self { slots, font, started: crate::web::now_ms() }
+    }
+
+    // 読み込み済みならその画像（失敗・読み込み中・未指定は None）
+    pub fn get(&self, id: AssetId) -> Option<ImageBitmap> {
+        match &self.slots.borrow()[id as usize] { Slot::Ready(img) => Some(img.clone()), _ => None }
+    }
+
+    // 読み込んだフォントが使えるか
+    pub fn font_ready(&self) -> bool { self.font.get() == FontState::Ready }
+
+    // 文字を描いてよいか（フォントの読み込み中は、待ち時間を過ぎるまで描かない）
+    pub fn text_ready(&self) -> bool { self.font.get() != FontState::Loading || crate::web::now_ms() - self.started > FONT_WAIT_MS }
+
+    // (済んだ数, 指定された数)。失敗したものも済んだ側に数える
+    pub fn progress(&self) -> (u32, u32) {
+        let slots = self.slots.borrow();
+        let font = self.font.get();
+        let total = slots.iter().filter(|s| !matches!(s, Slot::Unused)).count() as u32 + (font != FontState::Unused) as u32;
+        let loading = slots.iter().filter(|s| matches!(s, Slot::Loading)).count() as u32 + (font == FontState::Loading) as u32;
+        (total - loading, total)
+    }
+}
+
+async fn decode(url: &str) -> Result<ImageBitmap, JsValue> {
+    let req = Request::new_with_str(url)?;
+    let resp: Response = JsFuture::from(crate::web::fetch(&req)).await?.dyn_into()?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("{} responded {}", url, resp.status())));
+    }
+    let blob = JsFuture::from(resp.blob()?).await?.dyn_into()?;
+    JsFuture::from(crate::web::create_image_bitmap(&blob)?).await?.dyn_into()
+}
+
+async fn load_font(url: &str) -> Result<(), JsValue> {
+    let face = FontFace::new_with_str(GAME_FONT, &format!("url({:?})", url))?;
+    JsFuture::from(face.load()?).await?;
+    // document.fonts も self.fonts も FontFaceSet
+    let fonts: FontFaceSet = js_sys::Reflect::get(&js_sys::global(), &"fonts".into()).ok()
+        .filter(|f| !f.is_undefined())
+        .or_else(|| web_sys::window().and_then(|w| w.document()).map(|d| d.fonts().into()))
+        .ok_or_else(|| JsValue::from_str("FontFaceSet unavailable"))?
+        .unchecked_into();
+    fonts.add(&face)
+}
@@ -0,0 +1,208 @@
+// 効果音と BGM（WebAudio）。ファイルは起動時に fetch しておき、AudioContext は自動再生の制限があるので
+// 最初のキー入力やタップ（unlock）で作ってからデコードする
+use crate::core::{Cue, Game, Sound};
+use crate::settings::Settings;
+use crate::synth;
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{AudioBuffer, AudioBufferSourceNode, AudioContext, AudioNode, AudioScheduledSourceNode, GainNode, Request, Response};
+
+// config の audio：効果音ごとのファイルの URL（書かなかったものは鳴らさない）。
+// pickup は宝石、trap は罠を拾ったとき、milestone はスコアの節目。musicTitle はゲームオーバー画面、musicGame はプレイ中にループする。
+// synth を true にすると、ファイルを指定していない効果音はその場で合成して鳴らす（音声ファイルなしで配れる）
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct AudioConfig {
+    pub spawn: Option<String>,
+    pub near_miss: Option<String>,
+    pub pickup: Option<String>,
+    pub hit: Option<String>,
+    pub game_over: Option<String>,
+    pub music_title: Option<String>,
+    pub music_game: Option<String>,
+    pub trap: Option<String>,
+    pub milestone: Option<String>,
+    pub synth: bool,
+}
+
+// 音の置き場所（AudioConfig の順）
+const SLOTS: usize = 9;
+
+// BGM の切り替えにかける時間（秒）
+const FADE: f64 = 1.5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Track { Title, Game }
+
+impl Track {
+    // プレイ中はゲームの曲、ゲームオーバー（結果画面）ではタイトルの曲
+    pub fn for_game(g: &Game) -> Self { if g.over { Track::Title } else { Track::Game } }
+    pub fn name(self) -> &'static str { match self { Track::Title => "title", Track::Game => "game" } }
+    pub fn parse(s: &str) -> Option<Self> { match s { "title" => Some(Track::Title), "game" => Some(Track::Game), _ => None } }
+    fn slot(self) -> usize { match self { Track::Title => 5, Track::Game => 6 } }
+}
+
+// 音量：全体 → (BGM, 効果音) の順に GainNode をつなぐ。消音なら全体を 0 にする
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct Mix { pub master: f64, pub music: f64, pub sfx: f64 }
+
+impl Mix {
+    pub fn of(s: &Settings) -> Self {
+        Self { master: if s.muted { 0.0 } else { s.volume }, music: s.music_volume, sfx: s.sfx_volume }
+    }
+}
+
+// 鳴っている BGM（ループ再生の元と、フェード用の音量）
+struct Playing { track: Track, src: AudioBufferSourceNode, gain: GainNode }
+
+fn slot(s: Sound) -> usize {
+    match s { Sound::Spawn => 0, Sound::NearMiss => 1, Sound::Pickup => 2, Sound::Hit => 3, Sound::GameOver => 4, Sound::Trap => 7, Sound::Milestone => 8 }
+}
+
+enum Clip { Missing, Fetched(js_sys::ArrayBuffer), Ready(AudioBuffer) }
+
+struct Output { ctx: AudioContext, master: GainNode, music: GainNode, sfx: GainNode, noise: Option<AudioBuffer> }
+
+// worker 版ではメインスレッドで鳴らすので、キー入力とメッセージの両方から触れるよう共有する
+#[derive(Clone)]
+pub(crate) struct Audio {
+    clips: Rc<RefCell<[Clip; SLOTS]>>,
+    out: Rc<RefCell<Option<Output>>>,
+    music: Rc<RefCell<Option<Playing>>>,
+    // 鳴らしたい BGM と音量（unlock やデコードを待っている間も覚えておき、できたら鳴らす）
+    wanted: Rc<Cell<Option<Track>>>,
+    mix: Rc<Cell<Mix>>,
+    synth: bool,
+}
+
+impl Audio {
+    pub fn load(config: &AudioConfig) -> Self {
+        let audio = Self { clips: Rc::new(RefCell::new(std::array::from_fn(|_| Clip::Missing))), out: Rc::default(), music: Rc::default(), wanted: Rc::default(), mix: Rc::new(Cell::new(Mix { master: 1.0, music: 1.0, sfx: 1.0 })), synth: config.synth };
+        let urls = [&config.spawn, &config.near_miss, &config.pickup, &config.hit, &config.game_over, &config.music_title, &config.music_game, &config.trap, &config.milestone];
+        for (i, url) in urls.into_iter().enumerate() {
+            let Some(url) = url.clone() else { continue };
+            let audio = audio.clone();
+            spawn_local(async move {
+                let Ok(buf) = fetch_bytes(&url).await else { return };
+                audio.clips.borrow_mut()[i] = Clip::Fetched(buf);
+                // すでに unlock 済みならすぐデコードする
+                audio.decode(i);
+            });
+        }
+        audio
+    }
+
+    // ユーザー操作の中で呼ぶ。初回に AudioContext を作り、読み込み済みのものをデコードする
+    pub fn unlock(&self) {
+        if let Some(out) = self.out.borrow().as_ref() {
+            let _ = out.ctx.resume();
+            return;
+        }
+        let Ok(ctx) = AudioContext::new() else { return };
+        let (Ok(master), Ok(music), Ok(sfx)) = (ctx.create_gain(), ctx.create_gain(), ctx.create_gain()) else { return };
+        let wired = master.connect_with_audio_node(&ctx.destination()).is_ok()
+            && music.connect_with_audio_node(&master).is_ok()
+            && sfx.connect_with_audio_node(&master).is_ok();
+        if !wired { return; }
+        let noise = if self.synth { synth::noise_buffer(&ctx) } else { None };
+        *self.out.borrow_mut() = Some(Output { ctx, master, music, sfx, noise });
+        self.set_mix(self.mix.get());
+        for i in 0..SLOTS { self.decode(i); }
+        self.retry_music();
+    }
+
+    fn decode(&self, i: usize) {
+        let Some(ctx) = self.out.borrow().as_ref().map(|o| o.ctx.clone()) else { return };
+        let Clip::Fetched(buf) = &self.clips.borrow()[i] else { return };
+        let Ok(promise) = ctx.decode_audio_data(buf) else { return };
+        let audio = self.clone();
+        spawn_local(async move {
+            if let Ok(Ok(decoded)) = JsFuture::from(promise).await.map(|b| b.dyn_into::<AudioBuffer>()) {
+                audio.clips.borrow_mut()[i] = Clip::Ready(decoded);
+                audio.retry_music();
+            }
+        });
+    }
+
+    // 毎フレーム呼んでよい（unlock 前なら覚えておき、unlock したときに入れる）
+    pub fn set_mix(&self, mix: Mix) {
+        self.mix.set(mix);
+        let Some(out) = self.out.borrow().as_ref().map(|o| (o.master.clone(), o.music.clone(), o.sfx.clone())) else { return };
+        for (gain, v) in [(out.0, mix.master), (out.1, mix.music), (out.2, mix.sfx)] {
+            if gain.gain().value() != v as f32 { gain.gain().set_value(v as f32); }
+        }
+    }
+
+    // unlock 前やデコード前は鳴らさない。ファイルが無ければ（synth なら）合成する
+    pub fn play(&self, cue: Cue) {
+        let out = self.out.borrow();
+        let Some(out) = out.as_ref() else { return };
+        let Some(dest) = panned(out, cue.pan) else { return };
+        let buf = match (&self.clips.borrow()[slot(cue.sound)], &out.noise) {
+            (Clip::Ready(buf), _) => buf.clone(),
+            (Clip::Missing, Some(noise)) => return synth::play(&out.ctx, &dest, noise, cue.sound),
+            _ => return,
+        };
+        let Ok(src) = out.ctx.create_buffer_source() else { return };
+        src.set_buffer(Some(&buf));
+        if src.connect_with_audio_node(&dest).is_ok() { let _ = src.start(); }
+    }
+
+    // 毎フレーム呼んでよい。曲が変わったら今の曲をフェードアウトし、次の曲をフェードインする
+    // （次の曲が無い・まだデコードできていなければフェードアウトだけ）
+    pub fn set_music(&self, track: Track) {
+        self.wanted.set(Some(track));
+        let out = self.out.borrow();
+        let Some(out) = out.as_ref() else { return };
+        let mut music = self.music.borrow_mut();
+        if music.as_ref().is_some_and(|p| p.track == track) { return; }
+        let now = out.ctx.current_time();
+        if let Some(old) = music.take() {
+            fade(&old.gain, now, 0.0);
+            let _ = AudioScheduledSourceNode::stop_with_when(&old.src, now + FADE);
+        }
+        let Clip::Ready(buf) = &self.clips.borrow()[track.slot()] else { return };
+        let (Ok(src), Ok(gain)) = (out.ctx.create_buffer_source(), out.ctx.create_gain()) else { return };
+        src.set_buffer(Some(buf));
+        src.set_loop(true);
+        gain.gain().set_value(0.0);
+        if src.connect_with_audio_node(&gain).is_err() || gain.connect_with_audio_node(&out.music).is_err() { return; }
+        fade(&gain, now, 1.0);
+        if src.start().is_ok() { *music = Some(Playing { track, src, gain }); }
+    }
+
+    // unlock の後やデコードが済んだときに、待たせていた BGM を鳴らす
+    fn retry_music(&self) {
+        if let Some(track) = self.wanted.get() { self.set_music(track); }
+    }
+}
+
+// 効果音の行き先。真ん中ならそのまま、左右に振るなら StereoPannerNode を1つ挟む（鳴り終われば捨てられる）
+fn panned(out: &Output, pan: f64) -> Option<AudioNode> {
+    if pan == 0.0 { return Some(out.sfx.clone().into()); }
+    let panner = out.ctx.create_stereo_panner().ok()?;
+    panner.pan().set_value(pan.clamp(-1.0, 1.0) as f32);
+    panner.connect_with_audio_node(&out.sfx).ok()?;
+    Some(panner.into())
+}
+
+// 今の音量から FADE 秒かけて to にする
+fn fade(gain: &GainNode, now: f64, to: f64) {
+    let param = gain.gain();
+    let _ = param.cancel_scheduled_values(now);
+    let _ = param.set_value_at_time(param.value(), now);
+    let _ = param.linear_ramp_to_value_at_time(to as f32, now + FADE);
+}
+
+async fn fetch_bytes(url: &str) -> Result<js_sys::ArrayBuffer, JsValue> {
+    let req = Request::new_with_str(url)?;
+    let resp: Response = JsFuture::from(crate::web::fetch(&req)).await?.dyn_into()?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("{} responded {}", url, resp.status())));
+    }
+    JsFuture::from(resp.array_buffer()?).await?.dyn_into()
+}
@@ -0,0 +1,66 @@
+// 横に伸びる壁（ときどき出る障害物）。すき間を1つ開けたまま下へ流れ、壊せない。
+// 当たっても被弾にはならない：すき間の外にいる自機の上に来たらそこで止まって押さえ、すき間の下まで動けば抜けてまた流れる。
+// 壁が自機の段にある間は、横にもすき間の外へは出られない
+use crate::core::Rect;
+use serde::{Deserialize, Serialize};
+
+// 壁の厚さと落ちる速さ（px/秒）
+pub(crate) const THICK: f64 = 16.0;
+const FALL: f64 = 80.0;
+// すき間の幅（自機の幅の倍数）
+pub(crate) const GAP: f64 = 2.5;
+// ランの始めから最初の1つまでの秒数
+pub(crate) const FIRST: f64 = 20.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Barrier {
+    pub y: f64,
+    // 直前の update() 前の y（描画の補間用）
+    #[serde(skip)]
+    pub prev_y: f64,
+    // すき間の左端と幅
+    pub gap_x: f64,
+    pub gap_w: f64,
+}
+
+impl Barrier {
+    // 画面の上端の外から
+    pub fn new(gap_x: f64, gap_w: f64) -> Self { Self { y: -THICK, prev_y: -THICK, gap_x, gap_w } }
+
+    // 自機の段まで落ちてくるのにかかる秒数
+    pub fn travel(row: f64) -> f64 { (row + THICK) / FALL }
+
+    // どこまで落ちるか。止まる位置（自機の上端）があればそこまで
+    pub fn fall(&mut self, dt: f64, stop: Option<f64>) {
+        self.prev_y = self.y;
+        self.y += FALL * dt;
+        if let Some(top) = stop { self.y = self.y.min(top - THICK); }
+    }
+
+    // r が横方向ですき間に収まっているか
+    pub fn fits(&self, r: &Rect) -> bool { r.x >= self.gap_x && r.x + r.w <= self.gap_x + self.gap_w }
+
+    // r と同じ段にかかっているか
+    pub fn in_row(&self, r: &Rect) -> bool { self.y + THICK > r.y && self.y < r.y + r.h }
+
+    // 段にかかっている自機の x をすき間の中に収める
+    pub fn confine(&self, r: &mut Rect) { r.x = r.x.clamp(self.gap_x, (self.gap_x + self.gap_w - r.w).max(self.gap_x)); }
+
+    // 左右の壁（幅 0 の側もある）
+    pub fn walls(&self, y: f64, width: f64) -> [Rect; 2] {
+        let right = self.gap_x + self.gap_w;
+        [Rect { x: 0.0, y, w: self.gap_x.max(0.0), h: THICK }, Rect { x: right, y, w: (width - right).max(0.0), h: THICK }]
+    }
+
+    pub fn gone(&self, height: f64) -> bool { self.y > height }
+}
+
+// すき間の左端。どの自機（中心 xs）からも、壁が落ちてくるまでに reach の半分以内で届く範囲の中から t（0..1）で選ぶ。
+// 重なる範囲が無ければ（対戦で左右に離れているとき）画面の中ならどこでもよい
+pub(crate) fn place(xs: &[f64], reach: f64, gap_w: f64, width: f64, t: f64) -> f64 {
+    let (min, max) = (gap_w * 0.5, (width - gap_w * 0.5).max(gap_w * 0.5));
+    let lo = xs.iter().map(|x| x - reach * 0.5).fold(min, f64::max);
+    let hi = xs.iter().map(|x| x + reach * 0.5).fold(max, f64::min);
+    let (lo, hi) = if lo <= hi { (lo, hi) } else { (min, max) };
+    lo + (hi - lo) * t - gap_w * 0.5
+}
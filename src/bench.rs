@@ -0,0 +1,91 @@
+// ベンチマーク：隕石を大量に置いた盤面で、更新だけ（描画なし）と描画だけ（更新なし）をそれぞれ回し、
+// 1回あたりの平均時間をコンソールに出す。ページの URL に ?bench（?bench=5000 で隕石の数）を付けるか GameCell::bench() から。
+// 遊んでいるランとは別の Game を使うので、記録や設定には触らない
+use crate::core::{Game, FIXED_DT};
+use crate::rng::XorShift;
+use crate::simconfig::SimConfig;
+use crate::storage::MemoryStorage;
+use crate::web::{js_object, now_ms};
+use wasm_bindgen::JsValue;
+use web_sys::window;
+
+pub(crate) const DEFAULT_METEORS: u32 = 2000;
+const FRAMES: u32 = 300;
+const SEED: u64 = 1;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum BenchKind { Update, Draw, Both }
+
+impl BenchKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s { "update" => Some(BenchKind::Update), "draw" => Some(BenchKind::Draw), "both" => Some(BenchKind::Both), _ => None }
+    }
+    fn update(self) -> bool { self != BenchKind::Draw }
+    fn draw(self) -> bool { self != BenchKind::Update }
+}
+
+pub(crate) struct BenchResult { pub meteors: u32, pub update_ms: Option<f64>, pub draw_ms: Option<f64> }
+
+// ページの URL の ?bench。値が無ければ既定の数
+pub(crate) fn from_query() -> Option<u32> {
+    let search = window()?.location().search().ok()?;
+    search.trim_start_matches('?').split('&').find_map(|kv| {
+        let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+        (k == "bench").then(|| v.parse().unwrap_or(DEFAULT_METEORS))
+    })
+}
+
+// 計測用の盤面（保存はメモリ上、チュートリアルもデモも出さない）
+pub(crate) fn board(config: &SimConfig, width: f64, height: f64, meteors: u32) -> Game {
+    let config = SimConfig { attract: false, ..config.clone() };
+    let mut g = Game::new(width, height, &config, Box::new(MemoryStorage::default()), Box::new(XorShift::new(SEED)));
+    g.fill_meteors(meteors as usize);
+    g
+}
+
+// 固定ステップ1回ずつ。画面外に出た分は計測の外で足し、被弾しても止まらないように生き返らせる
+pub(crate) fn time_updates(g: &mut Game, meteors: u32) -> f64 {
+    let mut total = 0.0;
+    for _ in 0..FRAMES {
+        g.fill_meteors(meteors as usize);
+        g.over = false;
+        for p in &mut g.players { p.alive = true; }
+        let t = now_ms();
+        g.step(FIXED_DT);
+        total += now_ms() - t;
+        g.events.clear();
+    }
+    total / FRAMES as f64
+}
+
+// 同じ盤面を描き続ける。WebGL は命令を積むまでの時間で、GPU の処理は含まない
+pub(crate) fn time_draws(mut draw: impl FnMut()) -> f64 {
+    let t = now_ms();
+    for _ in 0..FRAMES { draw(); }
+    (now_ms() - t) / FRAMES as f64
+}
+
+// コンソールに出し、JS には {meteors, frames, updateMs, drawMs, renderer} で返す
+pub(crate) fn report(r: &BenchResult, renderer: &str) -> JsValue {
+    let ms = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.3} ms", v));
+    web_sys::console::log_1(&format!("bench: {} meteors x {} frames ({}): update {}, draw {}", r.meteors, FRAMES, renderer, ms(r.update_ms), ms(r.draw_ms)).into());
+    js_object(&[
+        ("meteors", r.meteors.into()),
+        ("frames", FRAMES.into()),
+        ("updateMs", r.update_ms.map_or(JsValue::NULL, JsValue::from)),
+        ("drawMs", r.draw_ms.map_or(JsValue::NULL, JsValue::from)),
+        ("renderer", renderer.into()),
+    ])
+}
+
+// kind に応じて回す（描画は呼ぶ側が renderer を持っているので draw で渡してもらう）
+pub(crate) fn run(g: &mut Game, meteors: u32, kind: BenchKind, draw: impl FnMut(&Game)) -> BenchResult {
+    let update_ms = kind.update().then(|| time_updates(g, meteors));
+    let mut draw = draw;
+    let draw_ms = if kind.draw() {
+        g.fill_meteors(meteors as usize);
+        let g = &*g;
+        Some(time_draws(|| draw(g)))
+    } else { None };
+    BenchResult { meteors, update_ms, draw_ms }
+}
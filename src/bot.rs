@@ -0,0 +1,35 @@
+// 自動で避けるボット（デモ用）。自機の高さまで LOOKAHEAD 秒以内に落ちてくる隕石の横幅をふさがった所とみなし、
+// 残りでいちばん広い隙間の真ん中へ向かう。先読みはしないので、隙間へ向かう途中で当たることもある
+use crate::core::{Game, Input, FIXED_DT};
+
+const LOOKAHEAD: f64 = 0.8;
+// 隕石の横に空けておく余裕（px）
+const PAD: f64 = 6.0;
+
+pub(crate) fn steer(g: &Game, i: usize) -> Input {
+    let p = &g.players[i].r;
+    let bottom = p.y + p.h;
+    // 自機の中心が入ると当たる範囲
+    let half = p.w * 0.5 + PAD;
+    let mut blocked: Vec<(f64, f64)> = g.meteors.iter()
+        .filter(|m| m.r.y < bottom && m.r.y + m.r.h + m.vy * LOOKAHEAD > p.y)
+        .map(|m| (m.r.x - half, m.r.x + m.r.w + half))
+        .collect();
+    blocked.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // 中心が動ける範囲のうち、ふさがっていない区間で最も広いもの
+    let (lo, hi) = (p.w * 0.5, g.width - p.w * 0.5);
+    let mut best: Option<(f64, f64)> = None;
+    let mut from = lo;
+    for (a, b) in blocked.into_iter().chain([(hi, hi)]) {
+        let to = a.min(hi);
+        if to > from && best.is_none_or(|(s, e)| to - from > e - s) { best = Some((from, to)); }
+        from = from.max(b);
+    }
+    let Some((s, e)) = best else { return Input::default() };
+    let target = (s + e) * 0.5;
+    let x = p.x + p.w * 0.5;
+    // 1ステップで動ける量より近ければ止まる（行き過ぎて左右に揺れないように）
+    let dead = g.player_config.speed * FIXED_DT;
+    Input { left: x > target + dead, right: x < target - dead }
+}
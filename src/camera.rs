@@ -0,0 +1,51 @@
+// カメラ：揺れと寄り。描画の前に offset() だけ平行移動し、zoom() の点を中心に拡大する（HUD は揺らさない・寄らない）
+// 揺れは衝撃で強さを足し、時間で指数的に減らす。爆弾やボスの攻撃もここに kick() すればよい。
+// 寄りは focus() で目標の倍率に少しずつ近づけ、release() で戻す
+
+// 1秒で残る割合が exp(-DECAY)
+const DECAY: f64 = 6.0;
+// これ以上は揺らさない（px）
+const MAX_SHAKE: f64 = 14.0;
+// 寄る・戻る速さ（1秒あたり）
+const ZOOM_RATE: f64 = 20.0;
+
+pub(crate) struct Camera { shake: f64, t: f64, zoom: f64, target: f64, focus: (f64, f64) }
+
+impl Default for Camera {
+    fn default() -> Self { Self { shake: 0.0, t: 0.0, zoom: 1.0, target: 1.0, focus: (0.0, 0.0) } }
+}
+
+impl Camera {
+    pub fn kick(&mut self, amount: f64) { self.shake = (self.shake + amount).min(MAX_SHAKE); }
+
+    pub fn update(&mut self, dt: f64) {
+        self.t += dt;
+        self.shake *= (-DECAY * dt).exp();
+        if self.shake < 0.1 { self.shake = 0.0; }
+        self.zoom += (self.target - self.zoom) * (ZOOM_RATE * dt).min(1.0);
+        if (self.zoom - self.target).abs() < 1e-3 { self.zoom = self.target; }
+    }
+
+    pub fn reset(&mut self) {
+        self.shake = 0.0;
+        self.zoom = 1.0;
+        self.target = 1.0;
+    }
+
+    // (x, y) を動かさずに zoom 倍まで寄る
+    pub fn focus(&mut self, x: f64, y: f64, zoom: f64) {
+        self.focus = (x, y);
+        self.target = zoom;
+    }
+
+    pub fn release(&mut self) { self.target = 1.0; }
+
+    // (倍率, 中心の x, y)。寄っていなければ倍率 1
+    pub fn zoom(&self) -> (f64, f64, f64) { (self.zoom, self.focus.0, self.focus.1) }
+
+    // 揺れの向きは時間から決める（乱数を使わないのでリプレイに影響しない）
+    pub fn offset(&self) -> (f64, f64) {
+        if self.shake == 0.0 { return (0.0, 0.0); }
+        (self.shake * (self.t * 71.0).sin(), self.shake * (self.t * 53.0 + 1.3).cos())
+    }
+}
@@ -0,0 +1,788 @@
+// Canvas2D での描画（既定）。HUD は WebGL2 版でもオフスクリーンの 2D canvas でこれを使う
+use crate::anim::{Animation, Clip};
+use crate::assets::AssetId;
+use crate::collide::Collider;
+use crate::chart;
+use crate::core::{Orientation, PRACTICE_CHECKPOINTS};
+use crate::grade::Stamp;
+use crate::deathcam::DeathCam;
+use crate::gravity;
+use crate::heatmap;
+use crate::hud::Hud;
+use crate::wind::Phase;
+use crate::i18n::Msg;
+use crate::menu;
+use crate::shop;
+use crate::toast;
+use crate::error::GameError;
+use crate::leaderboard::{Leaderboard, Status};
+use crate::render::{self, Frame, Renderer, MONO, SANS};
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageBitmap, OffscreenCanvas, Path2d};
+
+// 自機の傾きの最大（ラジアン）
+const MAX_TILT: f64 = 0.26;
+
+pub(crate) struct Canvas2dRenderer {
+    ctx: CanvasRenderingContext2d,
+    dpr: f64,
+    // 背景（塗りと星、または背景画像）を描いておく層。作れなければ毎フレーム直接描く
+    background: Option<Layer>,
+    // 隕石の形（render::rock_shape / box_shape の書き込み先。隕石ごとに確保し直さないよう持ち回る）
+    shape: Vec<(f64, f64)>,
+}
+
+// 描いた内容の元（変わったときだけ描き直す）：実ピクセルの大きさ・背景色・星の色・背景画像の有無
+#[derive(PartialEq)]
+struct LayerKey { w: u32, h: u32, background: String, stars: String, image: bool }
+
+struct Layer { canvas: OffscreenCanvas, ctx: CanvasRenderingContext2d, key: LayerKey }
+
+impl Layer {
+    fn new(key: LayerKey) -> Option<Self> {
+        let canvas = OffscreenCanvas::new(key.w, key.h).ok()?;
+        let ctx = canvas.get_context("2d").ok()??.unchecked_into::<CanvasRenderingContext2d>();
+        Some(Self { canvas, ctx, key })
+    }
+}
+
+impl Canvas2dRenderer {
+    pub fn new(canvas: &HtmlCanvasElement, dpr: f64) -> Result<Self, GameError> {
+        let mut r = Self { ctx: context_2d(canvas)?, dpr, background: None, shape: Vec::new() };
+        r.resize(dpr);
+        Ok(r)
+    }
+
+    // worker 内の OffscreenCanvas 用。OffscreenCanvasRenderingContext2D は描画 API が同じなので、そのまま同じ型で扱う
+    pub fn offscreen(canvas: &OffscreenCanvas, dpr: f64) -> Result<Self, GameError> {
+        let ctx = canvas
+            .get_context("2d").map_err(|e| GameError::dom("getContext(\"2d\")", e))?
+            .ok_or(GameError::NoContext2d)?
+            .unchecked_into::<CanvasRenderingContext2d>();
+        let mut r = Self { ctx, dpr, background: None, shape: Vec::new() };
+        r.resize(dpr);
+        Ok(r)
+    }
+
+    // 背景は層に描いておき、毎フレームはそれを1回貼るだけにする（大きさ・配色・背景画像が変わったら描き直す）
+    fn draw_background(&mut self, f: &Frame) {
+        let g = f.game;
+        let key = LayerKey {
+            w: (g.width * self.dpr).round() as u32,
+            h: (g.height * self.dpr).round() as u32,
+            background: f.theme.background.clone(),
+            stars: f.theme.stars.clone(),
+            image: f.assets.get(AssetId::Background).is_some(),
+        };
+        if self.background.as_ref().is_none_or(|l| l.key != key) {
+            self.background = Layer::new(key);
+            if let Some(l) = &self.background {
+                let _ = l.ctx.set_transform(self.dpr, 0.0, 0.0, self.dpr, 0.0, 0.0);
+                paint_background(&l.ctx, f);
+            }
+        }
+        match &self.background {
+            Some(l) => { let _ = self.ctx.draw_image_with_offscreen_canvas_and_dw_and_dh(&l.canvas, 0.0, 0.0, g.width, g.height); }
+            None => paint_background(&self.ctx, f),
+        }
+    }
+
+    // 背景・プレイヤー・隕石・天候
+    fn draw_world(&mut self, f: &Frame) {
+        self.draw_background(f);
+        let g = f.game;
+        let c = &self.ctx;
+        let colors = f.theme;
+
+        // 節目の光
+        let pulse = render::milestone_pulse(g);
+        if pulse > 0.0 {
+            c.set_global_alpha(pulse);
+            c.set_fill_style_str(&colors.accent);
+            c.fill_rect(0.0, 0.0, g.width, g.height);
+            c.set_global_alpha(1.0);
+        }
+
+        // ここから先はカメラの揺れの分だけずらす
+        let (dx, dy) = g.camera.offset();
+        c.save();
+        let _ = c.translate(dx, dy);
+        let (zoom, fx, fy) = g.camera.zoom();
+        if zoom != 1.0 {
+            let _ = c.translate(fx, fy);
+            let _ = c.scale(zoom, zoom);
+            let _ = c.translate(-fx, -fy);
+        }
+
+        // プレイヤー（明るい色で目立つように）。画像があれば全員それで描く
+        let atlas = f.assets.get(AssetId::Atlas);
+        let animations = &f.config.assets.animations;
+        let ship = Sprite { atlas: atlas.as_ref(), clip: animations.player.as_ref(), still: f.assets.get(AssetId::Player) };
+        for (i, p) in g.players.iter().enumerate().filter(|(_, p)| p.alive) {
+            let x = f.lerp(p.prev_x, p.r.x);
+            // 動く向きに傾ける（中心で回す。当たり判定は傾けない）
+            c.save();
+            if !g.settings.reduced_motion {
+                let _ = c.translate(x + p.r.w * 0.5, p.r.y + p.r.h * 0.5);
+                let _ = c.rotate(p.tilt * MAX_TILT);
+                let _ = c.translate(-(x + p.r.w * 0.5), -(p.r.y + p.r.h * 0.5));
+            }
+            if !ship.draw(c, &p.anim, x, p.r.y, p.r.w, p.r.h) {
+                c.set_fill_style_str(f.player_color(i));
+                // プレイヤーの輪郭を追加（より見やすくするため）
+                c.set_stroke_style_str(&f.theme.text);
+                c.set_line_width(f.line_width());
+                if f.shapes() {
+                    path(c, &render::ship_shape(i, x, p.r.y, p.r.w, p.r.h));
+                    c.fill();
+                    c.stroke();
+                } else {
+                    c.fill_rect(x, p.r.y, p.r.w, p.r.h);
+                    c.stroke_rect(x, p.r.y, p.r.w, p.r.h);
+                }
+            }
+            c.restore();
+        }
+        let player = &g.players[0].r;
+
+        // オンラインの他プレイヤー（半透明の枠。名前は HUD 側）
+        if let Some(net) = f.net {
+            for r in net.remotes().values() {
+                c.set_global_alpha(if r.alive { 0.5 } else { 0.15 });
+                c.set_stroke_style_str(&colors.remote);
+                c.stroke_rect(r.x * g.width, player.y, player.w, player.h);
+            }
+            c.set_global_alpha(1.0);
+        }
+
+        // ゴースト（ベストランの同じ経過時間の位置）
+        if let (true, false, false, Some(ghost)) = (g.settings.show_ghost, g.over, g.versus, &g.best_ghost) {
+            if let Some(gx) = ghost.position_at(g.stats.elapsed) {
+                c.set_global_alpha(0.3);
+                c.set_fill_style_str(&colors.ghost);
+                c.fill_rect(gx * g.width, player.y, player.w, player.h);
+                c.set_global_alpha(1.0);
+            }
+        }
+
+        // レーザー（画面の高さいっぱい）
+        for l in &g.lasers {
+            for (x, w, color, a) in render::laser_bands(f, l) {
+                c.set_global_alpha(a);
+                c.set_fill_style_str(color);
+                c.fill_rect(x, 0.0, w, g.height);
+            }
+        }
+        c.set_global_alpha(1.0);
+
+        // 拾いもの
+        for p in &g.pickups {
+            for (pts, color) in render::pickup_shape(f, p) {
+                path(c, &pts);
+                c.set_fill_style_str(color);
+                c.fill();
+            }
+        }
+
+        // すき間の開いた壁（縁は上下に明るい線）
+        for b in &g.barriers {
+            for r in render::barrier_walls(f, b) {
+                if r.w <= 0.0 { continue; }
+                c.set_fill_style_str(render::BARRIER);
+                c.fill_rect(r.x, r.y, r.w, r.h);
+                c.set_fill_style_str(render::BARRIER_EDGE);
+                c.fill_rect(r.x, r.y, r.w, 2.0);
+                c.fill_rect(r.x, r.y + r.h - 2.0, r.w, 2.0);
+            }
+        }
+
+        // ブラックホール（隕石より下に。渦の点を描いてから黒い中心を重ねる）
+        for w in &g.wells {
+            c.set_fill_style_str(render::WELL_GLOW);
+            for (x, y, s, a) in render::well_swirl(f, w) {
+                c.set_global_alpha(a);
+                c.fill_rect(x - s * 0.5, y - s * 0.5, s, s);
+            }
+            c.set_global_alpha(1.0);
+            c.begin_path();
+            let _ = c.arc(w.x, f.lerp(w.prev_y, w.y), gravity::CORE, 0.0, std::f64::consts::TAU);
+            c.set_fill_style_str(render::WELL_CORE);
+            c.fill();
+            c.set_stroke_style_str(render::WELL_GLOW);
+            c.set_line_width(f.line_width());
+            c.stroke();
+        }
+
+        // 速い隕石の残像（本体より先に描いて下に敷く）
+        c.set_fill_style_str(&colors.meteor);
+        for (x, y, w, h, a) in render::meteor_trails(f) {
+            c.set_global_alpha(a);
+            c.fill_rect(x, y, w, h);
+        }
+        c.set_global_alpha(1.0);
+
+        // 隕石
+        let rock = Sprite { atlas: atlas.as_ref(), clip: animations.meteor.as_ref(), still: f.assets.get(AssetId::Meteor) };
+        c.set_fill_style_str(&colors.meteor);
+        // 高コントラストでは隕石も明るい輪郭で囲む
+        let high_contrast = g.settings.high_contrast;
+        c.set_stroke_style_str(&colors.text);
+        c.set_line_width(f.line_width());
+        // 画像で描けなかった隕石は、回した形を1つの Path2D に集めて最後にまとめて塗る
+        let batch = Path2d::new().ok();
+        for m in &g.meteors {
+            let y = f.lerp(m.prev_y, m.r.y);
+            let (cx, cy) = (m.r.x + m.r.w * 0.5, y + m.r.h * 0.5);
+            if rock.has_image() {
+                // 中心のまわりに回す
+                c.save();
+                let _ = c.translate(cx, cy);
+                let _ = c.rotate(m.angle);
+                let _ = c.translate(-cx, -cy);
+                let drawn = rock.draw(c, &m.anim, m.r.x, y, m.r.w, m.r.h);
+                c.restore();
+                if drawn { continue; }
+            }
+            let Some(batch) = &batch else { continue };
+            if f.shapes() { render::rock_shape(&mut self.shape, m.id, m.r.x, y, m.r.w, m.r.h) } else { render::box_shape(&mut self.shape, m.r.x, y, m.r.w, m.r.h) }
+            render::rotate(&mut self.shape, cx, cy, m.angle);
+            add_polygon(batch, &self.shape);
+        }
+        if let Some(batch) = &batch {
+            c.fill_with_path_2d(batch);
+            if high_contrast { c.stroke_with_path(batch); }
+        }
+
+        // パーティクル（寿命に合わせて薄く）
+        for p in g.particles.iter() {
+            c.set_global_alpha(p.alpha());
+            c.set_fill_style_str(p.kind.color());
+            c.fill_rect(p.x - p.size * 0.5, p.y - p.size * 0.5, p.size, p.size);
+        }
+        c.set_global_alpha(1.0);
+        c.set_line_width(1.0);
+
+        // デバッグ：当たり判定（隕石は近接回避の感知範囲も）
+        if f.config.debug {
+            c.set_stroke_style_str(render::DEBUG_SENSE);
+            for m in &g.meteors {
+                let s = m.sense_rect();
+                c.stroke_rect(s.x, s.y, s.w, s.h);
+            }
+            c.set_stroke_style_str(render::DEBUG_HITBOX);
+            for h in g.meteors.iter().map(|m| m.hitbox()) {
+                match h {
+                    Collider::Circle(h) => {
+                        c.begin_path();
+                        let _ = c.arc(h.x, h.y, h.r, 0.0, std::f64::consts::TAU);
+                    }
+                    Collider::Obb(b) => path(c, &b.corners()),
+                }
+                c.stroke();
+            }
+            for r in g.players.iter().filter(|p| p.alive).map(|p| p.hitbox(g.hitbox_shrink())) {
+                c.stroke_rect(r.x, r.y, r.w, r.h);
+            }
+        }
+
+        c.restore();
+
+        // 天候：砂嵐は上から視界を遮り、イオンストームは画面のノイズで予告
+        for (y, h, a) in render::fog_bands(g) {
+            c.set_fill_style_str(&format!("rgba(120,92,60,{:.3})", a));
+            c.fill_rect(0.0, y, g.width, h);
+        }
+        // 突風：風下へ流れる筋
+        c.set_fill_style_str(render::STREAK_COLOR);
+        for (x, y, w, a) in render::wind_streaks(g) {
+            c.set_global_alpha(a);
+            c.fill_rect(x, y, w, 1.0);
+        }
+        c.set_global_alpha(1.0);
+        // 罠の霧
+        if let Some((rects, a)) = render::trap_fog(f) {
+            c.set_global_alpha(a);
+            c.set_fill_style_str(render::FOG_COLOR);
+            for r in rects { c.fill_rect(r.x, r.y, r.w, r.h); }
+            c.set_global_alpha(1.0);
+        }
+        let noise = g.weather.static_level();
+        if noise > 0.0 {
+            c.set_fill_style_str(&format!("rgba(200,220,255,{:.3})", 0.35 * noise));
+            for (x, y, w) in render::static_noise(g) { c.fill_rect(x, y, w, 1.0); }
+        }
+    }
+
+    // 文字まわりとオーバーレイ。背景は描かないので、透明な canvas に描けば重ねて使える
+    pub fn draw_hud(&self, f: &Frame) {
+        // フォントが届く前にシステムのフォントで描くと、届いたときに文字が入れ替わって見えるので待つ
+        if !f.assets.text_ready() { return; }
+        let g = f.game;
+        let c = &self.ctx;
+        let player = &g.players[0].r;
+
+        // オンラインの他プレイヤーの名前
+        if let Some(net) = f.net {
+            c.set_font(&f.font(false, 10.0, MONO));
+            c.set_fill_style_str(&f.theme.remote);
+            for r in net.remotes().values() {
+                c.set_global_alpha(if r.alive { 0.5 } else { 0.15 });
+                let _ = c.fill_text(&format!("{} {:04}", r.name, r.score as i32), r.x * g.width, player.y - 4.0);
+            }
+            c.set_global_alpha(1.0);
+        }
+
+        // ポップアップ
+        c.set_fill_style_str(&f.theme.accent);
+        c.set_font(&f.font(true, 14.0, SANS));
+        for p in &g.popups {
+            c.set_global_alpha(p.ttl.min(1.0));
+            let _ = c.fill_text(p.text, p.x, p.y);
+        }
+        c.set_global_alpha(1.0);
+
+        // スコアの節目（大きく出て縮み、消えていく）
+        if let Some(m) = &g.celebration {
+            let scale = if g.settings.reduced_motion { 1.0 } else { m.scale() };
+            c.set_global_alpha(m.alpha());
+            c.set_fill_style_str(&f.theme.accent);
+            c.set_font(&f.font(true, (32.0 * scale).round(), SANS));
+            c.set_text_align("center");
+            let _ = c.fill_text(&format!("{}!", m.score), g.width * 0.5, g.height * 0.35);
+            c.set_text_align("start");
+            c.set_global_alpha(1.0);
+        }
+
+        Hud::new(c, f, self.dpr).draw();
+
+        // トースト（右下に積む。出し方は toast.rs）
+        c.set_font(&f.font(false, 13.0, MONO));
+        c.set_text_align("center");
+        for (t, x, y, w, a) in g.toasts.layout(g.width, g.height, !g.settings.reduced_motion) {
+            // 高コントラストでは半透明にしない
+            if !g.settings.high_contrast { c.set_global_alpha(a); }
+            c.set_fill_style_str(&f.theme.toast);
+            c.fill_rect(x, y, w, toast::H);
+            c.set_fill_style_str(&f.theme.accent);
+            let _ = c.fill_text_with_max_width(&t.text, x + w * 0.5, y + toast::H * 0.5 + 5.0, w - 8.0);
+        }
+        c.set_text_align("start");
+        c.set_global_alpha(1.0);
+
+        // 突風の警告（画面の上の帯。予告のあいだは点滅させる）
+        if let Some(dir) = g.wind.banner() {
+            let blink = g.wind.phase == Phase::Warn && !g.settings.reduced_motion && (g.wind.t * 4.0) as i32 % 2 == 1;
+            if !blink {
+                c.set_fill_style_str(&f.theme.toast);
+                c.fill_rect(g.width * 0.5 - 90.0, 40.0, 180.0, 24.0);
+                c.set_fill_style_str(&f.theme.accent);
+                c.set_font(&f.font(true, 14.0, MONO));
+                c.set_text_align("center");
+                let arrow = if dir < 0.0 { "\u{2190}\u{2190}" } else { "\u{2192}\u{2192}" };
+                let _ = c.fill_text(&g.lang.fill(Msg::Gust, &[&arrow]), g.width * 0.5, 57.0);
+                c.set_text_align("start");
+            }
+        }
+
+        // チュートリアルの課題（画面の上の方に大きく）
+        if let Some(t) = &g.tutorial {
+            c.set_fill_style_str(&f.theme.text);
+            c.set_font(&f.font(true, 18.0, SANS));
+            c.set_text_align("center");
+            let _ = c.fill_text(g.lang.text(t.lesson.prompt()), g.width * 0.5, g.height * 0.3);
+            c.set_font(&f.font(false, 12.0, MONO));
+            c.set_fill_style_str(&f.theme.hud);
+            let _ = c.fill_text(g.lang.text(Msg::Tutorial), g.width * 0.5, g.height * 0.3 - 28.0);
+            c.set_text_align("start");
+        }
+
+        if f.rotate {
+            self.draw_rotate_prompt(f);
+        } else if let Some(d) = g.deathcam.as_ref().filter(|_| g.over) {
+            self.draw_deathcam(f, d);
+        } else if g.over && g.versus {
+            self.draw_versus_results(f);
+        } else if g.over && g.shop.open {
+            self.draw_shop(f);
+        } else if g.over && g.heatmap.open {
+            self.draw_heatmap(f);
+        } else if g.over {
+            c.set_fill_style_str(&f.theme.overlay);
+            c.fill_rect(0.0, 0.0, g.width, g.height);
+            c.set_fill_style_str(&f.theme.text);
+            // 見出しは言語で幅が変わるので中央ぞろえ
+            c.set_font(&f.font(true, 28.0, SANS));
+            c.set_text_align("center");
+            let _ = c.fill_text(g.lang.text(Msg::GameOver), g.width*0.5, g.height*0.5 - 8.0);
+            if g.new_record {
+                c.set_fill_style_str(&f.theme.accent);
+                c.set_font(&f.font(true, 18.0, SANS));
+                let _ = c.fill_text(g.lang.text(Msg::NewRecord), g.width*0.5, g.height*0.5 - 44.0);
+                c.set_fill_style_str(&f.theme.text);
+            }
+            // アシストを使ったランはそうとわかるように
+            if g.assisted() {
+                c.set_fill_style_str(&f.theme.accent);
+                c.set_font(&f.font(false, 14.0, MONO));
+                let text = if g.game_speed < 1.0 { g.lang.fill(Msg::AssistSpeed, &[&((g.game_speed * 100.0).round() as i32)]) } else { g.lang.text(Msg::Assist).to_string() };
+                let _ = c.fill_text(&text, g.width*0.5, g.height*0.5 - 70.0);
+                c.set_fill_style_str(&f.theme.text);
+            }
+            c.set_text_align("start");
+            c.set_font(&f.font(false, 16.0, MONO));
+            let _ = c.fill_text(g.lang.text(Msg::Retry), g.width*0.5 - 85.0, g.height*0.5 + 20.0);
+            let hint = if g.daily.is_some() { Msg::ToEndless } else { Msg::ToDaily };
+            let _ = c.fill_text(g.lang.text(hint), g.width*0.5 - 85.0, g.height*0.5 + 42.0);
+            let _ = c.fill_text(g.lang.text(Msg::ToVersus), g.width*0.5 - 85.0, g.height*0.5 + 64.0);
+            let _ = c.fill_text(g.lang.text(Msg::ToTutorial), g.width*0.5 - 85.0, g.height*0.5 + 86.0);
+            let practice = match PRACTICE_CHECKPOINTS.iter().find(|&&c| g.practice.is_none_or(|p| c > p)) {
+                Some(c) => g.lang.fill(Msg::PracticeFrom, &[c]),
+                None => g.lang.text(Msg::PracticeEnd).to_string(),
+            };
+            let _ = c.fill_text(&practice, g.width*0.5 - 85.0, g.height*0.5 + 108.0);
+            let _ = c.fill_text(&g.lang.fill(Msg::ToShop, &[&g.shop.coins]), g.width*0.5 - 85.0, g.height*0.5 + 130.0);
+            let _ = c.fill_text(g.lang.text(Msg::ToHeatmap), g.width*0.5 - 85.0, g.height*0.5 + 152.0);
+            if let Some(s) = &g.grade { self.draw_grade(f, s); }
+            // ランの推移（横向きは左下、縦長はランキングと重ならないよう右下）
+            let cw = 220.0_f64.min(g.width * 0.45);
+            let cx = if g.orientation == Orientation::Landscape { 10.0 } else { g.width - cw - 10.0 };
+            chart::draw(c, f, &g.stats.samples, cx, g.height - 100.0, cw, 80.0);
+            if let Some(lb) = f.leaderboard { self.draw_leaderboard(f, lb); }
+        } else if g.paused {
+            self.draw_menu(f);
+        } else {
+            // 一時停止ボタン（タップ用。右下）
+            let (x, y, w, h) = menu::pause_button(g);
+            c.set_stroke_style_str(&f.theme.hud);
+            c.set_fill_style_str(&f.theme.hud);
+            c.set_line_width(f.line_width());
+            c.stroke_rect(x, y, w, h);
+            c.fill_rect(x + w * 0.3, y + h * 0.25, w * 0.12, h * 0.5);
+            c.fill_rect(x + w * 0.58, y + h * 0.25, w * 0.12, h * 0.5);
+        }
+    }
+
+    // 落ちる直前の再生。止まった画面を背景で覆い、残した場面の自機と隕石を描く（当てた隕石は赤く囲む）
+    fn draw_deathcam(&self, f: &Frame, d: &DeathCam) {
+        let g = f.game;
+        let c = &self.ctx;
+        let shot = d.shot();
+        c.set_fill_style_str(&f.theme.background);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        c.set_fill_style_str(&f.theme.meteor);
+        let mut shape = Vec::new();
+        for &(id, r) in &shot.meteors {
+            render::rock_shape(&mut shape, id, r.x, r.y, r.w, r.h);
+            path(c, &shape);
+            c.fill();
+        }
+        if let Some(&(id, r)) = shot.meteors.iter().find(|(id, _)| Some(*id) == d.fatal) {
+            c.set_stroke_style_str(render::FATAL_COLOR);
+            c.set_line_width(3.0);
+            render::rock_shape(&mut shape, id, r.x, r.y, r.w, r.h);
+            path(c, &shape);
+            c.stroke();
+            c.begin_path();
+            let _ = c.arc(r.x + r.w * 0.5, r.y + r.h * 0.5, r.w.max(r.h) * 0.5 + 10.0, 0.0, std::f64::consts::TAU);
+            c.stroke();
+        }
+        let p = shot.player;
+        c.set_fill_style_str(f.player_color(0));
+        c.fill_rect(p.x, p.y, p.w, p.h);
+        c.set_fill_style_str(&f.theme.accent);
+        c.set_font(&f.font(false, 14.0, MONO));
+        c.set_text_align("center");
+        let _ = c.fill_text(g.lang.text(Msg::LastMoments), g.width * 0.5, g.height * 0.2);
+        c.set_text_align("start");
+    }
+
+    // 落ちた位置の分布。画面の横幅を列に分け、多い列ほど赤く濃く塗る（下端に色の凡例）
+    fn draw_heatmap(&self, f: &Frame) {
+        let g = f.game;
+        let c = &self.ctx;
+        let h = &g.heatmap;
+        c.set_fill_style_str(&f.theme.overlay);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        let w = g.width / heatmap::BINS as f64;
+        let total = h.total();
+        if total > 0 {
+            for i in 0..heatmap::BINS {
+                let level = h.level(i);
+                if level <= 0.0 { continue; }
+                c.set_global_alpha(0.15 + 0.6 * level);
+                c.set_fill_style_str(&render::heat_color(level));
+                c.fill_rect(i as f64 * w, 0.0, w, g.height);
+            }
+            c.set_global_alpha(1.0);
+        }
+        // 凡例：下端に色の帯
+        for i in 0..heatmap::BINS {
+            c.set_fill_style_str(&render::heat_color(i as f64 / (heatmap::BINS - 1) as f64));
+            c.fill_rect(g.width * 0.5 - 60.0 + i as f64 * 5.0, g.height - 24.0, 5.0, 6.0);
+        }
+        c.set_fill_style_str(&f.theme.text);
+        c.set_font(&f.font(true, 28.0, SANS));
+        c.set_text_align("center");
+        let _ = c.fill_text(g.lang.text(Msg::Heatmap), g.width * 0.5, g.height * 0.3);
+        c.set_font(&f.font(false, 14.0, MONO));
+        let hint = if total == 0 { g.lang.text(Msg::HeatmapEmpty).to_string() } else { g.lang.fill(Msg::HeatmapHint, &[&total]) };
+        let _ = c.fill_text(&hint, g.width * 0.5, g.height * 0.3 + 28.0);
+        c.set_text_align("start");
+    }
+
+    // 評価のハンコ（見出しの右。大きく傾いて出て押される）と、モードのベスト
+    fn draw_grade(&self, f: &Frame, s: &Stamp) {
+        let g = f.game;
+        let c = &self.ctx;
+        let (x, y) = (g.width * 0.5 + 130.0_f64.min(g.width * 0.3), g.height * 0.5 - 20.0);
+        let motion = !g.settings.reduced_motion;
+        c.save();
+        let _ = c.translate(x, y);
+        if motion {
+            let _ = c.rotate(s.tilt());
+            let _ = c.scale(s.scale(), s.scale());
+            c.set_global_alpha(s.alpha());
+        }
+        c.set_stroke_style_str(s.grade.color());
+        c.set_fill_style_str(s.grade.color());
+        c.set_line_width(3.0);
+        c.begin_path();
+        let _ = c.arc(0.0, 0.0, 26.0, 0.0, std::f64::consts::TAU);
+        c.stroke();
+        c.set_font(&f.font(true, 34.0, SANS));
+        c.set_text_align("center");
+        let _ = c.fill_text(s.grade.letter(), 0.0, 12.0);
+        c.restore();
+        c.set_font(&f.font(false, 12.0, MONO));
+        c.set_text_align("center");
+        c.set_fill_style_str(if s.new_best { &f.theme.accent } else { &f.theme.hud });
+        let text = match s.best {
+            _ if s.new_best => g.lang.text(Msg::GradeNewBest).to_string(),
+            Some(b) => g.lang.fill(Msg::GradeBest, &[&b.letter()]),
+            None => String::new(),
+        };
+        let _ = c.fill_text(&text, x, y + 44.0);
+        c.set_text_align("start");
+    }
+
+    // 一時停止中の設定メニュー。選んでいる行は強調色で囲む
+    fn draw_menu(&self, f: &Frame) {
+        let g = f.game;
+        let c = &self.ctx;
+        c.set_fill_style_str(&f.theme.overlay);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        let (_, top, _, _) = menu::row(g, 0);
+        let (_, last, _, row_h) = menu::row(g, menu::ITEMS.len() - 1);
+        c.set_fill_style_str(&f.theme.text);
+        c.set_font(&f.font(true, 28.0, SANS));
+        c.set_text_align("center");
+        let _ = c.fill_text(g.lang.text(Msg::Paused), g.width * 0.5, top - 24.0);
+        c.set_text_align("start");
+        c.set_font(&f.font(false, 16.0, MONO));
+        for (i, item) in menu::ITEMS.iter().enumerate() {
+            let (x, y, w, h) = menu::row(g, i);
+            let selected = i == g.menu.sel;
+            if selected {
+                c.set_stroke_style_str(&f.theme.accent);
+                c.set_line_width(f.line_width());
+                c.stroke_rect(x, y + 2.0, w, h - 4.0);
+            }
+            c.set_fill_style_str(if selected { &f.theme.accent } else { &f.theme.text });
+            let _ = c.fill_text(g.lang.text(item.label()), x + 10.0, y + h * 0.5 + 5.0);
+            c.set_text_align("right");
+            let _ = c.fill_text(&item.value(g), x + w - 10.0, y + h * 0.5 + 5.0);
+            c.set_text_align("start");
+        }
+        c.set_font(&f.font(false, 12.0, MONO));
+        c.set_fill_style_str(&f.theme.hud);
+        c.set_text_align("center");
+        let _ = c.fill_text(g.lang.text(Msg::MenuHint), g.width * 0.5, last + row_h + 20.0);
+        c.set_text_align("start");
+    }
+
+    // ゲームオーバー画面の店。メニューと同じ並びで、最後の行は閉じる
+    fn draw_shop(&self, f: &Frame) {
+        let g = f.game;
+        let c = &self.ctx;
+        c.set_fill_style_str(&f.theme.overlay);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        let n = shop::UPGRADES.len() + 1;
+        let (_, top, _, _) = shop::row(g, 0);
+        let (_, last, _, row_h) = shop::row(g, n - 1);
+        c.set_fill_style_str(&f.theme.text);
+        c.set_font(&f.font(true, 28.0, SANS));
+        c.set_text_align("center");
+        let _ = c.fill_text(g.lang.text(Msg::Shop), g.width * 0.5, top - 24.0);
+        c.set_text_align("start");
+        c.set_font(&f.font(false, 16.0, MONO));
+        for i in 0..n {
+            let (x, y, w, h) = shop::row(g, i);
+            let selected = i == g.shop.sel;
+            if selected {
+                c.set_stroke_style_str(&f.theme.accent);
+                c.set_line_width(f.line_width());
+                c.stroke_rect(x, y + 2.0, w, h - 4.0);
+            }
+            c.set_fill_style_str(if selected { &f.theme.accent } else { &f.theme.text });
+            let label = shop::UPGRADES.get(i).map_or(Msg::ShopClose, |u| u.label());
+            let _ = c.fill_text(g.lang.text(label), x + 10.0, y + h * 0.5 + 5.0);
+            c.set_text_align("right");
+            let _ = c.fill_text(&shop::value(g, i), x + w - 10.0, y + h * 0.5 + 5.0);
+            c.set_text_align("start");
+        }
+        c.set_font(&f.font(false, 12.0, MONO));
+        c.set_fill_style_str(&f.theme.hud);
+        c.set_text_align("center");
+        let _ = c.fill_text(&g.lang.fill(Msg::ShopHint, &[&g.shop.coins]), g.width * 0.5, last + row_h + 20.0);
+        c.set_text_align("start");
+    }
+
+    // 前のフレームの HUD を消す（オフスクリーンに重ねて描くとき用）
+    pub fn clear(&self, w: f64, h: f64) { self.ctx.clear_rect(0.0, 0.0, w, h); }
+
+    // 「端末を回転してください」
+    fn draw_rotate_prompt(&self, f: &Frame) {
+        let g = f.game;
+        let c = &self.ctx;
+        c.set_fill_style_str(&f.theme.background);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        c.set_fill_style_str(&f.theme.text);
+        c.set_text_align("center");
+        c.set_font(&f.font(true, 20.0, SANS));
+        let want = g.lang.text(if g.orientation == Orientation::Portrait { Msg::Landscape } else { Msg::Portrait });
+        let _ = c.fill_text(g.lang.text(Msg::Rotate), g.width * 0.5, g.height * 0.5 - 8.0);
+        c.set_font(&f.font(false, 14.0, MONO));
+        let _ = c.fill_text(&g.lang.fill(Msg::PlaysIn, &[&want]), g.width * 0.5, g.height * 0.5 + 18.0);
+        c.set_text_align("start");
+    }
+
+    // ゲームオーバー画面にランキング（上位10件。横向きは右側、縦長は下）
+    fn draw_leaderboard(&self, f: &Frame, lb: &Leaderboard) {
+        let g = f.game;
+        let c = &self.ctx;
+        let (x, y) = match g.orientation {
+            Orientation::Landscape => (g.width - 170.0, 50.0),
+            Orientation::Portrait => (10.0, g.height * 0.5 + 90.0),
+        };
+        c.set_font(&f.font(false, 12.0, MONO));
+        c.set_fill_style_str(&f.theme.hud);
+        let _ = c.fill_text(g.lang.text(Msg::Top10), x, y);
+        match &*lb.status() {
+            Status::Idle => {}
+            Status::Sending => { let _ = c.fill_text(g.lang.text(Msg::Sending), x, y + 18.0); }
+            Status::Failed => { let _ = c.fill_text(g.lang.text(Msg::LeaderboardDown), x, y + 18.0); }
+            Status::Done(top) => {
+                for (i, e) in top.iter().enumerate() {
+                    let name: String = e.name.chars().take(10).collect();
+                    let _ = c.fill_text(&format!("{:>2}. {:<10} {:>5}", i + 1, name, e.score), x, y + 18.0 + i as f64 * 16.0);
+                }
+            }
+        }
+    }
+
+    // 対戦の結果画面：生き残った方が勝ち（同時に被弾したら引き分け）
+    fn draw_versus_results(&self, f: &Frame) {
+        let g = f.game;
+        let c = &self.ctx;
+        c.set_fill_style_str(&f.theme.overlay);
+        c.fill_rect(0.0, 0.0, g.width, g.height);
+        c.set_text_align("center");
+        let cx = g.width * 0.5;
+        let cy = g.height * 0.5;
+        let (title, color) = match g.players.iter().position(|p| p.alive) {
+            Some(i) => (g.lang.fill(Msg::PlayerWins, &[&(i + 1)]), f.player_color(i)),
+            None => (g.lang.text(Msg::Draw).to_string(), f.theme.text.as_str()),
+        };
+        c.set_fill_style_str(color);
+        c.set_font(&f.font(true, 28.0, SANS));
+        let _ = c.fill_text(&title, cx, cy - 30.0);
+        c.set_font(&f.font(false, 16.0, MONO));
+        for (i, p) in g.players.iter().enumerate() {
+            c.set_fill_style_str(f.player_color(i));
+            let _ = c.fill_text(&format!("P{}: {:04}", i + 1, p.score as i32), cx + (i as f64 - 0.5) * 160.0, cy);
+        }
+        c.set_fill_style_str(&f.theme.text);
+        let _ = c.fill_text(g.lang.text(Msg::Rematch), cx, cy + 30.0);
+        let _ = c.fill_text(g.lang.text(Msg::SoloPlay), cx, cy + 52.0);
+        c.set_text_align("start");
+    }
+}
+
+// 描く画像の候補：アトラスのコマ送り > 単体の画像 > なし（呼び出し側が矩形で描く）
+struct Sprite<'a> { atlas: Option<&'a ImageBitmap>, clip: Option<&'a Clip>, still: Option<ImageBitmap> }
+
+impl Sprite<'_> {
+    fn has_image(&self) -> bool { (self.atlas.is_some() && self.clip.is_some()) || self.still.is_some() }
+
+    fn draw(&self, c: &CanvasRenderingContext2d, anim: &Animation, x: f64, y: f64, w: f64, h: f64) -> bool {
+        if let (Some(atlas), Some([sx, sy, sw, sh])) = (self.atlas, self.clip.and_then(|clip| anim.source(clip))) {
+            let _ = c.draw_image_with_image_bitmap_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(atlas, sx, sy, sw, sh, x, y, w, h);
+            return true;
+        }
+        let Some(img) = &self.still else { return false };
+        let _ = c.draw_image_with_image_bitmap_and_dw_and_dh(img, x, y, w, h);
+        true
+    }
+}
+
+impl Renderer for Canvas2dRenderer {
+    fn name(&self) -> &'static str { "canvas2d" }
+
+    fn draw(&mut self, f: &Frame) {
+        self.draw_world(f);
+        self.draw_hud(f);
+    }
+
+    fn resize(&mut self, dpr: f64) {
+        self.dpr = dpr;
+        let _ = self.ctx.set_transform(dpr, 0.0, 0.0, dpr, 0.0, 0.0);
+        self.ctx.set_line_width(1.0);
+    }
+
+    // 状態はすべて初期化されているので、変換行列を入れ直し、背景の層は作り直す
+    fn restore(&mut self) {
+        self.background = None;
+        self.resize(self.dpr);
+    }
+}
+
+// 背景（画像か、塗りと星）を描く。背景の層に焼くときも、層を使わないときもこれ
+fn paint_background(c: &CanvasRenderingContext2d, f: &Frame) {
+    let g = f.game;
+    // 背景画像があれば星の代わりに敷く
+    if let Some(bg) = f.assets.get(AssetId::Background) {
+        let _ = c.draw_image_with_image_bitmap_and_dw_and_dh(&bg, 0.0, 0.0, g.width, g.height);
+        return;
+    }
+    c.set_fill_style_str(&f.theme.background);
+    c.fill_rect(0.0, 0.0, g.width, g.height);
+    // 星（1つの Path2D にまとめて1回で塗る）
+    c.set_fill_style_str(&f.theme.stars);
+    if let Ok(batch) = Path2d::new() {
+        for (x, y) in render::stars(g.width, g.height) { batch.rect(x, y, 1.0, 1.0); }
+        c.fill_with_path_2d(&batch);
+    }
+}
+
+// 多角形のパスを作る（塗り・線は呼び出し側）
+fn add_polygon(p: &Path2d, pts: &[(f64, f64)]) {
+    for (i, &(x, y)) in pts.iter().enumerate() {
+        if i == 0 { p.move_to(x, y); } else { p.line_to(x, y); }
+    }
+    p.close_path();
+}
+
+fn path(c: &CanvasRenderingContext2d, pts: &[(f64, f64)]) {
+    c.begin_path();
+    for (i, &(x, y)) in pts.iter().enumerate() {
+        if i == 0 { c.move_to(x, y); } else { c.line_to(x, y); }
+    }
+    c.close_path();
+}
+
+pub(crate) fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, GameError> {
+    canvas
+        .get_context("2d").map_err(|e| GameError::dom("getContext(\"2d\")", e))?
+        .ok_or(GameError::NoContext2d)?
+        .dyn_into::<CanvasRenderingContext2d>().map_err(|_| GameError::NoContext2d)
+}
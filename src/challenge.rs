@@ -0,0 +1,56 @@
+// 挑戦リンク：?seed=...&mode=endless|daily（&date=...&difficulty=...）を付けた URL を配り、開いた人に同じ隕石列のランを遊ばせる
+use crate::core::Game;
+use crate::daily;
+use crate::settings::Difficulty;
+
+pub(crate) struct Challenge { seed: Option<u64>, date: Option<String>, difficulty: Option<Difficulty> }
+
+impl Challenge {
+    // location.search から（today は今日の日付）。seed も mode=daily も無ければ None（ふつうに起動する）
+    pub fn from_query(search: &str, today: &str) -> Option<Self> {
+        let mut c = Self { seed: None, date: None, difficulty: None };
+        let mut is_daily = false;
+        for kv in search.trim_start_matches('?').split('&') {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            match k {
+                "seed" => c.seed = v.parse().ok().filter(|&s| s != 0),
+                "mode" => is_daily = v == "daily",
+                "date" => c.date = Some(v.to_string()).filter(|d| valid_date(d)),
+                "difficulty" => c.difficulty = Difficulty::parse(v),
+                _ => {}
+            }
+        }
+        if is_daily && c.date.is_none() { c.date = Some(today.to_string()); }
+        if !is_daily { c.date = None; }
+        (c.seed.is_some() || c.date.is_some()).then_some(c)
+    }
+
+    // 今のランを遊べるリンク（base は location の origin + pathname）
+    pub fn url(g: &Game, base: &str) -> String {
+        let mut url = format!("{}?seed={}", base, g.seed);
+        match &g.daily {
+            Some(d) => url.push_str(&format!("&mode=daily&date={}", d.date)),
+            None => url.push_str("&mode=endless"),
+        }
+        if g.difficulty != Difficulty::Normal { url.push_str(&format!("&difficulty={}", g.difficulty.name())); }
+        url
+    }
+
+    // 今日のデイリーならデイリーとして、それ以外（過去のデイリーを含む）はシードを固定したエンドレスで始める。
+    // 難易度はこのページを開いている間だけ合わせる（保存しない）
+    pub fn apply(self, g: &mut Game, today: &str) {
+        if let Some(d) = self.difficulty { g.settings.difficulty = d; }
+        g.tutorial = None;
+        match (self.date, self.seed) {
+            (Some(date), _) if date == today => g.set_daily(Some(&date)),
+            (Some(date), _) => g.follow_seed(daily::seed_for(&date)),
+            (None, Some(seed)) => g.follow_seed(seed),
+            (None, None) => {}
+        }
+    }
+}
+
+// "YYYY-MM-DD"
+fn valid_date(d: &str) -> bool {
+    d.len() == 10 && d.bytes().enumerate().all(|(i, b)| if i == 4 || i == 7 { b == b'-' } else { b.is_ascii_digit() })
+}
@@ -0,0 +1,38 @@
+// ゲームオーバー画面の小さな折れ線グラフ：1秒ごとのスコア（accent）と落下速度（hud）。
+// 2本とも自分の最大値で割って同じ枠に重ね、どこで伸びが止まったかを見せる
+use crate::core::Sample;
+use crate::i18n::Msg;
+use crate::render::{Frame, MONO};
+use web_sys::CanvasRenderingContext2d;
+
+pub(crate) fn draw(c: &CanvasRenderingContext2d, f: &Frame, samples: &[Sample], x: f64, y: f64, w: f64, h: f64) {
+    if samples.len() < 2 { return; }
+    c.set_stroke_style_str(&f.theme.hud);
+    c.set_line_width(1.0);
+    c.stroke_rect(x, y, w, h);
+    c.set_line_width(f.line_width() + 0.5);
+    c.set_stroke_style_str(&f.theme.hud);
+    line(c, samples, |s| s.speed, x, y, w, h);
+    c.set_stroke_style_str(&f.theme.accent);
+    line(c, samples, |s| s.score, x, y, w, h);
+    c.set_line_width(1.0);
+    c.set_font(&f.font(false, 10.0, MONO));
+    c.set_fill_style_str(&f.theme.accent);
+    let _ = c.fill_text(f.game.lang.text(Msg::ChartScore), x, y - 4.0);
+    c.set_fill_style_str(&f.theme.hud);
+    let _ = c.fill_text(f.game.lang.text(Msg::ChartSpeed), x + 44.0, y - 4.0);
+    c.set_text_align("right");
+    let _ = c.fill_text(&format!("{}s", samples.len()), x + w, y - 4.0);
+    c.set_text_align("start");
+}
+
+fn line(c: &CanvasRenderingContext2d, samples: &[Sample], value: fn(&Sample) -> f64, x: f64, y: f64, w: f64, h: f64) {
+    let max = samples.iter().map(value).fold(0.0, f64::max).max(1.0);
+    c.begin_path();
+    for (i, s) in samples.iter().enumerate() {
+        let px = x + w * i as f64 / (samples.len() - 1) as f64;
+        let py = y + h - h * value(s) / max;
+        if i == 0 { c.move_to(px, py); } else { c.line_to(px, py); }
+    }
+    c.stroke();
+}
@@ -0,0 +1,127 @@
+// ランの録画（任意）：canvas の映像を MediaRecorder で録り、ゲームオーバーで直近の数秒を webm にして渡す。
+// webm は途中から切り出せないので、N 秒ずらした2本を 2N 秒ごとに録り直し、ゲームオーバーの時点で長く録れている方
+// （N〜2N 秒）を使う
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobEvent, BlobPropertyBag, HtmlCanvasElement, MediaRecorder, MediaRecorderOptions, MediaStream, Url};
+
+const MIME: &str = "video/webm";
+
+// 1本ぶんの録画。残す方は止めた後に dataavailable / stop が遅れて届くので、コールバックは次のゲームオーバーまで持っておく
+struct Segment {
+    rec: MediaRecorder,
+    started: f64,
+    // 止まったときに録れた分を clip に入れるか
+    keep: Rc<Cell<bool>>,
+    _on_data: Closure<dyn FnMut(BlobEvent)>,
+    _on_stop: Closure<dyn FnMut()>,
+}
+
+impl Segment {
+    fn start(stream: &MediaStream, now: f64, clip: &Rc<RefCell<Option<String>>>) -> Option<Self> {
+        let opts = MediaRecorderOptions::new();
+        opts.set_mime_type(MIME);
+        let rec = MediaRecorder::new_with_media_stream_and_media_recorder_options(stream, &opts).ok()?;
+        let chunks: Rc<RefCell<Vec<Blob>>> = Rc::default();
+        let on_data = {
+            let chunks = chunks.clone();
+            Closure::<dyn FnMut(BlobEvent)>::new(move |e: BlobEvent| if let Some(b) = e.data() { chunks.borrow_mut().push(b) })
+        };
+        let keep: Rc<Cell<bool>> = Rc::default();
+        let on_stop = {
+            let (clip, keep) = (clip.clone(), keep.clone());
+            Closure::<dyn FnMut()>::new(move || {
+                if !keep.get() { return; }
+                let parts = chunks.borrow().iter().collect::<js_sys::Array>();
+                let bag = BlobPropertyBag::new();
+                bag.set_type(MIME);
+                let Ok(blob) = Blob::new_with_blob_sequence_and_options(&parts, &bag) else { return };
+                let Ok(url) = Url::create_object_url_with_blob(&blob) else { return };
+                // 前の録画の URL は手放す
+                if let Some(old) = clip.borrow_mut().replace(url) { let _ = Url::revoke_object_url(&old); }
+            })
+        };
+        rec.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+        rec.set_onstop(Some(on_stop.as_ref().unchecked_ref()));
+        rec.start().ok()?;
+        Some(Self { rec, started: now, keep, _on_data: on_data, _on_stop: on_stop })
+    }
+
+    // 止めて clip にする
+    fn finish(&self) {
+        self.keep.set(true);
+        let _ = self.rec.stop();
+    }
+
+    // 捨てる（すぐ落とすので、遅れて届くイベントを受けないようにしてから止める）
+    fn discard(self) {
+        self.rec.set_ondataavailable(None);
+        self.rec.set_onstop(None);
+        let _ = self.rec.stop();
+    }
+}
+
+pub(crate) struct ClipRecorder {
+    stream: MediaStream,
+    seconds: f64,
+    // 今録っている2本と、ゲームオーバーで止めた1本（stop が届くまで持つ）
+    live: [Option<Segment>; 2],
+    finishing: Option<Segment>,
+    run_start: f64,
+    was_over: bool,
+    // 最後のランの録画（object URL）
+    clip: Rc<RefCell<Option<String>>>,
+}
+
+impl ClipRecorder {
+    // MediaRecorder や captureStream が使えなければ None（録画なしで動く）
+    pub fn new(canvas: &HtmlCanvasElement, seconds: f64) -> Option<Self> {
+        if !js_sys::Reflect::has(&js_sys::global(), &"MediaRecorder".into()).unwrap_or(false) || !MediaRecorder::is_type_supported(MIME) { return None; }
+        let stream = canvas.capture_stream().ok()?;
+        Some(Self { stream, seconds: seconds.max(1.0), live: [None, None], finishing: None, run_start: 0.0, was_over: true, clip: Rc::default() })
+    }
+
+    // 毎フレーム呼ぶ（now は秒）。ランの始まりで録り始め、ゲームオーバーで長い方を残す
+    pub fn update(&mut self, now: f64, over: bool) {
+        match (self.was_over, over) {
+            (true, false) => {
+                for s in self.live.iter_mut().filter_map(Option::take) { s.discard(); }
+                self.run_start = now;
+                self.live[0] = Segment::start(&self.stream, now, &self.clip);
+            }
+            (false, true) => {
+                let keep = if self.live[1].as_ref().is_some_and(|b| self.live[0].as_ref().is_none_or(|a| b.started < a.started)) { 1 } else { 0 };
+                if let Some(s) = self.live[1 - keep].take() { s.discard(); }
+                if let Some(s) = self.live[keep].take() {
+                    s.finish();
+                    if let Some(old) = self.finishing.replace(s) { old.discard(); }
+                }
+            }
+            (false, false) => {
+                let n = self.seconds;
+                if self.live[1].is_none() && now - self.run_start >= n {
+                    self.live[1] = Segment::start(&self.stream, now, &self.clip);
+                }
+                for slot in &mut self.live {
+                    if slot.as_ref().is_some_and(|s| now - s.started >= 2.0 * n) {
+                        if let Some(s) = slot.take() { s.discard(); }
+                        *slot = Segment::start(&self.stream, now, &self.clip);
+                    }
+                }
+            }
+            (true, true) => {}
+        }
+        self.was_over = over;
+    }
+
+    pub fn url(&self) -> Option<String> { self.clip.borrow().clone() }
+}
+
+impl Drop for ClipRecorder {
+    fn drop(&mut self) {
+        for s in self.live.iter_mut().filter_map(Option::take).chain(self.finishing.take()) { s.discard(); }
+        if let Some(url) = self.clip.borrow_mut().take() { let _ = Url::revoke_object_url(&url); }
+    }
+}
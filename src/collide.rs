@@ -0,0 +1,69 @@
+// 当たり判定の形。隕石は円（既定）か、見た目の回転に合わせた回転矩形。自機は回転しない矩形のまま
+use crate::core::Rect;
+use serde::{Deserialize, Serialize};
+
+// config の spawn.hitbox（"circle" / "box"）
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HitboxShape { #[default] Circle, Box }
+
+#[derive(Clone, Copy)]
+pub(crate) struct Circle { pub x: f64, pub y: f64, pub r: f64 }
+
+// 中心 (x, y)、半分の幅と高さ、回転（ラジアン）
+#[derive(Clone, Copy)]
+pub(crate) struct Obb { pub x: f64, pub y: f64, pub hw: f64, pub hh: f64, pub angle: f64 }
+
+impl Obb {
+    // 左上から時計回り
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        let (s, c) = self.angle.sin_cos();
+        [(-self.hw, -self.hh), (self.hw, -self.hh), (self.hw, self.hh), (-self.hw, self.hh)]
+            .map(|(dx, dy)| (self.x + dx * c - dy * s, self.y + dx * s + dy * c))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Collider { Circle(Circle), Obb(Obb) }
+
+fn rect_corners(r: &Rect) -> [(f64, f64); 4] {
+    [(r.x, r.y), (r.x + r.w, r.y), (r.x + r.w, r.y + r.h), (r.x, r.y + r.h)]
+}
+
+// 分離軸：どれか1つの軸への影が重ならなければ離れている。矩形どうしなので、軸は両方の辺の向きだけで足りる
+fn separated(a: &[(f64, f64); 4], b: &[(f64, f64); 4], axis: (f64, f64)) -> bool {
+    let span = |pts: &[(f64, f64); 4]| pts.iter().map(|p| p.0 * axis.0 + p.1 * axis.1).fold((f64::MAX, f64::MIN), |(lo, hi), d| (lo.min(d), hi.max(d)));
+    let ((a0, a1), (b0, b1)) = (span(a), span(b));
+    a1 <= b0 || b1 <= a0
+}
+
+impl Collider {
+    pub fn intersects_rect(&self, o: &Rect) -> bool {
+        match self {
+            // 矩形の中で中心に一番近い点までの距離で比べる
+            Collider::Circle(c) => {
+                let dx = c.x - c.x.clamp(o.x, o.x + o.w);
+                let dy = c.y - c.y.clamp(o.y, o.y + o.h);
+                dx * dx + dy * dy < c.r * c.r
+            }
+            Collider::Obb(b) => {
+                let (pts, rect) = (b.corners(), rect_corners(o));
+                let (s, c) = b.angle.sin_cos();
+                ![(1.0, 0.0), (0.0, 1.0), (c, s), (-s, c)].into_iter().any(|axis| separated(&pts, &rect, axis))
+            }
+        }
+    }
+
+    // 形を囲む矩形（空間ハッシュや感知範囲に使う）
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Collider::Circle(c) => Rect { x: c.x - c.r, y: c.y - c.r, w: c.r * 2.0, h: c.r * 2.0 },
+            Collider::Obb(b) => {
+                let pts = b.corners();
+                let (x0, x1) = pts.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+                let (y0, y1) = pts.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+                Rect { x: x0, y: y0, w: x1 - x0, h: y1 - y0 }
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// new GameCell(config) で渡す設定。省略した項目は既定値（今までの固定値）になる
+use crate::assets::AssetConfig;
+use crate::audio::AudioConfig;
+use crate::core::Orientation;
+use crate::error::GameError;
+use crate::render::RendererKind;
+use crate::simconfig::SimConfig;
+use crate::theme::Theme;
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::HtmlCanvasElement;
+
+#[derive(Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct GameConfig {
+    // canvas 要素そのもの、または CSS セレクタ（省略時は canvasId、どちらもなければ "#game"）
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    pub canvas: JsValue,
+    pub canvas_id: Option<String>,
+    // シード・自機と隕石の設定・レベル・言語など、ゲームの進行に使う項目（simconfig.rs）。同じオブジェクトに平らに書く
+    #[serde(flatten)]
+    pub sim: SimConfig,
+    // 既定のテーマ（"default"）の色。書かなかった色は組み込みの既定値
+    pub colors: Theme,
+    pub leaderboard: Option<LeaderboardConfig>,
+    // ランの始まりと終わり（モード・長さ・スコア・終わった理由だけ）を POST する URL（analytics.rs）。省略時は送らない
+    pub analytics_endpoint: Option<String>,
+    // 遊べる向き（"landscape" / "portrait"）。指定すると、違う向きの間は止めて回転を促す
+    pub orientation: Option<Orientation>,
+    // "canvas2d"（既定）/ "webgl2"。WebGL2 が使えなければ canvas2d で描く
+    pub renderer: RendererKind,
+    // 画像の URL（{"player","meteor","background"}）。読み込めるまでと失敗時は矩形で描く
+    pub assets: AssetConfig,
+    // 効果音と BGM の URL（{"spawn","nearMiss","pickup","hit","gameOver","musicTitle","musicGame"}）と、
+    // ファイルの無い効果音を合成するか（"synth"）。最初のキー入力かタップの後から鳴る
+    pub audio: AudioConfig,
+    // 指定するとランを録画し、ゲームオーバーで直近のおよそこの秒数を webm にする（GameCell::clip_url / K キー。worker 版では録らない）
+    pub clip_seconds: Option<f64>,
+    // 初めて遊ぶ人（チュートリアルを終えておらず記録もない）には最初にチュートリアルを出す
+    pub tutorial: bool,
+    // 描画の上限（fps）。省略時は画面の更新ごと
+    pub max_fps: Option<f64>,
+    // 省電力（設定の batterySaver、または充電していなくて電池が 20% 以下）のときの上限
+    pub low_power_fps: f64,
+    // パニックしたときに {message, location} で呼ぶ関数（crash.rs）。canvas にはエラー画面を出す
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    pub on_error: JsValue,
+    // 当たり判定の枠・スポーンの間隔・自機の位置などを重ねて描く（ページの URL に ?debug を付けても有効）
+    pub debug: bool,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LeaderboardConfig { pub endpoint: String, pub player_name: String }
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            canvas: JsValue::UNDEFINED,
+            canvas_id: None,
+            sim: SimConfig::default(),
+            colors: Theme::default(),
+            leaderboard: None,
+            analytics_endpoint: None,
+            orientation: None,
+            renderer: RendererKind::default(),
+            assets: AssetConfig::default(),
+            audio: AudioConfig::default(),
+            clip_seconds: None,
+            tutorial: true,
+            max_fps: None,
+            low_power_fps: 30.0,
+            on_error: JsValue::UNDEFINED,
+            debug: false,
+        }
+    }
+}
+
+impl GameConfig {
+    // undefined / null なら既定値。canvas 要素やセレクタ文字列だけを渡してもよい
+    pub fn from_js(v: JsValue) -> Result<Self, JsValue> {
+        if v.is_undefined() || v.is_null() { return Ok(Self::default()); }
+        if v.is_string() || v.is_instance_of::<HtmlCanvasElement>() {
+            return Ok(Self { canvas: v, ..Self::default() });
+        }
+        let mut config: Self = serde_wasm_bindgen::from_value(v)?;
+        config.sim.animations = config.assets.animations.clone();
+        if let Some(level) = &config.sim.level { level.check().map_err(|e| JsValue::from_str(&e))?; }
+        Ok(config)
+    }
+
+    pub fn on_error(&self) -> Option<js_sys::Function> { self.on_error.dyn_ref().cloned() }
+
+    // 描画先の canvas を探す
+    pub fn resolve_canvas(&self) -> Result<HtmlCanvasElement, GameError> {
+        if let Some(el) = self.canvas.dyn_ref::<HtmlCanvasElement>() {
+            return Ok(el.clone());
+        }
+        let selector = match (self.canvas.as_string(), &self.canvas_id) {
+            (Some(sel), _) => sel,
+            (None, Some(id)) => format!("#{}", id),
+            (None, None) => "#game".to_string(),
+        };
+        let doc = web_sys::window().ok_or(GameError::NoWindow)?.document().ok_or(GameError::NoDocument)?;
+        // 不正なセレクタだと querySelector は例外を投げる
+        doc.query_selector(&selector)
+            .map_err(|e| GameError::dom(&format!("querySelector({:?})", selector), e))?
+            .ok_or_else(|| GameError::CanvasNotFound(selector.clone()))?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| GameError::NotACanvas(selector))
+    }
+}
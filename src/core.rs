@@ -0,0 +1,1008 @@
+// ゲーム本体（シミュレーションだけ）。web_sys に依存しないので、描画や DOM の配線は web 側が受け持つ
+use crate::achievements::Achievements;
+use crate::anim::{Animation, AnimationConfig};
+use crate::barrier::{self, Barrier};
+use crate::camera::Camera;
+use crate::collide::{Circle, Collider, HitboxShape, Obb};
+use crate::daily::DailyRun;
+use crate::deathcam::{DeathCam, Recorder};
+use crate::director::SpawnDirector;
+use crate::energy::{self, Energy};
+use crate::ghost::GhostTrack;
+use crate::grade::{self, Grade, Stamp};
+use crate::gravity::{self, Well};
+use crate::grid::Grid;
+use crate::bot;
+use crate::heatmap::Heatmap;
+use crate::highscore::HighScore;
+use crate::i18n::{Lang, Msg};
+use crate::laser::{self, Laser};
+use crate::menu::Menu;
+use crate::milestone::Celebration;
+use crate::level::{Level, Wave};
+use crate::particles::Particles;
+use crate::pickup::{self, Pickup, Traps};
+use crate::profile;
+use crate::replay::{Playback, Replay, DT_UNIT};
+use crate::rng::{Rng, DEFAULT_SEED};
+use crate::settings::{ControlScheme, Difficulty, Settings};
+use crate::ships::{self, ShipKind, SHIPS};
+use crate::shop::{Owned, Shop};
+use crate::simconfig::{PlayerConfig, SimConfig, SpawnConfig};
+use crate::stage;
+use crate::storage::Storage;
+use crate::toast::Toasts;
+use crate::tutorial::{self, Tutorial};
+use crate::weather::WeatherState;
+use crate::wind::Wind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Rect { pub x: f64, pub y: f64, pub w: f64, pub h: f64 }
+impl Rect {
+    pub fn intersects(&self, o: &Rect) -> bool {
+        self.x < o.x + o.w && self.x + self.w > o.x && self.y < o.y + o.h && self.y + self.h > o.y
+    }
+    pub fn expanded(&self, m: f64) -> Rect {
+        Rect { x: self.x - m, y: self.y - m, w: self.w + m * 2.0, h: self.h + m * 2.0 }
+    }
+}
+
+// 傾きが入力に追いつく速さ（1/秒）
+pub(crate) const TILT_RATE: f64 = 10.0;
+
+// ニアミス判定用のマージンとボーナス
+const NEAR_MISS_MARGIN: f64 = 10.0;
+pub(crate) const NEAR_MISS_BONUS: f64 = 50.0;
+
+// 画面の向き（縦長なら Portrait）
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Orientation { Landscape, Portrait }
+impl Orientation {
+    pub fn of(width: f64, height: f64) -> Self {
+        if height > width { Orientation::Portrait } else { Orientation::Landscape }
+    }
+}
+// 物の大きさの基準にする横幅（これより狭い縦長画面では縮める）
+const REFERENCE_WIDTH: f64 = 640.0;
+
+// 固定ステップ（約120Hz）。リプレイの dt 単位の整数倍にして、記録しても値が変わらないようにする
+pub(crate) const FIXED_DT: f64 = 833.0 * DT_UNIT;
+// 1回の step() で追いつく上限（タブ復帰直後などは残りを捨てる）
+const MAX_STEPS: u32 = 8;
+// 決着してもすぐには終わらせず、実時間で DEATH_SLOW 秒だけ DEATH_SCALE 倍の速さで流しながら、落ちた自機へ DEATH_ZOOM 倍まで寄る
+pub(crate) const DEATH_SLOW: f64 = 0.5;
+pub(crate) const DEATH_SCALE: f64 = 0.2;
+pub(crate) const DEATH_ZOOM: f64 = 1.35;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Meteor {
+    // ランの中で一意な番号（出た順）。形や回り方はこれで決める
+    #[serde(default)]
+    pub id: u32,
+    pub r: Rect,
+    // 直前の update() 前の y（描画の補間用）
+    #[serde(skip)]
+    pub prev_y: f64,
+    pub vy: f64,
+    // 横の速さ（ブラックホールに引かれたときだけ 0 以外）
+    #[serde(default)]
+    pub vx: f64,
+    // 感知範囲に入ったか / ボーナス付与済みか（プレイヤーごとのビット、1人1回だけ）
+    pub grazed: u8,
+    pub near_miss_awarded: u8,
+    // 見た目の回転（ラジアン）と回る速さ（ラジアン/秒）。shape が box なら当たり判定も一緒に回る
+    #[serde(default)]
+    pub angle: f64,
+    #[serde(default)]
+    pub spin: f64,
+    #[serde(default)]
+    pub shape: HitboxShape,
+    // スプライトのコマ（見た目だけなので保存しない）
+    #[serde(skip)]
+    pub anim: Animation,
+}
+impl Meteor {
+    // 回る速さは番号から決める（ゲームの乱数を使うと隕石列が変わるので）
+    pub fn new(id: u32, r: Rect, vy: f64, shape: HitboxShape) -> Self {
+        let spin = (id.wrapping_mul(2_654_435_761) >> 29) as f64 * 0.5 - 1.5;
+        Self { id, r, prev_y: r.y, vy, vx: 0.0, grazed: 0, near_miss_awarded: 0, angle: 0.0, spin, shape, anim: Animation::default() }
+    }
+    // 円なら矩形に内接する円、box なら矩形を angle だけ回したもの
+    pub fn hitbox(&self) -> Collider { self.hitbox_at(self.r.y) }
+    fn hitbox_at(&self, top: f64) -> Collider {
+        let (x, y) = (self.r.x + self.r.w * 0.5, top + self.r.h * 0.5);
+        match self.shape {
+            HitboxShape::Circle => Collider::Circle(Circle { x, y, r: self.r.w.min(self.r.h) * 0.5 }),
+            HitboxShape::Box => Collider::Obb(Obb { x, y, hw: self.r.w * 0.5, hh: self.r.h * 0.5, angle: self.angle }),
+        }
+    }
+    // 当たり判定より一回り大きい感知用の矩形
+    pub fn sense_rect(&self) -> Rect { self.hitbox().bounds().expanded(NEAR_MISS_MARGIN) }
+    // このステップの移動（prev_y → y）の途中で r に触れたか。速いと1ステップで自機を飛び越えるので、
+    // 動く前の形と「r を移動量だけ上に伸ばした矩形」で比べる（ほぼ縦にしか動かないので、これで移動中のどこかで重なるかと同じとみなす。
+    // ブラックホールで横に流される分は1ステップで数 px なので無視する）
+    pub fn swept_hits(&self, r: &Rect) -> bool {
+        let d = (self.r.y - self.prev_y).max(0.0);
+        self.hitbox_at(self.prev_y).intersects_rect(&Rect { y: r.y - d, h: r.h + d, ..*r })
+    }
+}
+
+// コンボ：ボーナスを時間内に連続で取ると倍率が上がる
+pub(crate) const COMBO_WINDOW: f64 = 2.5;
+
+// タイトルで放っておかれてからデモを始めるまでの秒数と、デモが終わってから次のデモまでの秒数
+const DEMO_IDLE: f64 = 15.0;
+const DEMO_AGAIN: f64 = 3.0;
+
+// アシストモード：スコアによる加速・スポーン間隔の詰まり方にかける倍率と、当たり判定を追加で縮める割合
+const ASSIST_RAMP: f64 = 0.6;
+const ASSIST_SHRINK: f64 = 0.15;
+// 隕石の上限で減らした分の埋め合わせ：速さの倍率の上限と、上限で見送った出現1回ごとに足す分
+const MAX_CAP_BOOST: f64 = 1.5;
+const CAP_SKIP_WEIGHT: f64 = 0.25;
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Combo { pub count: u32, pub timer: f64 }
+impl Combo {
+    pub fn new() -> Self { Self { count: 0, timer: 0.0 } }
+    pub fn multiplier(&self) -> f64 { (1.0 + 0.25 * self.count.saturating_sub(1) as f64).min(4.0) }
+    // ボーナス獲得を登録して、適用する倍率を返す
+    pub fn hit(&mut self) -> f64 {
+        self.count += 1;
+        self.timer = COMBO_WINDOW;
+        self.multiplier()
+    }
+    pub fn tick(&mut self, dt: f64) {
+        if self.count == 0 { return; }
+        self.timer -= dt;
+        if self.timer <= 0.0 { self.reset(); }
+    }
+    pub fn reset(&mut self) { self.count = 0; self.timer = 0.0; }
+}
+
+// メモリ予算（低メモリモードでは確保量と履歴系バッファを絞る）
+#[derive(Clone, Copy)]
+pub(crate) struct Budgets { pub meteor_capacity: usize, pub max_popups: usize, pub max_particles: usize, pub history: bool }
+impl Budgets {
+    pub fn normal() -> Self { Self { meteor_capacity: 64, max_popups: 16, max_particles: 256, history: true } }
+    pub fn low_memory() -> Self { Self { meteor_capacity: 32, max_popups: 3, max_particles: 48, history: false } }
+}
+
+// 残すものだけ残す。消すときは末尾と入れ替えるので、後ろを詰め直さず確保もし直さない（順番は変わる）
+pub(crate) fn swap_retain<T>(v: &mut Vec<T>, keep: impl Fn(&T) -> bool) {
+    let mut i = 0;
+    while i < v.len() {
+        if keep(&v[i]) { i += 1; } else { v.swap_remove(i); }
+    }
+}
+
+// ステップごとに作り直さないよう Game が持っておく作業用の Vec
+#[derive(Default)]
+pub(crate) struct Scratch {
+    // 自機に当たった (隕石の id, 大きさ) と、ブラックホール・レーザーの番号
+    pub hits: Vec<(u32, f64)>,
+    pub wells: Vec<usize>,
+    pub lasers: Vec<usize>,
+    // エネルギーで持ちこたえて消す隕石の id
+    pub absorbed: Vec<u32>,
+    // 拾われたものの番号
+    pub taken: Vec<usize>,
+    // 壁とレーザーを出すときの、生きている自機の中心の x
+    pub aims: Vec<f64>,
+}
+
+// 1ラン分の統計（実績判定などに使う）。samples は1秒ごとの記録（ゲームオーバー画面のグラフ用）
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RunStats {
+    pub elapsed: f64,
+    pub score: f64,
+    pub dodged: u32,
+    pub near_misses: u32,
+    pub moved: bool,
+    #[serde(default)]
+    pub samples: Vec<Sample>,
+    // 1P が拾った宝石（ゲームオーバーでコインにする）
+    #[serde(default)]
+    pub gems: u32,
+    // 1P がエネルギーで受けた被弾の量（評価で引く）
+    #[serde(default)]
+    pub damage: f64,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct Sample { pub score: f64, pub speed: f64 }
+
+// score イベントを出す刻み
+pub(crate) const SCORE_EVENT_STEP: f64 = 100.0;
+
+// ゲームオーバー画面の P で順に選べる練習の開始スコア（その次は通常に戻る）
+pub(crate) const PRACTICE_CHECKPOINTS: &[f64] = &[1500.0, 3000.0, 5000.0, 8000.0];
+
+// 「CLOSE!」などの浮き上がって消える文字
+pub(crate) struct Popup { pub text: &'static str, pub x: f64, pub y: f64, pub ttl: f64 }
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Input { pub left: bool, pub right: bool }
+
+// プレイヤー1人分（2人対戦では2つ並ぶ）
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Player {
+    pub r: Rect,
+    // 直前の update() 前の x（描画の補間用）
+    #[serde(skip)]
+    pub prev_x: f64,
+    pub input: Input,
+    pub combo: Combo,
+    pub score: f64,
+    pub alive: bool,
+    // エネルギー（energy.rs。メーターが無ければ max が 0）
+    #[serde(default)]
+    pub energy: Energy,
+    // ブラックホールに引かれて流される横の速さ（px/秒、入力とは別に足して少しずつ減らす）
+    #[serde(default)]
+    pub drift: f64,
+    // dasher の飛び出しの残り秒数と、次に出せるまでの秒数、直前の update() で動いた向き（ships.rs）
+    #[serde(default)]
+    pub dash: f64,
+    #[serde(default)]
+    pub dash_cd: f64,
+    #[serde(default)]
+    pub last_dir: f64,
+    #[serde(skip)]
+    pub anim: Animation,
+    // 見た目の傾き（-1..1、右に動くと正）。入力の向きになめらかに寄せる
+    #[serde(skip)]
+    pub tilt: f64,
+}
+impl Player {
+    pub fn new(r: Rect) -> Self {
+        Self { r, prev_x: r.x, input: Input::default(), combo: Combo::new(), score: 0.0, alive: true, energy: Energy::default(), drift: 0.0, dash: 0.0, dash_cd: 0.0, last_dir: 0.0, anim: Animation::default(), tilt: 0.0 }
+    }
+    // 被弾の判定に使う矩形（見た目より shrink の割合だけ小さい。かすめただけでは当たらない）
+    pub fn hitbox(&self, shrink: f64) -> Rect {
+        let s = shrink.clamp(0.0, 0.9);
+        Rect { x: self.r.x + self.r.w * s * 0.5, y: self.r.y + self.r.h * s * 0.5, w: self.r.w * (1.0 - s), h: self.r.h * (1.0 - s) }
+    }
+}
+
+
+// update() の中で起きた出来事。各システムはこれを積むだけで、効果音・パーティクル・統計は Game::react が、
+// JS のコールバックや読み上げは呼び出し側が取り出して受け持つ（x は画面上の横位置）
+#[derive(Clone)]
+pub(crate) enum GameEvent {
+    // ランの開始（やり直し・モード切り替えを含む）
+    Start,
+    MeteorSpawned { x: f64 },
+    // 被弾して落ちた（x, y は自機の中心、cause は "meteor" / "well" / "laser"）
+    Collision { player: u32, x: f64, y: f64, cause: &'static str },
+    // winner は対戦で勝った方の番号（引き分け・1人プレイは None）。solo なら記録を残したラン
+    GameOver { score: f64, new_record: bool, winner: Option<u32>, solo: bool, game_speed: f64, assisted: bool },
+    Score { score: f64 },
+    NearMiss { player: u32, combo: u32, x: f64 },
+    Wave { stage: u32, name: String, weather: &'static str },
+    Achievement { id: &'static str, title: &'static str },
+    // スコアの節目（milestone.rs の STEP ごと）
+    Milestone { score: f64 },
+    // 拾いもの（kind は pickup.rs の名前、x は拾った位置）
+    PowerUp { kind: &'static str, trap: bool, x: f64 },
+}
+
+// 効果音のきっかけ（見た目と同じく乱数やスコアには関わらない）
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Sound { Spawn, NearMiss, Pickup, Trap, Milestone, Hit, GameOver }
+
+impl Sound {
+    pub fn name(self) -> &'static str {
+        match self { Sound::Spawn => "spawn", Sound::NearMiss => "nearMiss", Sound::Pickup => "pickup", Sound::Trap => "trap", Sound::Milestone => "milestone", Sound::Hit => "hit", Sound::GameOver => "gameOver" }
+    }
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "spawn" => Some(Sound::Spawn), "nearMiss" => Some(Sound::NearMiss), "pickup" => Some(Sound::Pickup), "trap" => Some(Sound::Trap), "milestone" => Some(Sound::Milestone),
+            "hit" => Some(Sound::Hit), "gameOver" => Some(Sound::GameOver), _ => None,
+        }
+    }
+}
+
+// 鳴らす音と左右の位置（-1 が左端、1 が右端）。隕石の音は自機から見た向きに振る
+#[derive(Clone, Copy)]
+pub(crate) struct Cue { pub sound: Sound, pub pan: f64 }
+
+impl GameEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEvent::Start => "start",
+            GameEvent::MeteorSpawned { .. } => "spawn",
+            GameEvent::Collision { .. } => "hit",
+            GameEvent::GameOver { .. } => "gameover",
+            GameEvent::Score { .. } => "score",
+            GameEvent::NearMiss { .. } => "nearmiss",
+            GameEvent::Wave { .. } => "wave",
+            GameEvent::Achievement { .. } => "achievement",
+            GameEvent::PowerUp { .. } => "powerup",
+            GameEvent::Milestone { .. } => "milestone",
+        }
+    }
+}
+
+pub(crate) struct Game {
+    pub player_config: PlayerConfig,
+    pub spawn_config: SpawnConfig,
+    // 読み込んだレベル（無ければ組み込みのステージ表とスポーンの設定で進む）
+    pub level: Option<Level>,
+    // アトラスのアニメーション（コマ送りはシミュレーションの時間で進める）
+    pub(crate) clips: AnimationConfig,
+    // 記録や設定の保存先
+    pub storage: Box<dyn Storage>,
+    pub width: f64,
+    pub height: f64,
+    pub orientation: Orientation,
+    pub players: Vec<Player>,
+    // 2人対戦（A/D と矢印で分け合い、最後に残った方の勝ち）
+    pub versus: bool,
+    pub meteors: Vec<Meteor>,
+    // ブラックホール（gravity.rs）と、次が出るまでの秒数
+    pub wells: Vec<Well>,
+    pub(crate) well_timer: f64,
+    // 突風（wind.rs）
+    pub wind: Wind,
+    // すき間の開いた壁（barrier.rs）と、次が出るまでの秒数
+    pub barriers: Vec<Barrier>,
+    pub(crate) barrier_timer: f64,
+    // 予告つきのレーザー（laser.rs）と、次が出るまでの秒数
+    pub lasers: Vec<Laser>,
+    pub(crate) laser_timer: f64,
+    // 拾いもの（pickup.rs）と、次が出るまでの秒数、効いている罠
+    pub pickups: Vec<Pickup>,
+    pub(crate) pickup_timer: f64,
+    pub traps: Traps,
+    // 次に出す隕石の番号
+    pub next_id: u32,
+    pub popups: Vec<Popup>,
+    // 節目のお祝いの表示（見た目だけ）
+    pub celebration: Option<Celebration>,
+    pub particles: Particles,
+    pub camera: Camera,
+    // F3 で切り替える FPS・フレーム時間の表示（保存しない）
+    pub show_perf: bool,
+    pub low_memory: bool,
+    pub budgets: Budgets,
+    pub stats: RunStats,
+    pub achievements: Achievements,
+    pub toasts: Toasts,
+    pub stage: usize,
+    pub weather: WeatherState,
+    pub high_score: HighScore,
+    // 落ちた位置の分布（heatmap.rs）
+    pub heatmap: Heatmap,
+    // コインと強化の店（shop.rs）と、今のランに効いている強化
+    pub shop: Shop,
+    pub upgrades: Owned,
+    // 今のランの機体（ships.rs）と、解除済みの機体の組（ゲームオーバーで新しく解除されたものを知らせる）
+    pub ship: ShipKind,
+    pub ships_unlocked: u8,
+    // 決着してからゲームオーバーまでの残り（シミュレーションの秒。決着していなければ None）
+    pub dying: Option<f64>,
+    // 直前のランで記録を更新したか（ゲームオーバー画面で表示）
+    pub new_record: bool,
+    // 直前のランの評価（grade.rs。記録に残るランだけ）
+    pub grade: Option<Stamp>,
+    // 最後の数秒の場面と、ゲームオーバー後にそれを見せている再生（deathcam.rs）
+    pub last_moments: Recorder,
+    pub deathcam: Option<DeathCam>,
+    // update() 中に溜まったイベント
+    pub events: Vec<GameEvent>,
+    // このフレームで鳴らす効果音（step() のたびに空にするので、鳴らす側は step() の後に取り出す）
+    pub sounds: Vec<Cue>,
+    // 隕石の感知範囲を入れた空間ハッシュと、問い合わせの結果（どちらも毎フレーム使い回す）
+    pub(crate) grid: Grid,
+    pub(crate) nearby: Vec<u32>,
+    // collide と tick_pickups の途中の結果（毎ステップ clear して使い回す）
+    pub(crate) scratch: Scratch,
+    pub(crate) last_score_step: i64,
+    // 外から決められたシード（オンライン対戦・挑戦リンク）。あればデイリーより優先して毎回このシードから
+    pub fixed_seed: Option<u64>,
+    // デイリーチャレンジ中なら Some（None は通常のエンドレス）
+    pub daily: Option<DailyRun>,
+    // 今のランのシード（同じシードなら同じ隕石列になる）
+    pub seed: u64,
+    pub rng: Box<dyn Rng>,
+    // 隕石の出し方をページが決めるとき（director.rs）。このランは記録に残さない
+    pub director: Option<Box<dyn SpawnDirector>>,
+    // チュートリアル中なら Some（これも記録に残さない）
+    pub tutorial: Option<Tutorial>,
+    // 練習モードの開始スコア（その点数の速さと密度から始める。記録やランキングには残さない）
+    pub practice: Option<f64>,
+    // デモ中（ボットが自機を動かす。記録には残さず、何か入力があればタイトルに戻る）
+    pub demo: bool,
+    // デモを出すか（config の attract）
+    attract: bool,
+    // DevTools に区間を出すか（config の profile、profile.rs）
+    pub(crate) profile: bool,
+    // タイトル（ゲームオーバー画面）で入力がないまま過ぎた秒数
+    idle: f64,
+    // 今のランの記録と、再生中ならその位置
+    pub recording: Replay,
+    pub playback: Option<Playback>,
+    // 今のランの軌跡と、ベストランのゴースト
+    pub ghost_run: GhostTrack,
+    pub best_ghost: Option<GhostTrack>,
+    pub settings: Settings,
+    // 文言の言語（i18n.rs）
+    pub lang: Lang,
+    // 今のランの難易度（設定を変えても次のランから反映）
+    pub difficulty: Difficulty,
+    // 今のランのゲーム速度（経過時間にかける。これも次のランから）
+    pub game_speed: f64,
+    // 今のランがアシストモードか（これも次のランから）
+    pub assist: bool,
+    // 今のランで罠の拾いものを出すか（これも次のランから）
+    pub trap_pickups: bool,
+    pub spawn_timer: f64,
+    // 同時に出ている隕石の上限（spawnConfig.maxMeteors）で続けて見送った出現の数
+    pub(crate) capped_spawns: u32,
+    // まだ update() に回していない時間（FIXED_DT 未満）
+    acc: f64,
+    // ランのスコア（プレイヤーの最高値。難易度やステージはこれで進む）
+    pub score: f64,
+    pub speed: f64,
+    pub over: bool,
+    pub paused: bool,
+    // 一時停止中の設定メニュー（menu.rs）
+    pub menu: Menu,
+}
+
+impl Game {
+    pub fn new(width: f64, height: f64, config: &SimConfig, storage: Box<dyn Storage>, mut rng: Box<dyn Rng>) -> Self {
+        let seed = config.seed.unwrap_or(DEFAULT_SEED);
+        rng.reseed(seed);
+        // reducedMotion を保存していなければ OS の設定に従う
+        let mut settings = Settings::load(&*storage, Settings { reduced_motion: config.prefers_reduced_motion, ..Settings::default() });
+        if let Some(scheme) = config.control_scheme { settings.control_scheme = scheme; }
+        if let Some(palette) = config.palette { settings.palette = palette; }
+        let mut g = Self {
+            player_config: config.player.clone(),
+            spawn_config: config.spawn.clone(),
+            level: config.level.clone(),
+            clips: config.animations.clone(),
+            speed: config.spawn.base_speed,
+            width,
+            height,
+            orientation: Orientation::of(width, height),
+            players: Vec::with_capacity(2),
+            versus: false,
+            meteors: Vec::with_capacity(Budgets::normal().meteor_capacity),
+            wells: Vec::new(),
+            well_timer: gravity::FIRST,
+            wind: Wind::new(config.spawn.wind_interval),
+            barriers: Vec::new(),
+            barrier_timer: barrier::FIRST,
+            lasers: Vec::new(),
+            laser_timer: laser::FIRST,
+            pickups: Vec::new(),
+            pickup_timer: pickup::FIRST,
+            traps: Traps::default(),
+            next_id: 0,
+            popups: Vec::with_capacity(Budgets::normal().max_popups),
+            celebration: None,
+            particles: Particles::new(Budgets::normal().max_particles),
+            camera: Camera::default(),
+            show_perf: false,
+            low_memory: false,
+            budgets: Budgets::normal(),
+            stats: RunStats::default(),
+            achievements: Achievements::load(&*storage),
+            toasts: Toasts::default(),
+            stage: 0,
+            weather: WeatherState::new(stage::stage(0).weather),
+            high_score: HighScore::load(&*storage),
+            heatmap: Heatmap::load(&*storage),
+            shop: Shop::load(&*storage),
+            upgrades: Owned::default(),
+            ship: ShipKind::Classic,
+            ships_unlocked: 0,
+            dying: None,
+            new_record: false,
+            grade: None,
+            last_moments: Recorder::default(),
+            deathcam: None,
+            events: Vec::new(),
+            sounds: Vec::new(),
+            grid: Grid::default(),
+            nearby: Vec::new(),
+            scratch: Scratch::default(),
+            last_score_step: 0,
+            fixed_seed: None,
+            demo: false,
+            attract: config.attract,
+            profile: config.profile,
+            idle: 0.0,
+            daily: None,
+            seed,
+            rng,
+            director: None,
+            tutorial: None,
+            practice: None,
+            recording: Replay::new(seed, Difficulty::Normal),
+            playback: None,
+            ghost_run: GhostTrack::new(),
+            best_ghost: GhostTrack::load(&*storage),
+            settings,
+            lang: config.language.unwrap_or_default(),
+            difficulty: Difficulty::Normal,
+            game_speed: 1.0,
+            assist: false,
+            trap_pickups: true,
+            spawn_timer: 0.0,
+            capped_spawns: 0,
+            acc: 0.0,
+            score: 0.0,
+            over: false,
+            paused: false,
+            menu: Menu::default(),
+            storage,
+        };
+        g.ships_unlocked = ships::unlocked_mask(&g);
+        g.restart();
+        g
+    }
+
+    // 人数分のプレイヤーを横に等間隔で並べる
+    fn spawn_players(&mut self) {
+        let n = if self.versus { 2 } else { 1 };
+        self.players.clear();
+        let (w, h) = self.player_size();
+        let max = if self.assist { self.player_config.energy.max(energy::ASSIST) } else { self.player_config.energy.max(0.0) } + self.upgrades.energy();
+        for i in 0..n {
+            let x = self.width * (i + 1) as f64 / (n + 1) as f64 - w * 0.5;
+            self.players.push(Player { energy: Energy::new(max), ..Player::new(Rect { x, y: self.height - 20.0 - h, w, h }) });
+        }
+    }
+
+    // 自機の大きさ（画面の大きさと機体で変わる）
+    fn player_size(&self) -> (f64, f64) {
+        let scale = self.size_scale() * self.ship.size();
+        (self.player_config.width * scale, self.player_config.height * scale)
+    }
+
+    // キー → (プレイヤー, 左右)。対戦では A/D が1P、矢印が2P、1人ならどちらでも1P
+    pub fn set_key(&mut self, key: &str, down: bool) {
+        let (who, left) = match key {
+            "a" | "A" => (0, true),
+            "d" | "D" => (0, false),
+            "ArrowLeft" => (1, true),
+            "ArrowRight" => (1, false),
+            _ => return,
+        };
+        let idx = match (self.versus, self.settings.control_scheme) {
+            (true, _) => who,
+            (false, ControlScheme::Both) => 0,
+            (false, ControlScheme::Wasd) if who == 0 => 0,
+            (false, ControlScheme::Arrows) if who == 1 => 0,
+            _ => return,
+        };
+        if let Some(p) = self.players.get_mut(idx) {
+            if left { p.input.left = down; } else { p.input.right = down; }
+        }
+    }
+
+    // 押しっぱなしのキーを離したことにする
+    pub fn clear_input(&mut self) {
+        for p in &mut self.players { p.input = Input::default(); }
+    }
+
+    // 低メモリモード：バッファを予算に合わせて確保し直す（プレイ中は再確保しない）
+    pub fn set_low_memory(&mut self, on: bool) {
+        self.low_memory = on;
+        self.budgets = if on { Budgets::low_memory() } else { Budgets::normal() };
+        let b = self.budgets;
+        self.popups.truncate(b.max_popups);
+        self.popups.shrink_to(b.max_popups);
+        self.particles.set_cap(b.max_particles);
+        self.meteors.shrink_to(b.meteor_capacity);
+        self.meteors.reserve(b.meteor_capacity.saturating_sub(self.meteors.len()));
+        if !b.history {
+            self.recording = Replay::new(self.seed, self.difficulty);
+            self.ghost_run = GhostTrack::new();
+        }
+    }
+
+    // x にある音の左右の位置。1人なら自機から見て、対戦なら画面の中央から見る（半画面離れたら端）
+    pub(crate) fn pan(&self, x: f64) -> f64 {
+        let p = &self.players[0].r;
+        let from = if self.versus { self.width * 0.5 } else { p.x + p.w * 0.5 };
+        ((x - from) / (self.width * 0.5)).clamp(-1.0, 1.0)
+    }
+
+    // 今のウェーブとその中の経過秒（レベルを読み込んでいるときだけ）
+    pub(crate) fn wave(&self) -> Option<(&Wave, f64)> {
+        self.level.as_ref().map(|l| { let (_, w, t) = l.at(self.stats.elapsed); (w, t) })
+    }
+
+    // 次のスポーンまでの間隔（スコアかウェーブの曲線・天候・難易度で縮む。出現数の上限があればそれより詰めない）
+    pub fn spawn_interval(&self) -> f64 {
+        let min = self.spawn_config.max_rate.filter(|&r| r > 0.0).map_or(0.0, |r| 1.0 / r);
+        self.uncapped_spawn_interval().max(min)
+    }
+
+    fn uncapped_spawn_interval(&self) -> f64 {
+        let sp = &self.spawn_config;
+        let base = self.wave().and_then(|(w, t)| w.interval.at(t)).unwrap_or(sp.interval_min.max(sp.interval - self.score * sp.interval_ramp * self.ramp()));
+        base.max(0.15) * self.weather.kind.spawn_interval_scale() / self.difficulty.scale()
+    }
+
+    // 隕石が上限まで出ているか（spawnConfig.maxMeteors）
+    pub(crate) fn crowded(&self) -> bool { self.spawn_config.max_meteors.is_some_and(|n| self.meteors.len() >= n) }
+
+    // 上限で減った隕石の分だけ落下を速くする倍率（上限が効いていなければ 1）。出現が間引かれた割合と見送った数から
+    pub fn cap_boost(&self) -> f64 {
+        let thinned = self.spawn_interval() / self.uncapped_spawn_interval() + self.capped_spawns as f64 * CAP_SKIP_WEIGHT;
+        thinned.sqrt().min(MAX_CAP_BOOST)
+    }
+
+    // スコアで難しくなる速さの倍率（アシストモードでは遅く）
+    pub fn ramp(&self) -> f64 { if self.assist { ASSIST_RAMP } else { 1.0 } }
+
+    // 自機の当たり判定を縮める割合（アシストモードではさらに小さく）
+    pub fn hitbox_shrink(&self) -> f64 { self.player_config.hitbox_shrink + if self.assist { ASSIST_SHRINK } else { 0.0 } }
+
+    // アシストを使ったラン（アシストモードか、ゲーム速度を下げた）。結果画面・イベント・ランキングで印を付ける
+    pub fn assisted(&self) -> bool { self.assist || self.game_speed < 1.0 }
+
+    // 物の大きさの倍率。横向きはそのまま、縦長で幅が狭いときは幅に合わせて縮める（引き伸ばしはしない）
+    pub fn size_scale(&self) -> f64 {
+        match self.orientation {
+            Orientation::Landscape => 1.0,
+            Orientation::Portrait => (self.width / REFERENCE_WIDTH).clamp(0.5, 1.0),
+        }
+    }
+
+    // 画面サイズが変わったら、盤面の物を比率を保って移す（プレイヤーは大きさを合わせ直して下端に付け直す）
+    pub fn resize(&mut self, width: f64, height: f64) {
+        if width <= 0.0 || height <= 0.0 { return; }
+        let (sx, sy) = (width / self.width, height / self.height);
+        self.width = width;
+        self.height = height;
+        self.orientation = Orientation::of(width, height);
+        let (w, h) = self.player_size();
+        for p in &mut self.players {
+            p.r.w = w;
+            p.r.h = h;
+            p.r.x = (p.r.x * sx).clamp(0.0, (width - w).max(0.0));
+            p.r.y = height - 20.0 - h;
+        }
+        for m in &mut self.meteors { m.r.x *= sx; m.r.y *= sy; }
+        for w in &mut self.wells { w.x *= sx; w.y *= sy; }
+        for l in &mut self.lasers { l.x *= sx; }
+        for p in &mut self.pickups { p.x *= sx; p.y *= sy; }
+        for b in &mut self.barriers { b.y *= sy; b.gap_x *= sx; b.gap_w = (b.gap_w * sx).max(w * 1.2); }
+        for p in &mut self.popups { p.x *= sx; p.y *= sy; }
+        self.settle();
+    }
+
+    pub fn save_settings(&self) { self.settings.save(&*self.storage); }
+
+    // デイリーチャレンジの切り替え（date は "YYYY-MM-DD"、None で通常に戻す。ランは最初からやり直し）
+    pub fn set_daily(&mut self, date: Option<&str>) {
+        self.daily = date.map(|d| DailyRun::for_date(d, &*self.storage));
+        self.tutorial = None;
+        self.reset();
+    }
+
+    // 2人対戦の切り替え（ランは最初からやり直し）
+    pub fn set_versus(&mut self, on: bool) {
+        self.versus = on;
+        self.tutorial = None;
+        self.reset();
+    }
+
+    // チュートリアルを最初から（1人で。終えたら通常のランに戻る）
+    pub fn start_tutorial(&mut self) {
+        self.versus = false;
+        self.daily = None;
+        self.tutorial = Some(Tutorial::new());
+        self.reset();
+    }
+
+    // 練習モードの切り替え（None で通常に戻す。ランは最初からやり直し）
+    pub fn set_practice(&mut self, start_score: Option<f64>) {
+        self.practice = start_score.filter(|s| s.is_finite()).map(|s| s.max(0.0));
+        if self.practice.is_some() { self.versus = false; self.tutorial = None; }
+        self.reset();
+    }
+
+    // 次のチェックポイントから練習する（最後の次は通常に戻る）
+    pub fn next_practice(&mut self) {
+        let next = PRACTICE_CHECKPOINTS.iter().copied().find(|&s| self.practice.is_none_or(|p| s > p));
+        self.set_practice(next);
+    }
+
+    // 初めて遊ぶ人か（チュートリアルを終えておらず、記録もない）
+    pub fn first_time(&self) -> bool { !tutorial::done(&*self.storage) && self.high_score.best == 0.0 }
+
+    // レベルの差し替え（None で組み込みのステージ表に戻す。ランは最初からやり直し）
+    pub fn set_level(&mut self, level: Option<Level>) {
+        self.level = level;
+        self.reset();
+    }
+
+    // 隕石の出し方の差し替え（None で組み込みに戻す。ランは最初からやり直し）
+    pub fn set_director(&mut self, director: Option<Box<dyn SpawnDirector>>) {
+        self.director = director;
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        // オンラインとデイリーは毎回同じシードから。通常は前のランの乱数列から次のシードを引く
+        self.seed = match (self.fixed_seed, &self.daily) {
+            (Some(seed), _) => seed,
+            (None, Some(d)) => d.seed,
+            (None, None) => self.rng.next_u64(),
+        };
+        self.playback = None;
+        self.restart();
+    }
+
+    // シードを固定して1人プレイでやり直す（オンラインでサーバーからシードが届いたとき・挑戦リンクから開いたとき）
+    pub fn follow_seed(&mut self, seed: u64) {
+        self.fixed_seed = Some(seed);
+        self.versus = false;
+        self.playback = None;
+        self.seed = seed;
+        self.restart();
+    }
+
+    // リプレイを最初から再生する
+    pub fn start_playback(&mut self, replay: Replay) {
+        self.versus = false;
+        self.practice = None;
+        self.tutorial = None;
+        self.seed = replay.seed;
+        self.restart();
+        self.difficulty = replay.difficulty;
+        self.speed = self.spawn_config.base_speed * self.difficulty.scale();
+        self.upgrades = replay.upgrades;
+        self.ship = replay.ship;
+        self.spawn_players();
+        self.playback = Some(Playback::new(replay));
+    }
+
+    // タイトルで DEMO_IDLE 秒放っておかれたらデモを始める。デモが終わったら DEMO_AGAIN 秒おいて次のデモ
+    fn tick_demo(&mut self, dt: f64) {
+        if !self.over || self.paused { self.idle = 0.0; return; }
+        self.idle += dt;
+        // 対戦・リプレイ・オンライン（シード固定）の後は出さない
+        let allowed = self.attract && !self.versus && self.playback.is_none() && self.fixed_seed.is_none();
+        if allowed && self.idle >= if self.demo { DEMO_AGAIN } else { DEMO_IDLE } {
+            self.reset();
+            self.demo = true;
+        }
+    }
+
+    // キーやタップがあった。デモ中ならやめてタイトルに戻し、その入力はそこで使い切る（true）
+    pub fn wake(&mut self) -> bool {
+        self.idle = 0.0;
+        if !self.demo { return false; }
+        self.demo = false;
+        self.over = true;
+        self.new_record = false;
+        self.score = 0.0;
+        self.stats = RunStats::default();
+        self.grade = None;
+        self.last_moments.clear();
+        self.deathcam = None;
+        self.dying = None;
+        self.meteors.clear();
+        self.wells.clear();
+        self.barriers.clear();
+        self.lasers.clear();
+        self.pickups.clear();
+        self.traps = Traps::default();
+        self.popups.clear();
+        self.celebration = None;
+        self.toasts.clear();
+        self.particles.clear();
+        self.spawn_players();
+        self.clear_input();
+        true
+    }
+
+    // 前のランの決着の演出（ゆっくり・寄ったカメラ・直前の再生とその記録・評価のハンコ）を捨て、結果画面で開いた店とヒートマップを閉じる。
+    // やり直しと途中保存の読み込みで使う
+    pub(crate) fn clear_aftermath(&mut self) {
+        self.shop.open = false;
+        self.heatmap.open = false;
+        self.camera.reset();
+        self.last_moments.clear();
+        self.deathcam = None;
+        self.dying = None;
+        self.grade = None;
+    }
+
+    // self.seed からランをやり直す
+    fn restart(&mut self) {
+        self.rng.reseed(self.seed);
+        self.difficulty = self.settings.difficulty;
+        self.game_speed = self.settings.game_speed;
+        // モードを変えたりやり直したりしたらデモは終わり（デモ自身は reset() の後で立て直す）
+        self.demo = false;
+        self.assist = self.settings.assist;
+        self.trap_pickups = self.settings.trap_pickups;
+        // 店の強化と機体はエンドレスの1人プレイだけ（リプレイの再生は start_playback で入れ直す）
+        let endless = !self.versus && self.daily.is_none() && self.fixed_seed.is_none() && self.practice.is_none() && self.tutorial.is_none() && self.director.is_none();
+        self.upgrades = if endless { self.shop.owned } else { Owned::default() };
+        self.ship = if endless && self.settings.ship.unlocked(self) { self.settings.ship } else { ShipKind::Classic };
+        self.recording = Replay::new(self.seed, self.difficulty);
+        self.recording.upgrades = self.upgrades;
+        self.recording.ship = self.ship;
+        self.ghost_run = GhostTrack::new();
+        self.spawn_players();
+        self.meteors.clear();
+        self.wells.clear();
+        self.well_timer = gravity::FIRST;
+        self.wind = Wind::new(self.spawn_config.wind_interval);
+        self.barriers.clear();
+        self.barrier_timer = barrier::FIRST;
+        self.lasers.clear();
+        self.laser_timer = laser::FIRST;
+        self.pickups.clear();
+        self.pickup_timer = pickup::FIRST;
+        self.traps = Traps::default();
+        self.next_id = 0;
+        self.popups.clear();
+        self.celebration = None;
+        self.particles.clear();
+        self.clear_aftermath();
+        self.stats = RunStats::default();
+        self.stage = 0;
+        self.weather.set(self.level.as_ref().map_or(stage::stage(0).weather, |l| l.waves[0].weather));
+        self.spawn_timer = 0.0;
+        self.capped_spawns = 0;
+        self.events.push(GameEvent::Start);
+        self.acc = 0.0;
+        self.score = 0.0;
+        self.speed = self.spawn_config.base_speed * self.difficulty.scale();
+        self.over = false;
+        self.new_record = false;
+        self.last_score_step = 0;
+        if let Some(start) = self.practice {
+            for p in &mut self.players { p.score = start; }
+            self.score = start;
+            self.speed = (self.spawn_config.base_speed + start * self.spawn_config.speed_ramp * self.ramp()) * self.difficulty.scale();
+            self.last_score_step = (start / SCORE_EVENT_STEP) as i64;
+        }
+    }
+
+    // ゲームオーバー時の記録（デイリーのベスト、ベストランのゴースト）
+    pub(crate) fn finish_run(&mut self) {
+        let score = self.score;
+        self.new_record = match &mut self.daily {
+            Some(d) => d.submit(score, &*self.storage),
+            None => self.high_score.submit(score, &*self.storage),
+        };
+        if self.new_record { self.toasts.push(self.lang.text(Msg::NewRecord).to_string(), 3.0); }
+        let p = &self.players[0];
+        self.heatmap.record((p.r.x + p.r.w * 0.5) / self.width, &*self.storage);
+        // 評価（モードごとの一番と比べる）
+        let g = Grade::of(&self.stats);
+        let best = grade::best(&*self.storage, self.run_mode());
+        let new_best = grade::submit(&*self.storage, self.run_mode(), g);
+        self.grade = Some(Stamp::new(g, best, new_best));
+        // 拾った宝石をコインにする
+        let gems = self.stats.gems;
+        if gems > 0 {
+            self.shop.earn(gems as u64, &*self.storage);
+            self.toasts.push(self.lang.fill(Msg::CoinsEarned, &[&gems]), 2.5);
+        }
+        // ベストや実績で新しく解除された機体
+        let mask = ships::unlocked_mask(self);
+        for (_, k) in SHIPS.iter().enumerate().filter(|&(i, _)| mask & !self.ships_unlocked & (1 << i) != 0) {
+            self.toasts.push(self.lang.fill(Msg::ShipUnlocked, &[&k.name()]), 3.0);
+        }
+        self.ships_unlocked = mask;
+        if self.budgets.history && score > self.best_ghost.as_ref().map_or(0.0, |g| g.score) {
+            let mut track = std::mem::replace(&mut self.ghost_run, GhostTrack::new());
+            track.score = score;
+            track.save(&*self.storage);
+            self.best_ghost = Some(track);
+        }
+    }
+
+    pub fn running(&self) -> bool { !(self.over || self.paused) }
+
+    // 記録・ゴースト・実績などを残す、組み込みの出し方の1人プレイか
+    pub fn solo(&self) -> bool {
+        !self.versus && self.playback.is_none() && self.director.is_none() && self.tutorial.is_none() && self.practice.is_none() && !self.demo
+    }
+
+    // ランキングや集計に付けるモード名
+    pub fn run_mode(&self) -> &'static str { if self.daily.is_some() { "daily" } else { "endless" } }
+
+    // 経過時間 dt をためて、固定ステップで update() する。
+    // 再生中は記録された dt と入力で（固定ステップ以前の記録もそのまま再現できる）
+    pub fn step(&mut self, dt: f64) {
+        self.sounds.clear();
+        self.tick_demo(dt);
+        // ゲーム速度を下げたら、動きもタイマーも同じ割合でゆっくりにする（固定ステップの刻みは同じなのでリプレイはそのまま）。
+        // 決着の直後も同じしくみでゆっくりにする
+        let dt = dt * self.game_speed * self.time_scale();
+        // パーティクルとカメラは見た目だけなので固定ステップに乗せず、ゲームオーバー後も動かす
+        if !self.paused {
+            self.particles.update(dt);
+            self.camera.update(dt);
+            // 評価のハンコは直前の再生を見終わってから押す
+            if self.deathcam.as_mut().is_some_and(|d| d.tick(dt)) { self.deathcam = None; }
+            if let (Some(s), None) = (&mut self.grade, &self.deathcam) { s.tick(dt); }
+        }
+        if !self.running() { return; }
+        self.acc += dt;
+        for _ in 0..MAX_STEPS {
+            let (step_dt, input) = match &self.playback {
+                Some(pb) => match pb.peek() {
+                    Some((d, left, right)) => (d, Some(Input { left, right })),
+                    None => break,
+                },
+                None => (FIXED_DT, None),
+            };
+            if self.acc < step_dt || !self.running() { return; }
+            if let Some(pb) = &mut self.playback { pb.advance(); }
+            if let Some(input) = input { self.players[0].input = input; }
+            self.acc -= step_dt;
+            self.update(step_dt);
+        }
+        self.acc = self.acc.min(FIXED_DT);
+    }
+
+    // 時間の流れる速さ（決着の直後だけ遅い）
+    pub fn time_scale(&self) -> f64 { if self.dying.is_some() { DEATH_SCALE } else { 1.0 } }
+
+    // 前の状態から今の状態までのどこを描くか（0..1）
+    pub fn alpha(&self) -> f64 { (self.acc / FIXED_DT).clamp(0.0, 1.0) }
+
+    // 補間の始点を今の位置に揃える（途中から再開したときなど）
+    pub fn settle(&mut self) {
+        for p in &mut self.players { p.prev_x = p.r.x; }
+        for m in &mut self.meteors { m.prev_y = m.r.y; }
+        for w in &mut self.wells { w.prev_y = w.y; }
+        for b in &mut self.barriers { b.prev_y = b.y; }
+        for p in &mut self.pickups { p.prev_y = p.y; }
+    }
+
+    fn update(&mut self, dt: f64) {
+        if !self.running() { return; }
+        let _span = profile::span(self.profile, "update");
+        let solo = self.solo();
+        if self.demo { self.players[0].input = bot::steer(self, 0); }
+        let input = self.players[0].input;
+        self.settle();
+        if solo && self.budgets.history {
+            self.recording.record(dt, input.left, input.right);
+        }
+
+        // システム（systems.rs）を順に回し、積まれた出来事に反応する
+        let first_event = self.events.len();
+        self.weather.tick(dt);
+        self.tick_wells(dt);
+        self.tick_wind(dt);
+        self.tick_barriers(dt);
+        self.tick_lasers(dt);
+        self.tick_pickups(dt);
+        self.move_players(dt);
+        self.spawn_meteors(dt);
+        self.move_meteors(dt);
+        self.emit_effects();
+        self.tick_animations(dt);
+        self.collide();
+        if solo && self.budgets.history { self.last_moments.record(dt, &self.players, &self.meteors); }
+        self.tick_tutorial(dt);
+        self.check_game_over(solo, dt);
+        for p in &mut self.players { p.combo.tick(dt); }
+        self.award_near_misses();
+        self.cleanup(dt);
+        self.update_score(dt);
+        self.update_stage();
+        self.react(first_event);
+        self.update_stats(dt, solo);
+    }
+
+}
@@ -0,0 +1,84 @@
+// パニックしたときのエラー画面。止まった canvas をそのままにせず、上にメッセージを描き、config の onError を
+// {message, location} で呼ぶ。パニックの後は wasm 全体がもう動かないので、登録してあるすべての canvas に出す。
+// WebGL で描いている canvas は 2d で描けないので、コンソールと onError だけ。worker 版は main に "crash" を送って呼んでもらう
+use crate::i18n::{Lang, Msg};
+use std::cell::RefCell;
+use std::panic::PanicHookInfo;
+use std::sync::Once;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, DedicatedWorkerGlobalScope};
+
+struct Target { canvas: JsValue, ctx: Option<CanvasRenderingContext2d>, lang: Lang, on_error: Option<js_sys::Function> }
+
+thread_local! {
+    static TARGETS: RefCell<Vec<Target>> = const { RefCell::new(Vec::new()) };
+}
+
+// 一度だけ。console_error_panic_hook が有効ならコンソールへの出力はそちらに任せる
+pub(crate) fn install() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| std::panic::set_hook(Box::new(|info| {
+        #[cfg(feature = "console_error_panic_hook")]
+        console_error_panic_hook::hook(info);
+        report(info);
+    })));
+}
+
+// 描画の準備ができてから呼ぶ（先に getContext("2d") すると WebGL が取れなくなる）。HTMLCanvasElement でも OffscreenCanvas でもよい
+pub(crate) fn register(canvas: &JsValue, lang: Lang, on_error: Option<js_sys::Function>) {
+    let ctx = js_sys::Reflect::get(canvas, &"getContext".into()).ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok())
+        .and_then(|f| f.call1(canvas, &"2d".into()).ok())
+        .and_then(|c| c.dyn_into::<CanvasRenderingContext2d>().ok());
+    TARGETS.with(|t| t.borrow_mut().push(Target { canvas: canvas.clone(), ctx, lang, on_error }));
+}
+
+pub(crate) fn unregister(canvas: &JsValue) {
+    TARGETS.with(|t| t.borrow_mut().retain(|t| t.canvas != *canvas));
+}
+
+fn report(info: &PanicHookInfo) {
+    let message = info.payload_as_str().unwrap_or("unknown panic").to_string();
+    let location = info.location().map_or_else(String::new, |l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    #[cfg(not(feature = "console_error_panic_hook"))]
+    web_sys::console::error_1(&format!("meteor_dodge panicked at {}: {}", location, message).into());
+    let detail = || crate::web::js_object(&[("message", message.as_str().into()), ("location", location.as_str().into())]);
+    // パニックが借用の途中で起きていることもある
+    let _ = TARGETS.with(|t| t.try_borrow().map(|targets| for t in targets.iter() {
+        draw(t, &message);
+        if let Some(f) = &t.on_error { let _ = f.call1(&JsValue::NULL, &detail()); }
+    }));
+    if let Some(scope) = js_sys::global().dyn_ref::<DedicatedWorkerGlobalScope>() {
+        let msg = detail();
+        let _ = js_sys::Reflect::set(&msg, &"t".into(), &"crash".into());
+        let _ = scope.post_message(&msg);
+    }
+}
+
+fn draw(t: &Target, message: &str) {
+    let Some(ctx) = &t.ctx else { return };
+    let size = |k: &str| js_sys::Reflect::get(&t.canvas, &k.into()).ok().and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let (w, h) = (size("width"), size("height"));
+    if w <= 0.0 || h <= 0.0 { return; }
+    let _ = ctx.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    ctx.set_global_alpha(1.0);
+    ctx.set_fill_style_str("rgba(10, 10, 20, 0.9)");
+    ctx.fill_rect(0.0, 0.0, w, h);
+    ctx.set_text_align("center");
+    ctx.set_text_baseline("middle");
+    let px = (w / 32.0).clamp(12.0, 28.0);
+    ctx.set_fill_style_str("#ff6b6b");
+    ctx.set_font(&format!("bold {}px sans-serif", px * 1.3));
+    let _ = ctx.fill_text(t.lang.text(Msg::Crashed), w * 0.5, h * 0.35);
+    ctx.set_fill_style_str("#ffffff");
+    ctx.set_font(&format!("{}px sans-serif", px));
+    let _ = ctx.fill_text(t.lang.text(Msg::CrashHint), w * 0.5, h * 0.35 + px * 2.0);
+    // メッセージは幅に合わせて折り返し、長すぎる分は省く
+    ctx.set_fill_style_str("#aaaaaa");
+    ctx.set_font(&format!("{}px monospace", px * 0.8));
+    let per_line = ((w * 0.9) / (px * 0.8 * 0.6)).max(10.0) as usize;
+    let chars: Vec<char> = message.chars().collect();
+    for (i, line) in chars.chunks(per_line).take(6).enumerate() {
+        let _ = ctx.fill_text(&line.iter().collect::<String>(), w * 0.5, h * 0.35 + px * (4.0 + i as f64 * 1.1));
+    }
+}
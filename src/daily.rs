@@ -0,0 +1,44 @@
+// デイリーチャレンジ：日付（UTC の "YYYY-MM-DD"、ブラウザでは web.rs の today()）から乱数シードを作り、全員が同じ隕石列に挑む
+use crate::storage::Storage;
+
+const BEST_KEY: &str = "meteor_dodge.daily_best";
+
+// 日付文字列 → シード（FNV-1a）。xorshift は 0 だと回らないので避ける
+pub(crate) fn seed_for(date: &str) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in date.bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    h.max(1)
+}
+
+// デイリーのベストは日付ごと（"日付:スコア" で保存し、日付が変われば 0 から）
+pub(crate) fn load_best(date: &str, storage: &dyn Storage) -> f64 {
+    storage.get(BEST_KEY)
+        .and_then(|v| {
+            let (d, score) = v.split_once(':')?;
+            if d == date { score.parse().ok() } else { None }
+        })
+        .unwrap_or(0.0)
+}
+
+pub(crate) fn save_best(date: &str, score: f64, storage: &dyn Storage) {
+    storage.set(BEST_KEY, &format!("{}:{}", date, score as i64));
+}
+
+pub(crate) struct DailyRun { pub date: String, pub seed: u64, pub best: f64 }
+
+impl DailyRun {
+    pub fn for_date(date: &str, storage: &dyn Storage) -> Self {
+        Self { seed: seed_for(date), best: load_best(date, storage), date: date.to_string() }
+    }
+
+    // 記録を更新したら保存して true
+    pub fn submit(&mut self, score: f64, storage: &dyn Storage) -> bool {
+        if score <= self.best { return false; }
+        self.best = score;
+        save_best(&self.date, score, storage);
+        true
+    }
+}
@@ -0,0 +1,70 @@
+// 落ちる直前の再生（デスカメラ）。記録に残るランでは最後の KEEP 秒の自機と隕石の位置を update() ごとに積んでおき、
+// ゲームオーバーになったらそれを SLOW 倍のゆっくりで見せてから結果画面を出す（当てた隕石は赤く囲む）。
+// 見た目だけで、乱数やスコアには関わらない。何かキーかタップで飛ばせる
+use crate::core::{Meteor, Player, Rect};
+use std::collections::VecDeque;
+
+// 残しておく秒数と、見せる速さ・最後の場面で止めておく秒数
+const KEEP: f64 = 3.0;
+const SLOW: f64 = 0.5;
+const HOLD: f64 = 0.6;
+
+// 1回の update() の後の場面（dt はその update() の刻み）
+#[derive(Default)]
+pub(crate) struct Shot { pub dt: f64, pub player: Rect, pub meteors: Vec<(u32, Rect)> }
+
+#[derive(Default)]
+pub(crate) struct Recorder {
+    shots: VecDeque<Shot>,
+    kept: f64,
+    // 1P に当たった隕石（ブラックホールやレーザーで落ちたら None）
+    pub fatal: Option<u32>,
+}
+
+impl Recorder {
+    pub fn clear(&mut self) {
+        self.shots.clear();
+        self.kept = 0.0;
+        self.fatal = None;
+    }
+
+    // 古い場面の Vec は使い回す
+    pub fn record(&mut self, dt: f64, players: &[Player], meteors: &[Meteor]) {
+        let mut shot = if self.kept >= KEEP { self.shots.pop_front().unwrap_or_default() } else { Shot::default() };
+        self.kept -= shot.dt;
+        shot.dt = dt;
+        shot.player = players.first().map_or(shot.player, |p| p.r);
+        shot.meteors.clear();
+        shot.meteors.extend(meteors.iter().map(|m| (m.id, m.r)));
+        self.kept += dt;
+        self.shots.push_back(shot);
+    }
+
+    // 積んだ場面を再生に回す（何も無ければ None）
+    pub fn take(&mut self) -> Option<DeathCam> {
+        if self.shots.is_empty() { return None; }
+        let cam = DeathCam { shots: std::mem::take(&mut self.shots).into(), fatal: self.fatal, t: 0.0 };
+        self.clear();
+        Some(cam)
+    }
+}
+
+pub(crate) struct DeathCam { shots: Vec<Shot>, pub fatal: Option<u32>, t: f64 }
+
+impl DeathCam {
+    // 見る側の時間を進める。見終わったら true
+    pub fn tick(&mut self, dt: f64) -> bool {
+        self.t += dt * SLOW;
+        self.t >= self.shots.iter().map(|s| s.dt).sum::<f64>() + HOLD * SLOW
+    }
+
+    // 今見せる場面
+    pub fn shot(&self) -> &Shot {
+        let mut t = self.t;
+        for s in &self.shots {
+            if t < s.dt { return s; }
+            t -= s.dt;
+        }
+        &self.shots[self.shots.len() - 1]
+    }
+}
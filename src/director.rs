@@ -0,0 +1,15 @@
+// 隕石の出し方の差し替え（独自のチャレンジや、台本どおりに出すチュートリアル・イベント用）。
+// 渡されていればスポーンの時間が来るたびに呼び、組み込みのランダムな出し方の代わりにする（ページの関数に任せるものは web.rs の JsDirector）
+use crate::collide::HitboxShape;
+use crate::core::Game;
+
+// 出す隕石1つ。x は 0..1（画面の左端〜右端）、書かなかったものは組み込みと同じく乱数と設定から決める
+pub(crate) struct Spawn { pub x: f64, pub size: Option<f64>, pub speed: Option<f64>, pub hitbox: Option<HitboxShape> }
+
+// 今出す隕石と、次に呼ぶまでの秒数（None ならいつもの間隔）
+#[derive(Default)]
+pub(crate) struct Plan { pub meteors: Vec<Spawn>, pub next: Option<f64> }
+
+pub(crate) trait SpawnDirector {
+    fn next(&mut self, g: &Game) -> Plan;
+}
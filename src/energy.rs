@@ -0,0 +1,45 @@
+// エネルギーのメーター（アシストモードと config の player.energy）。被弾すると隕石の大きさに応じて減り、
+// しばらく当たらなければ少しずつ戻る。残りより大きい被弾で空になると落ちる。
+// player.energy が 0（既定）でアシストでもなければメーターは無く、これまでどおり1回の被弾で終わり
+use serde::{Deserialize, Serialize};
+
+// アシストモードのメーターの大きさ（player.energy の方が大きければそちら）
+pub(crate) const ASSIST: f64 = 100.0;
+// 被弾してから戻り始めるまでの秒数と、戻る速さ（1秒あたり）
+const REGEN_DELAY: f64 = 2.0;
+const REGEN_RATE: f64 = 8.0;
+// 隕石の幅（画面の大きさで縮める前の px）あたりの減り方。ブラックホールとレーザーは決まった量
+const PER_PX: f64 = 1.5;
+pub(crate) const WELL: f64 = 60.0;
+pub(crate) const LASER: f64 = 45.0;
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Energy {
+    pub value: f64,
+    pub max: f64,
+    // 最後に被弾してからの秒数
+    since_hit: f64,
+}
+
+impl Energy {
+    pub fn new(max: f64) -> Self { Self { value: max, max, since_hit: REGEN_DELAY } }
+
+    pub fn enabled(&self) -> bool { self.max > 0.0 }
+
+    pub fn meteor_damage(width: f64) -> f64 { width * PER_PX }
+
+    // damage だけ減らす。まだ残っていれば true（持ちこたえた）
+    pub fn absorb(&mut self, damage: f64) -> bool {
+        if !self.enabled() { return false; }
+        self.since_hit = 0.0;
+        self.value = (self.value - damage).max(0.0);
+        self.value > 0.0
+    }
+
+    pub fn tick(&mut self, dt: f64) {
+        self.since_hit += dt;
+        if self.since_hit >= REGEN_DELAY { self.value = (self.value + REGEN_RATE * dt).min(self.max); }
+    }
+
+    pub fn ratio(&self) -> f64 { if self.enabled() { self.value / self.max } else { 0.0 } }
+}
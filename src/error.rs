@@ -0,0 +1,45 @@
+// 起動まわりで起きるエラー。JS には Error オブジェクト（message 付き）として渡す
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+#[derive(Debug)]
+pub(crate) enum GameError {
+    NoWindow,
+    NoDocument,
+    // セレクタに合う要素がない
+    CanvasNotFound(String),
+    // 見つかったが <canvas> ではない
+    NotACanvas(String),
+    // getContext("2d") が使えない（別の種類のコンテキストで取得済みなど）
+    NoContext2d,
+    // addEventListener / requestAnimationFrame などが例外を投げた
+    Dom(String),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::NoWindow => write!(f, "meteor_dodge: no window (not running in a browser?)"),
+            GameError::NoDocument => write!(f, "meteor_dodge: window has no document"),
+            GameError::CanvasNotFound(sel) => write!(f, "meteor_dodge: canvas not found: {}", sel),
+            GameError::NotACanvas(sel) => write!(f, "meteor_dodge: {} is not a <canvas> element", sel),
+            GameError::NoContext2d => write!(f, "meteor_dodge: could not get a 2d context from the canvas"),
+            GameError::Dom(msg) => write!(f, "meteor_dodge: {}", msg),
+        }
+    }
+}
+
+impl From<GameError> for JsValue {
+    fn from(e: GameError) -> JsValue { js_sys::Error::new(&e.to_string()).into() }
+}
+
+impl GameError {
+    // DOM API が投げた値を、わかる範囲でメッセージにする
+    pub fn dom(what: &str, err: JsValue) -> GameError {
+        let detail = js_sys::Reflect::get(&err, &"message".into()).ok()
+            .and_then(|m| m.as_string())
+            .or_else(|| err.as_string())
+            .unwrap_or_else(|| format!("{:?}", err));
+        GameError::Dom(format!("{} failed: {}", what, detail))
+    }
+}
@@ -0,0 +1,31 @@
+// ゲームパッド（標準配置）。毎フレーム navigator.getGamepads() を見て、押した・離したをキー名にして渡す。
+// 十字キー・左スティックは矢印、A は Enter、B は Backspace、Start は Escape（一時停止）。worker の中からは見えないのでメインスレッド版だけ
+use wasm_bindgen::JsCast;
+use web_sys::{window, Gamepad, GamepadButton};
+
+// (ボタン番号, キー名)
+const BUTTONS: [(u32, &str); 7] = [(12, "ArrowUp"), (13, "ArrowDown"), (14, "ArrowLeft"), (15, "ArrowRight"), (0, "Enter"), (1, "Backspace"), (9, "Escape")];
+// スティックを倒したとみなす量
+const DEAD_ZONE: f64 = 0.5;
+
+#[derive(Default)]
+pub(crate) struct Gamepads { held: [bool; BUTTONS.len()] }
+
+impl Gamepads {
+    // 前のフレームから変わったものを (キー名, 押した) で返す。つないでいるパッドはまとめて1つとして扱う
+    pub fn poll(&mut self) -> Vec<(&'static str, bool)> {
+        let Some(pads) = window().and_then(|w| w.navigator().get_gamepads().ok()) else { return Vec::new() };
+        let mut now = [false; BUTTONS.len()];
+        for pad in pads.iter().filter_map(|p| p.dyn_into::<Gamepad>().ok()).filter(|p| p.connected()) {
+            let buttons = pad.buttons();
+            let stick = pad.axes().get(0).as_f64().unwrap_or(0.0);
+            for (i, &(b, key)) in BUTTONS.iter().enumerate() {
+                let pressed = buttons.get(b).dyn_into::<GamepadButton>().is_ok_and(|b| b.pressed());
+                now[i] |= pressed || (key == "ArrowLeft" && stick < -DEAD_ZONE) || (key == "ArrowRight" && stick > DEAD_ZONE);
+            }
+        }
+        let changed = (0..BUTTONS.len()).filter(|&i| now[i] != self.held[i]).map(|i| (BUTTONS[i].1, now[i])).collect();
+        self.held = now;
+        changed
+    }
+}
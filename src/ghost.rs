@@ -0,0 +1,48 @@
+// ゴースト：ベストランのプレイヤー位置を一定間隔でサンプリングして保存し、次のランで重ねて表示する
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_INTERVAL: f64 = 0.1;
+const STORAGE_KEY: &str = "meteor_dodge.ghost";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct GhostTrack {
+    pub score: f64,
+    // 画面幅に対する割合（画面サイズが変わっても使えるように）
+    xs: Vec<f32>,
+}
+
+impl GhostTrack {
+    pub fn new() -> Self { Self { score: 0.0, xs: Vec::new() } }
+
+    // elapsed 秒までのサンプルを埋める（update() から毎フレーム呼ぶ）
+    pub fn record(&mut self, elapsed: f64, x_ratio: f64) {
+        while self.xs.len() as f64 * SAMPLE_INTERVAL <= elapsed {
+            self.xs.push(x_ratio as f32);
+        }
+    }
+
+    // t 秒時点の位置を前後のサンプルから線形補間（記録が終わっていたら None）
+    pub fn position_at(&self, t: f64) -> Option<f64> {
+        let f = t / SAMPLE_INTERVAL;
+        let i = f as usize;
+        let a = *self.xs.get(i)? as f64;
+        let b = self.xs.get(i + 1).map_or(a, |&b| b as f64);
+        Some(a + (b - a) * f.fract())
+    }
+
+    // "スコア;x,x,..."（x は千分率の整数）
+    pub fn load(storage: &dyn Storage) -> Option<Self> {
+        let v = storage.get(STORAGE_KEY)?;
+        let (score, xs) = v.split_once(';')?;
+        let xs = xs.split(',')
+            .map(|x| x.parse::<u16>().ok().map(|p| p as f32 / 1000.0))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { score: score.parse().ok()?, xs })
+    }
+
+    pub fn save(&self, storage: &dyn Storage) {
+        let xs: Vec<String> = self.xs.iter().map(|x| ((x * 1000.0).round() as u16).to_string()).collect();
+        storage.set(STORAGE_KEY, &format!("{};{}", self.score as i64, xs.join(",")));
+    }
+}
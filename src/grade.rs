@@ -0,0 +1,72 @@
+// ランの評価（S/A/B/C）。スコアを土台に近接回避で上げ、エネルギーで受けた被弾の量で下げる。
+// 記録に残るランのゲームオーバーで決め、結果画面にハンコのように押す。モード（run_mode）ごとの一番よい評価を
+// "endless=S;daily=A" の形で保存する
+use crate::core::RunStats;
+use crate::storage::Storage;
+
+const STORAGE_KEY: &str = "meteor_dodge.grades";
+// 近接回避1回で足す点と、受けたエネルギー1あたりで引く点
+const PER_NEAR_MISS: f64 = 50.0;
+const PER_DAMAGE: f64 = 5.0;
+// 押す動き：大きく傾いて出て、STAMP 秒で紙に落ちる
+const STAMP: f64 = 0.35;
+const STAMP_SCALE: f64 = 3.0;
+const STAMP_TILT: f64 = -0.5;
+// 止まったときの傾き（ラジアン）
+const REST_TILT: f64 = -0.2;
+
+// 悪い順（比べて大きい方がよい評価）
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub(crate) enum Grade { C, B, A, S }
+
+const GRADES: [Grade; 4] = [Grade::C, Grade::B, Grade::A, Grade::S];
+
+impl Grade {
+    pub fn letter(self) -> &'static str { match self { Grade::C => "C", Grade::B => "B", Grade::A => "A", Grade::S => "S" } }
+
+    fn parse(s: &str) -> Option<Self> { GRADES.into_iter().find(|g| g.letter() == s) }
+
+    // この点から上がその評価（C は下限なし）
+    fn floor(self) -> f64 { match self { Grade::C => f64::NEG_INFINITY, Grade::B => 1500.0, Grade::A => 4000.0, Grade::S => 8000.0 } }
+
+    pub fn of(stats: &RunStats) -> Self {
+        let points = stats.score + stats.near_misses as f64 * PER_NEAR_MISS - stats.damage * PER_DAMAGE;
+        GRADES.into_iter().rev().find(|g| points >= g.floor()).unwrap_or(Grade::C)
+    }
+
+    pub fn color(self) -> &'static str {
+        match self { Grade::S => "#ffd166", Grade::A => "#7be495", Grade::B => "#7fb8ff", Grade::C => "#c0c4cc" }
+    }
+}
+
+// モードごとの一番よい評価
+pub(crate) fn best(storage: &dyn Storage, mode: &str) -> Option<Grade> {
+    storage.get(STORAGE_KEY)?.split(';').filter_map(|e| e.split_once('=')).find(|(m, _)| *m == mode).and_then(|(_, g)| Grade::parse(g))
+}
+
+// 前の一番より良ければ保存して true
+pub(crate) fn submit(storage: &dyn Storage, mode: &str, grade: Grade) -> bool {
+    if best(storage, mode).is_some_and(|b| b >= grade) { return false; }
+    let saved = storage.get(STORAGE_KEY).unwrap_or_default();
+    let mut entries: Vec<String> = saved.split(';').filter(|e| !e.is_empty() && !e.starts_with(&format!("{}=", mode))).map(str::to_string).collect();
+    entries.push(format!("{}={}", mode, grade.letter()));
+    storage.set(STORAGE_KEY, &entries.join(";"));
+    true
+}
+
+// 結果画面に押す評価と、押してからの秒数（見た目だけ。ゲームオーバー後も動かす）
+#[derive(Clone, Copy)]
+pub(crate) struct Stamp { pub grade: Grade, pub best: Option<Grade>, pub new_best: bool, t: f64 }
+
+impl Stamp {
+    pub fn new(grade: Grade, best: Option<Grade>, new_best: bool) -> Self { Self { grade, best, new_best, t: 0.0 } }
+
+    pub fn tick(&mut self, dt: f64) { self.t += dt; }
+
+    fn landed(&self) -> f64 { (self.t / STAMP).min(1.0) }
+
+    // 大きさの倍率・傾き・不透明度
+    pub fn scale(&self) -> f64 { 1.0 + (STAMP_SCALE - 1.0) * (1.0 - self.landed()).powi(2) }
+    pub fn tilt(&self) -> f64 { REST_TILT + (STAMP_TILT - REST_TILT) * (1.0 - self.landed()) }
+    pub fn alpha(&self) -> f64 { self.landed().sqrt() }
+}
@@ -0,0 +1,64 @@
+// ブラックホール（まれに出る障害物）。ゆっくり落ちながら、自機と近くの隕石を距離の2乗に反比例する力で引き寄せる。
+// 中心に触れると被弾、吸い込まれた隕石は消える。引く力には上限があり、逆へ動き続ければ自機は抜け出せる
+use crate::collide::{Circle, Collider};
+use serde::{Deserialize, Serialize};
+
+// 中心の半径と、力が届く距離
+pub(crate) const CORE: f64 = 14.0;
+pub(crate) const REACH: f64 = 240.0;
+// 落ちる速さ（px/秒）と、見た目の渦の回る速さ（ラジアン/秒）
+const FALL: f64 = 35.0;
+const SPIN: f64 = 2.5;
+// 加速度 = STRENGTH / 距離^2（px/秒^2）。近いと発散するので MAX_PULL で打ち止め
+const STRENGTH: f64 = 6.0e6;
+const MAX_PULL: f64 = 450.0;
+// 自機の流され方の減衰（1/秒）。引かれ続けても MAX_PULL / DRAG = 150px/秒 までで、自機の速さより遅い
+pub(crate) const DRAG: f64 = 3.0;
+// 引かれても隕石はこれより遅く落ちない（画面の中に留まり続けないように）
+pub(crate) const MIN_FALL: f64 = 30.0;
+// ランの始めから最初の1つまでの秒数
+pub(crate) const FIRST: f64 = 30.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Well {
+    pub x: f64,
+    pub y: f64,
+    // 直前の update() 前の y（描画の補間用）
+    #[serde(skip)]
+    pub prev_y: f64,
+    // 渦の向き（見た目だけ）
+    #[serde(default)]
+    pub angle: f64,
+}
+
+impl Well {
+    // 画面の上端の少し外から
+    pub fn new(x: f64) -> Self { Self { x, y: -CORE * 2.0, prev_y: -CORE * 2.0, angle: 0.0 } }
+
+    pub fn fall(&mut self, dt: f64) {
+        self.prev_y = self.y;
+        self.y += FALL * dt;
+        self.angle += SPIN * dt;
+    }
+
+    pub fn core(&self) -> Collider { Collider::Circle(Circle { x: self.x, y: self.y, r: CORE }) }
+
+    // 中心に吸い込まれる位置か
+    pub fn swallows(&self, x: f64, y: f64) -> bool { (self.x - x).hypot(self.y - y) < CORE }
+
+    // (x, y) にかかる加速度 (ax, ay)。届かなければ 0
+    pub fn pull(&self, x: f64, y: f64) -> (f64, f64) {
+        let (dx, dy) = (self.x - x, self.y - y);
+        let d2 = dx * dx + dy * dy;
+        if !(1e-6..=REACH * REACH).contains(&d2) { return (0.0, 0.0); }
+        let (d, a) = (d2.sqrt(), (STRENGTH / d2).min(MAX_PULL));
+        (a * dx / d, a * dy / d)
+    }
+
+    pub fn gone(&self, height: f64) -> bool { self.y - REACH > height }
+}
+
+// いくつかのブラックホールの力を足し合わせる
+pub(crate) fn total_pull(wells: &[Well], x: f64, y: f64) -> (f64, f64) {
+    wells.iter().map(|w| w.pull(x, y)).fold((0.0, 0.0), |(ax, ay), (bx, by)| (ax + bx, ay + by))
+}
@@ -0,0 +1,38 @@
+// 当たり判定の下調べ（空間ハッシュ）。矩形を CELL 四方のマスに振り分け、問い合わせた矩形と同じマスにあるものだけを候補にする
+use crate::core::Rect;
+use std::collections::HashMap;
+
+// マスの大きさ（隕石の大きさ程度）
+const CELL: f64 = 64.0;
+
+#[derive(Default)]
+pub(crate) struct Grid {
+    // マスごとの番号の一覧。clear() では中身だけ空にして、確保した分は使い回す
+    cells: HashMap<(i32, i32), Vec<u32>>,
+}
+
+fn cells_of(r: &Rect) -> impl Iterator<Item = (i32, i32)> {
+    let (x0, x1) = ((r.x / CELL).floor() as i32, ((r.x + r.w) / CELL).floor() as i32);
+    let (y0, y1) = ((r.y / CELL).floor() as i32, ((r.y + r.h) / CELL).floor() as i32);
+    (x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+}
+
+impl Grid {
+    pub fn clear(&mut self) {
+        for v in self.cells.values_mut() { v.clear(); }
+    }
+
+    pub fn insert(&mut self, i: usize, r: &Rect) {
+        for c in cells_of(r) { self.cells.entry(c).or_default().push(i as u32); }
+    }
+
+    // r と同じマスにあるものの番号を out に入れる（重なっているとは限らないので、最後は呼ぶ側で確かめる）
+    pub fn query(&self, r: &Rect, out: &mut Vec<u32>) {
+        out.clear();
+        for c in cells_of(r) {
+            if let Some(v) = self.cells.get(&c) { out.extend_from_slice(v); }
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+}
@@ -0,0 +1,26 @@
+// 振動（スマホ向け）。navigator.vibrate が無いブラウザや worker の中では何もしない
+use crate::core::Sound;
+use web_sys::window;
+
+// 振動の長さ（ms）：振動・休み・振動…の順。隕石の出現は多すぎるので揺らさない
+fn pattern(s: Sound) -> &'static [u32] {
+    match s {
+        Sound::Spawn => &[],
+        Sound::NearMiss => &[15],
+        Sound::Pickup => &[20],
+        Sound::Trap => &[60, 40, 60],
+        Sound::Milestone => &[30, 30, 30],
+        Sound::Hit => &[80, 40, 120],
+        Sound::GameOver => &[200, 80, 200, 80, 400],
+    }
+}
+
+pub(crate) fn vibrate(s: Sound) {
+    let p = pattern(s);
+    if p.is_empty() { return; }
+    let Some(nav) = window().map(|w| w.navigator()) else { return };
+    // vibrate を持たない（iOS の Safari など）なら呼ばない
+    if !js_sys::Reflect::has(&nav, &"vibrate".into()).unwrap_or(false) { return; }
+    let arr: js_sys::Array = p.iter().map(|&ms| wasm_bindgen::JsValue::from(ms)).collect();
+    nav.vibrate_with_pattern(&arr);
+}
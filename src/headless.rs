@@ -0,0 +1,212 @@
+// ブラウザなしでゲームを回す（ボット、バランス調整のスクリプト、サーバー側でのリプレイ検証など）。
+// 保存先はメモリ上なので、ベストスコアや実績はこのインスタンスの間だけ
+use crate::core::{Game, GameEvent, Input, FIXED_DT};
+use crate::savestate::SavedRun;
+use crate::simconfig::SimConfig;
+use crate::storage::MemoryStorage;
+use serde::Serialize;
+
+pub use crate::rng::{Rng, XorShift};
+
+// ブラウザ版の既定の canvas と同じ大きさ
+const DEFAULT_WIDTH: f64 = 640.0;
+const DEFAULT_HEIGHT: f64 = 400.0;
+
+// 固定ステップ1回分の秒数（step にこれを渡すと update() がちょうど1回進む）
+pub const FIXED_STEP: f64 = FIXED_DT;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeadlessInput { pub left: bool, pub right: bool }
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PlayerSnapshot { pub x: f64, pub y: f64, pub w: f64, pub h: f64, pub score: f64, pub alive: bool, pub combo: u32 }
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MeteorSnapshot { pub x: f64, pub y: f64, pub w: f64, pub h: f64, pub vy: f64 }
+
+// ある時点の盤面まるごと
+#[derive(Clone, Debug, Serialize)]
+pub struct Snapshot {
+    pub seed: u64,
+    pub width: f64,
+    pub height: f64,
+    pub elapsed: f64,
+    pub score: f64,
+    pub speed: f64,
+    pub over: bool,
+    // 1始まり
+    pub stage: u32,
+    pub weather: &'static str,
+    pub dodged: u32,
+    pub near_misses: u32,
+    pub players: Vec<PlayerSnapshot>,
+    pub meteors: Vec<MeteorSnapshot>,
+    // 1P を落としたもの（"meteor" / "well" / "laser"。まだ落ちていなければ None）
+    pub cause: Option<&'static str>,
+    // 直前の step() で起きたイベントの名前（GameEvent::name。"start" / "spawn" / "hit" / "gameover" / "score" / "nearmiss" / "wave" / "achievement" / "powerup" / "milestone"）
+    pub events: Vec<&'static str>,
+}
+
+pub struct HeadlessGame { game: Game, events: Vec<&'static str>, cause: Option<&'static str> }
+
+impl HeadlessGame {
+    pub fn new(seed: u64) -> Self { Self::with_size(seed, DEFAULT_WIDTH, DEFAULT_HEIGHT) }
+
+    pub fn with_size(seed: u64, width: f64, height: f64) -> Self { Self::with_rng(seed, width, height, Box::new(XorShift::new(seed))) }
+
+    // 乱数源を差し替える（決まった列を返す Rng でテストするときなど）。rng は最初に seed で reseed される
+    pub fn with_rng(seed: u64, width: f64, height: f64, rng: Box<dyn Rng>) -> Self {
+        // 作った時点でランは始まっている（step() を呼ばない間にデモへ切り替わらないよう attract は切る）
+        let config = SimConfig { seed: Some(seed), attract: false, ..SimConfig::default() };
+        let game = Game::new(width, height, &config, Box::new(MemoryStorage::default()), rng);
+        Self { game, events: Vec::new(), cause: None }
+    }
+
+    // 入力を与えて dt 秒進める（ゲームオーバー後は何もしない）
+    pub fn step(&mut self, dt: f64, input: HeadlessInput) -> Snapshot {
+        self.game.players[0].input = Input { left: input.left, right: input.right };
+        self.game.step(dt);
+        self.events.clear();
+        for e in self.game.events.drain(..) {
+            match e {
+                GameEvent::Start => self.cause = None,
+                GameEvent::Collision { player: 1, cause, .. } => self.cause = Some(cause),
+                _ => {}
+            }
+            self.events.push(e.name());
+        }
+        self.snapshot()
+    }
+
+    // 次のランへ（シードは前のランの乱数列から）
+    pub fn reset(&mut self) {
+        self.game.reset();
+        self.events.clear();
+        self.cause = None;
+    }
+
+    pub fn is_over(&self) -> bool { self.game.over }
+
+    // 進行中のランを JSON で保存する（乱数の状態も含むので、load して同じ入力を与えれば同じ続きになる）
+    pub fn save(&self) -> String { SavedRun::capture(&self.game).to_json() }
+
+    pub fn load(&mut self, state: &str) -> Result<(), String> {
+        SavedRun::from_json(state)?.apply(&mut self.game);
+        self.events.clear();
+        self.cause = None;
+        Ok(())
+    }
+
+    // 盤面のハッシュ（FNV-1a）。同じシードと同じ入力なら一致するので、リプレイの検証や決定性のテストに使う
+    pub fn state_hash(&self) -> u64 {
+        let g = &self.game;
+        let mut h: u64 = 0xcbf29ce484222325;
+        let mut mix = |v: u64| for b in v.to_le_bytes() { h = (h ^ b as u64).wrapping_mul(0x100000001b3); };
+        mix(g.rng.state());
+        for v in [g.score, g.speed, g.spawn_timer, g.well_timer, g.barrier_timer, g.laser_timer, g.pickup_timer, g.stats.elapsed] { mix(v.to_bits()); }
+        mix(g.stage as u64);
+        for p in &g.players { mix(p.r.x.to_bits()); mix(p.score.to_bits()); mix(p.alive as u64); }
+        for m in &g.meteors { mix(m.id as u64); mix(m.r.x.to_bits()); mix(m.r.y.to_bits()); mix(m.vy.to_bits()); }
+        for w in &g.wells { mix(w.x.to_bits()); mix(w.y.to_bits()); }
+        for p in &g.pickups { mix(p.x.to_bits()); mix(p.y.to_bits()); }
+        for l in &g.lasers { mix(l.x.to_bits()); mix(l.t.to_bits()); }
+        for b in &g.barriers { mix(b.y.to_bits()); mix(b.gap_x.to_bits()); }
+        h
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let g = &self.game;
+        Snapshot {
+            seed: g.seed,
+            width: g.width,
+            height: g.height,
+            elapsed: g.stats.elapsed,
+            score: g.score,
+            speed: g.speed,
+            over: g.over,
+            stage: g.stage as u32 + 1,
+            weather: g.weather.kind.label(),
+            dodged: g.stats.dodged,
+            near_misses: g.stats.near_misses,
+            players: g.players.iter().map(|p| PlayerSnapshot {
+                x: p.r.x, y: p.r.y, w: p.r.w, h: p.r.h, score: p.score, alive: p.alive, combo: p.combo.count,
+            }).collect(),
+            meteors: g.meteors.iter().map(|m| MeteorSnapshot { x: m.r.x, y: m.r.y, w: m.r.w, h: m.r.h, vy: m.vy }).collect(),
+            events: self.events.clone(),
+            cause: self.cause,
+        }
+    }
+}
+
+// 強化学習向けの gym 風の環境：reset(seed) で始め、step(action) ごとに (観測, 報酬, 終わったか) を返す。
+// 1回の step は固定ステップ frame_skip 回分（既定 4 回、1秒に 30 回の判断）。報酬は増えたスコア / 100
+// （生き残った1秒あたり 1、ニアミスのボーナス込み）で、被弾した step だけ DEATH_PENALTY を引く
+pub const OBS_METEORS: usize = 5;
+// [自機の中心 x, 落下速度, (隕石の dx, dy, 幅, 速度) × OBS_METEORS]
+pub const OBS_LEN: usize = 2 + OBS_METEORS * 4;
+pub const DEATH_PENALTY: f64 = 5.0;
+const DEFAULT_FRAME_SKIP: u32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action { Stay, Left, Right }
+
+impl Action {
+    // 0: そのまま / 1: 左 / 2: 右
+    pub fn from_index(i: usize) -> Option<Self> { [Action::Stay, Action::Left, Action::Right].get(i).copied() }
+}
+
+#[derive(Clone, Debug)]
+pub struct StepResult { pub observation: Vec<f64>, pub reward: f64, pub done: bool }
+
+pub struct DodgeEnv { game: HeadlessGame, frame_skip: u32 }
+
+impl DodgeEnv {
+    pub fn new() -> Self { Self::with_frame_skip(DEFAULT_FRAME_SKIP) }
+
+    pub fn with_frame_skip(frame_skip: u32) -> Self { Self { game: HeadlessGame::new(0), frame_skip: frame_skip.clamp(1, 8) } }
+
+    // seed のランを最初から（同じ seed と同じ行動列なら毎回同じ結果になる）
+    pub fn reset(&mut self, seed: u64) -> Vec<f64> {
+        self.game = HeadlessGame::new(seed);
+        self.observation()
+    }
+
+    pub fn step(&mut self, action: Action) -> StepResult {
+        if self.game.is_over() || !self.game.game.players[0].alive { return StepResult { observation: self.observation(), reward: 0.0, done: true }; }
+        let before = self.game.game.score;
+        let input = HeadlessInput { left: action == Action::Left, right: action == Action::Right };
+        self.game.step(FIXED_DT * self.frame_skip as f64, input);
+        // 決着の後のゆっくりは待たずに、落ちた step で終わりにする
+        let done = self.game.is_over() || !self.game.game.players[0].alive;
+        let reward = (self.game.game.score - before) / 100.0 - if done { DEATH_PENALTY } else { 0.0 };
+        StepResult { observation: self.observation(), reward, done }
+    }
+
+    // 画面の幅・高さで割って 0〜1 前後にそろえる。隕石は自機より下に抜けていないものを近い順に、足りない分は遠く（dy = 1）で埋める
+    pub fn observation(&self) -> Vec<f64> {
+        let g = &self.game.game;
+        let p = &g.players[0].r;
+        let (px, py) = (p.x + p.w * 0.5, p.y + p.h * 0.5);
+        let mut near: Vec<(f64, [f64; 4])> = g.meteors.iter()
+            .filter(|m| m.r.y < p.y + p.h)
+            .map(|m| {
+                let (dx, dy) = ((m.r.x + m.r.w * 0.5 - px) / g.width, (py - m.r.y - m.r.h * 0.5) / g.height);
+                (dx * dx + dy * dy, [dx, dy, m.r.w / g.width, m.vy / g.height])
+            })
+            .collect();
+        near.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut obs = Vec::with_capacity(OBS_LEN);
+        obs.extend([px / g.width, g.speed / g.height]);
+        for i in 0..OBS_METEORS {
+            obs.extend(near.get(i).map_or([0.0, 1.0, 0.0, 0.0], |n| n.1));
+        }
+        obs
+    }
+
+    // 中のゲーム（スナップショットやスコアを見るとき）
+    pub fn game(&self) -> &HeadlessGame { &self.game }
+}
+
+impl Default for DodgeEnv {
+    fn default() -> Self { Self::new() }
+}
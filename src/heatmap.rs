@@ -0,0 +1,62 @@
+// 落ちた位置の分布（デスヒートマップ）。記録に残るランで落ちたら 1P の中心の x を画面幅の割合で BINS 本の列に数え、
+// セッションをまたいで保存する（"版;n0,n1,..."、列の数が違えば捨てる）。ゲームオーバー画面の H で開き、
+// 列ごとの多さを色（render.rs の heat_color）で重ねて、よく落ちる場所の癖を見せる
+use crate::core::Game;
+use crate::storage::Storage;
+
+const STORAGE_KEY: &str = "meteor_dodge.deaths";
+const VERSION: u32 = 1;
+pub(crate) const BINS: usize = 24;
+
+#[derive(Default)]
+pub(crate) struct Heatmap {
+    pub bins: [u32; BINS],
+    // ゲームオーバー画面の上に開いているか
+    pub open: bool,
+}
+
+impl Heatmap {
+    pub fn load(storage: &dyn Storage) -> Self {
+        let parse = |v: String| -> Option<Self> {
+            let (version, counts) = v.split_once(';')?;
+            if !(1..=VERSION).contains(&version.parse().ok()?) { return None; }
+            let counts: Vec<u32> = counts.split(',').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+            Some(Self { bins: counts.try_into().ok()?, open: false })
+        };
+        storage.get(STORAGE_KEY).and_then(parse).unwrap_or_default()
+    }
+
+    fn save(&self, storage: &dyn Storage) {
+        let counts: Vec<String> = self.bins.iter().map(u32::to_string).collect();
+        storage.set(STORAGE_KEY, &format!("{};{}", VERSION, counts.join(",")));
+    }
+
+    // x は画面幅に対する割合（0..1）
+    pub fn record(&mut self, x: f64, storage: &dyn Storage) {
+        let i = ((x.clamp(0.0, 1.0) * BINS as f64) as usize).min(BINS - 1);
+        self.bins[i] = self.bins[i].saturating_add(1);
+        self.save(storage);
+    }
+
+    pub fn total(&self) -> u32 { self.bins.iter().sum() }
+
+    // 列の濃さ（0..1、一番多い列が 1）
+    pub fn level(&self, i: usize) -> f64 {
+        let max = self.bins.iter().copied().max().unwrap_or(0);
+        if max == 0 { 0.0 } else { self.bins[i] as f64 / max as f64 }
+    }
+}
+
+// ゲームオーバー画面の H で開く・閉じる（店とは同時に開かない。対戦の結果画面では開かない）
+pub(crate) fn toggle(g: &mut Game) {
+    if !g.over || g.versus { return; }
+    g.heatmap.open = !g.heatmap.open;
+    g.shop.open = false;
+}
+
+// 開いている間のキー。開いていれば他のキーは効かせない
+pub(crate) fn key(g: &mut Game, key: &str) -> bool {
+    if !g.heatmap.open { return false; }
+    if matches!(key, "Escape" | "Backspace" | "Enter" | " " | "h" | "H") { g.heatmap.open = false; }
+    true
+}
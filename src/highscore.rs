@@ -0,0 +1,23 @@
+// ベストスコア（エンドレスの1人プレイ）。保存できない環境ではそのセッション内だけ覚える
+use crate::storage::Storage;
+
+const STORAGE_KEY: &str = "meteor_dodge.best";
+
+pub(crate) struct HighScore { pub best: f64 }
+
+impl HighScore {
+    pub fn load(storage: &dyn Storage) -> Self {
+        let best = storage.get(STORAGE_KEY)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.0);
+        Self { best }
+    }
+
+    // 記録を更新したら保存して true
+    pub fn submit(&mut self, score: f64, storage: &dyn Storage) -> bool {
+        if score <= self.best { return false; }
+        self.best = score;
+        storage.set(STORAGE_KEY, &(score as i64).to_string());
+        true
+    }
+}
@@ -0,0 +1,141 @@
+// HUD（スコア・ベスト・コンボ・状態の行）。画面の角を基準に行を積み、文字の大きさは DPR に合わせて丸める。
+// エネルギーのメーターのような残りを表すものもここに並べる
+use crate::core::{Orientation, COMBO_WINDOW};
+use crate::i18n::Msg;
+use crate::render::{self, Frame, MONO};
+use web_sys::CanvasRenderingContext2d;
+
+const MARGIN: f64 = 10.0;
+const FONT_PX: f64 = 16.0;
+const SMALL_PX: f64 = 12.0;
+// コンボのゲージの長さ
+const GAUGE_W: f64 = 170.0;
+// エネルギーのゲージを赤くする残りの割合
+const LOW_ENERGY: f64 = 0.3;
+
+#[derive(Clone, Copy)]
+enum Anchor { TopLeft, TopRight, BottomLeft }
+
+pub(crate) struct Hud<'a> {
+    c: &'a CanvasRenderingContext2d,
+    f: &'a Frame<'a>,
+    dpr: f64,
+    // 角ごとに次に置く行の下端（上の角は下へ、下の角は上へ進む）
+    top_left: f64,
+    top_right: f64,
+    bottom_left: f64,
+}
+
+impl<'a> Hud<'a> {
+    pub fn new(c: &'a CanvasRenderingContext2d, f: &'a Frame<'a>, dpr: f64) -> Self {
+        Self { c, f, dpr, top_left: MARGIN, top_right: MARGIN, bottom_left: f.game.height - MARGIN }
+    }
+
+    // 実際の文字の大きさ：縦長の狭い画面では少し小さく、高コントラストでは大きく、実ピクセルの整数にそろえてにじまないようにする
+    fn px(&self, px: f64) -> f64 {
+        let g = self.f.game;
+        let px = px * g.size_scale().max(0.75) * if g.settings.high_contrast { 1.25 } else { 1.0 };
+        (px * self.dpr).round() / self.dpr
+    }
+
+    // その角で h だけ場所を取り、書く行の下端（文字のベースライン）を返す
+    fn advance(&mut self, anchor: Anchor, h: f64) -> f64 {
+        match anchor {
+            Anchor::TopLeft => { self.top_left += h; self.top_left }
+            Anchor::TopRight => { self.top_right += h; self.top_right }
+            Anchor::BottomLeft => { let y = self.bottom_left; self.bottom_left -= h; y }
+        }
+    }
+
+    // 1行書いて、その角の次の行へ進む
+    fn line(&mut self, anchor: Anchor, px: f64, text: &str) -> (f64, f64) {
+        let c = self.c;
+        let px = self.px(px);
+        c.set_font(&format!("{}px {}", px, self.f.family(MONO)));
+        let x = if matches!(anchor, Anchor::TopRight) { self.f.game.width - MARGIN } else { MARGIN };
+        let y = self.advance(anchor, (px * 1.25).round());
+        c.set_text_align(if matches!(anchor, Anchor::TopRight) { "right" } else { "start" });
+        let _ = c.fill_text(text, x, y);
+        c.set_text_align("start");
+        (x, y)
+    }
+
+    // 見出しとゲージ。残りが少ないと赤くする
+    fn energy_bar(&mut self, anchor: Anchor, ratio: f64) {
+        let (f, c) = (self.f, self.c);
+        let (x, y) = self.line(anchor, SMALL_PX, f.game.lang.text(Msg::Energy));
+        let gx = if matches!(anchor, Anchor::TopRight) { x - GAUGE_W } else { x };
+        c.set_fill_style_str(&f.theme.toast);
+        c.fill_rect(gx, y + 4.0, GAUGE_W, 6.0);
+        c.set_fill_style_str(if ratio < LOW_ENERGY { "#ff5a5a" } else { &f.theme.accent });
+        let w = GAUGE_W * ratio.clamp(0.0, 1.0);
+        c.fill_rect(if matches!(anchor, Anchor::TopRight) { x - w } else { gx }, y + 4.0, w, 6.0);
+        c.set_fill_style_str(&f.theme.hud);
+        self.advance(anchor, 12.0);
+    }
+
+    pub fn draw(mut self) {
+        let f = self.f;
+        let g = f.game;
+        let c = self.c;
+        c.set_fill_style_str(&f.theme.hud);
+
+        // スコア（対戦は左右に1人ずつ）
+        if g.versus {
+            for (i, p) in g.players.iter().enumerate() {
+                c.set_fill_style_str(f.player_color(i));
+                let combo = if p.combo.count > 1 { format!(" x{}", p.combo.count) } else { String::new() };
+                let anchor = if i == 0 { Anchor::TopLeft } else { Anchor::TopRight };
+                self.line(anchor, FONT_PX, &format!("P{}: {:04}{}", i + 1, p.score as i32, combo));
+            }
+            c.set_fill_style_str(&f.theme.hud);
+        } else if let Some(d) = &g.daily {
+            self.line(Anchor::TopLeft, FONT_PX, &g.lang.fill(Msg::Score, &[&format!("{:04}", g.score as i32)]));
+            self.line(Anchor::TopLeft, FONT_PX, &g.lang.fill(Msg::DailyBest, &[&d.date, &format!("{:04}", d.best as i32)]));
+        } else {
+            self.line(Anchor::TopLeft, FONT_PX, &g.lang.fill(Msg::ScoreBest, &[&format!("{:04}", g.score as i32), &format!("{:04}", g.high_score.best as i32)]));
+        }
+
+        // コンボ（縦長では右上に収まらないので左の列に）
+        let combo = &g.players[0].combo;
+        if !g.versus && combo.count > 1 {
+            let anchor = if g.orientation == Orientation::Landscape { Anchor::TopRight } else { Anchor::TopLeft };
+            c.set_fill_style_str(&f.theme.accent);
+            let (x, y) = self.line(anchor, FONT_PX, &g.lang.fill(Msg::Combo, &[&combo.count, &format!("{:.2}", combo.multiplier())]));
+            // 残り時間のゲージ
+            let gx = if matches!(anchor, Anchor::TopRight) { x - GAUGE_W } else { x };
+            c.fill_rect(gx, y + 6.0, GAUGE_W * (combo.timer / COMBO_WINDOW), 3.0);
+            self.advance(anchor, 8.0);
+            c.set_fill_style_str(&f.theme.hud);
+        }
+
+        // エネルギー（メーターがあるときだけ。対戦はスコアと同じ側に1人ずつ）
+        for (i, p) in g.players.iter().enumerate().filter(|(_, p)| p.energy.enabled()) {
+            let anchor = if i == 0 { Anchor::TopLeft } else { Anchor::TopRight };
+            self.energy_bar(anchor, p.energy.ratio());
+        }
+
+        if g.playback.is_some() { self.line(Anchor::TopLeft, FONT_PX, g.lang.text(Msg::Replay)); }
+        if g.demo { self.line(Anchor::TopLeft, FONT_PX, g.lang.text(Msg::Demo)); }
+        if let Some(s) = g.practice { self.line(Anchor::TopLeft, FONT_PX, &g.lang.fill(Msg::Practice, &[&(s as i64)])); }
+        if let Some(net) = f.net {
+            self.line(Anchor::TopLeft, FONT_PX, &g.lang.fill(Msg::Online, &[&net.state().label(), &net.remotes().len()]));
+        }
+
+        // F3：FPS と直近1秒のフレーム時間、描いているものの数
+        if g.show_perf {
+            let p = f.perf;
+            self.line(Anchor::TopLeft, SMALL_PX, &format!("FPS: {}  avg {:.1}ms  worst {:.1}ms", p.fps(), p.average_ms(), p.worst_ms()));
+            self.line(Anchor::TopLeft, SMALL_PX, &format!("meteors {}  particles {}  popups {}", g.meteors.len(), g.particles.iter().count(), g.popups.len()));
+        }
+
+        // 画像の読み込み中（左下）
+        let (done, total) = f.assets.progress();
+        if done < total { self.line(Anchor::BottomLeft, FONT_PX, &g.lang.fill(Msg::Loading, &[&done, &total])); }
+
+        // デバッグ：自機の位置・画面サイズ・スポーンの間隔など（左下）
+        if f.config.debug {
+            for line in render::debug_lines(g) { self.line(Anchor::BottomLeft, SMALL_PX, &line); }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// 画面と読み上げに出す文言の表（英語・日本語）。言語は config の language、無ければ端末の言語（detect）から決める。
+// 文言を足すときは Msg に1つ足して、text() に両方の言語を書く（{} は fill() で順に埋める）
+use crate::weather::Weather;
+use serde::Deserialize;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Lang { #[default] En, Ja }
+
+#[derive(Clone, Copy)]
+pub(crate) enum Msg {
+    GameOver, NewRecord, Retry, ToDaily, ToEndless, ToVersus, ToTutorial, PracticeFrom, PracticeEnd, Paused,
+    Rotate, PlaysIn, Landscape, Portrait,
+    Top10, Sending, LeaderboardDown,
+    PlayerWins, Draw, Rematch, SoloPlay,
+    Score, ScoreBest, DailyBest, Combo, Replay, Practice, Online, Loading, Tutorial,
+    Stage, Achievement, TryAgain, Close, ChartScore, ChartSpeed,
+    StageLowOrbit, StageDustBelt, StageOuterRing, StageIonField,
+    AchSurvivor, AchStatue, AchDodger, AchDaredevil,
+    MenuResume, MenuVolume, MenuTheme, MenuControls, MenuReducedMotion, MenuGameSpeed, MenuAssist, On, Off, MenuHint, AssistSpeed, Assist, Energy, Demo, Crashed, CrashHint, BlackHole, Gust, MenuTraps, TrapInvert, TrapFog, TrapRush,
+    Shop, ShopEnergy, ShopSpeed, ShopPrice, ShopMaxed, ShopClose, ShopBought, ShopHint, ToShop, CoinsEarned, MenuShip, ShipUnlocked, GemBonus, NetStatus, GradeBest, GradeNewBest, ToHeatmap, Heatmap, HeatmapEmpty, HeatmapHint, LastMoments,
+    LessonMove, LessonDodge, LessonNearMiss, LessonDone,
+    SayStart, SayScore, SayWinner, SayFinalRecord, SayFinal, SayStage, SayAchievement,
+}
+
+impl Lang {
+    // "ja" / "ja-JP" など。先頭の言語だけ見る
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.split(['-', '_']).next()?.to_ascii_lowercase().as_str() {
+            "en" => Some(Lang::En),
+            "ja" => Some(Lang::Ja),
+            _ => None,
+        }
+    }
+
+    // 端末の言語（ブラウザでは navigator.language）から。分からない・対応していなければ英語
+    pub fn detect(locale: Option<&str>) -> Self { locale.and_then(Self::parse).unwrap_or_default() }
+
+    pub fn text(self, m: Msg) -> &'static str {
+        let (en, ja) = match m {
+            Msg::GameOver => ("GAME OVER", "ゲームオーバー"),
+            Msg::NewRecord => ("NEW RECORD!", "新記録！"),
+            Msg::Retry => ("Press R to retry", "R でもう一度"),
+            Msg::ToDaily => ("Press C for daily challenge", "C でデイリーチャレンジ"),
+            Msg::ToEndless => ("Press C for endless mode", "C でエンドレスに戻る"),
+            Msg::ToVersus => ("Press V for 2P versus", "V で2人対戦"),
+            Msg::ToTutorial => ("Press T for tutorial", "T でチュートリアル"),
+            Msg::PracticeFrom => ("Press P to practice from {}", "P で {} 点から練習"),
+            Msg::PracticeEnd => ("Press P to end practice", "P で練習をやめる"),
+            Msg::Paused => ("PAUSED", "一時停止"),
+            Msg::MenuResume => ("Resume", "再開"),
+            Msg::MenuVolume => ("Volume", "音量"),
+            Msg::MenuTheme => ("Theme", "配色"),
+            Msg::MenuControls => ("Controls", "操作"),
+            Msg::MenuReducedMotion => ("Reduce motion", "動きを減らす"),
+            Msg::MenuGameSpeed => ("Game speed", "ゲーム速度"),
+            Msg::MenuAssist => ("Assist mode", "アシストモード"),
+            Msg::AssistSpeed => ("ASSIST MODE: game speed {}%", "アシストモード: ゲーム速度 {}%"),
+            Msg::Assist => ("ASSIST MODE", "アシストモード"),
+            Msg::Energy => ("ENERGY", "エネルギー"),
+            Msg::Demo => ("DEMO - press any key", "デモ - 何かキーを押してください"),
+            Msg::Crashed => ("Something went wrong", "エラーが起きました"),
+            Msg::CrashHint => ("Reload the page to play again", "ページを再読み込みしてください"),
+            Msg::BlackHole => ("BLACK HOLE!", "ブラックホール！"),
+            Msg::Gust => ("GUST {}", "突風 {}"),
+            Msg::MenuTraps => ("Trap pickups", "罠アイテム"),
+            Msg::TrapInvert => ("TRAP! CONTROLS REVERSED", "罠！ 左右が逆に"),
+            Msg::TrapFog => ("TRAP! FOG", "罠！ 霧"),
+            Msg::TrapRush => ("TRAP! METEOR RUSH", "罠！ 隕石が加速"),
+            Msg::Shop => ("SHOP", "ショップ"),
+            Msg::ShopEnergy => ("Starting energy +40", "初期エネルギー +40"),
+            Msg::ShopSpeed => ("Move speed +5%", "移動速度 +5%"),
+            Msg::ShopPrice => ("{}  {} coins", "{}  コイン {}"),
+            Msg::ShopMaxed => ("{}  MAX", "{}  最大"),
+            Msg::ShopClose => ("Close", "閉じる"),
+            Msg::ShopBought => ("Upgrade bought!", "強化した！"),
+            Msg::ShopHint => ("\u{2191}\u{2193} select  Enter buy  Esc close  coins: {}", "\u{2191}\u{2193} 選択  Enter 購入  Esc 閉じる  コイン {}"),
+            Msg::ToShop => ("Press U for the shop ({} coins)", "U でショップ（コイン {}）"),
+            Msg::CoinsEarned => ("+{} coins", "コイン +{}"),
+            Msg::MenuShip => ("Ship", "機体"),
+            Msg::ShipUnlocked => ("NEW SHIP: {}", "新しい機体：{}"),
+            Msg::GemBonus => ("GEM +{}", "宝石 +{}"),
+            Msg::NetStatus => ("ONLINE: {}", "オンライン：{}"),
+            Msg::GradeBest => ("BEST {}", "ベスト {}"),
+            Msg::GradeNewBest => ("NEW BEST!", "ベスト更新！"),
+            Msg::ToHeatmap => ("Press H for the death map", "H で落ちた場所の分布"),
+            Msg::Heatmap => ("DEATH MAP", "落ちた場所"),
+            Msg::HeatmapEmpty => ("No deaths recorded yet", "まだ記録がありません"),
+            Msg::HeatmapHint => ("{} deaths - H to close", "{} 回 - H で閉じる"),
+            Msg::LastMoments => ("LAST MOMENTS - press any key to skip", "直前の再生 - 何かキーで飛ばす"),
+            Msg::On => ("ON", "オン"),
+            Msg::Off => ("OFF", "オフ"),
+            Msg::MenuHint => ("\u{2191}\u{2193} select  \u{2190}\u{2192} change  Esc resume", "\u{2191}\u{2193} 選択  \u{2190}\u{2192} 変更  Esc 再開"),
+            Msg::Rotate => ("Please rotate your device", "端末を回転してください"),
+            Msg::PlaysIn => ("this game plays in {}", "このゲームは{}で遊びます"),
+            Msg::Landscape => ("landscape", "横向き"),
+            Msg::Portrait => ("portrait", "縦向き"),
+            Msg::Top10 => ("TOP 10", "トップ10"),
+            Msg::Sending => ("sending...", "送信中..."),
+            Msg::LeaderboardDown => ("leaderboard unavailable", "ランキングに接続できません"),
+            Msg::PlayerWins => ("PLAYER {} WINS", "プレイヤー{}の勝ち"),
+            Msg::Draw => ("DRAW", "引き分け"),
+            Msg::Rematch => ("Press R for a rematch", "R で再戦"),
+            Msg::SoloPlay => ("Press V for solo play", "V で1人プレイ"),
+            Msg::Score => ("SCORE: {}", "スコア: {}"),
+            Msg::ScoreBest => ("SCORE: {}  BEST: {}", "スコア: {}  ベスト: {}"),
+            Msg::DailyBest => ("DAILY {}  BEST: {}", "デイリー {}  ベスト: {}"),
+            Msg::Combo => ("COMBO x{} ({}x)", "コンボ x{} ({}倍)"),
+            Msg::Replay => ("REPLAY", "リプレイ"),
+            Msg::Practice => ("PRACTICE (from {})", "練習（{} 点から）"),
+            Msg::Online => ("ONLINE: {} ({} others)", "オンライン: {}（ほか {} 人）"),
+            Msg::Loading => ("LOADING {}/{}", "読み込み中 {}/{}"),
+            Msg::Tutorial => ("TUTORIAL", "チュートリアル"),
+            Msg::Stage => ("STAGE {}: {} - {}", "ステージ {}: {} - {}"),
+            Msg::Achievement => ("ACHIEVEMENT: {}", "実績: {}"),
+            Msg::TryAgain => ("Ouch! Try again", "当たった！もう一度"),
+            Msg::Close => ("CLOSE!", "ニアミス！"),
+            Msg::ChartScore => ("score", "スコア"),
+            Msg::ChartSpeed => ("speed", "速度"),
+            Msg::StageLowOrbit => ("Low Orbit", "低軌道"),
+            Msg::StageDustBelt => ("Dust Belt", "塵の帯"),
+            Msg::StageOuterRing => ("Outer Ring", "外縁の環"),
+            Msg::StageIonField => ("Ion Field", "イオン域"),
+            Msg::AchSurvivor => ("Survivor: 60s alive", "サバイバー：60秒生き残る"),
+            Msg::AchStatue => ("Statue: 1000 pts without moving", "石像：動かずに1000点"),
+            Msg::AchDodger => ("Dodger: 500 meteors dodged", "よけの名人：隕石を500個よける"),
+            Msg::AchDaredevil => ("Daredevil: 10 near-misses", "命知らず：ニアミス10回"),
+            Msg::LessonMove => ("Press \u{2190} / \u{2192} (or A / D) to move", "\u{2190} / \u{2192}（または A / D）で移動"),
+            Msg::LessonDodge => ("Dodge the falling meteor", "落ちてくる隕石をよけよう"),
+            Msg::LessonNearMiss => ("Pass close to a meteor for a NEAR MISS bonus", "隕石のすぐ近くをかすめるとニアミスのボーナス"),
+            Msg::LessonDone => ("Well done! Here comes the real thing...", "よくできました！本番です..."),
+            Msg::SayStart => ("Game started", "ゲーム開始"),
+            Msg::SayScore => ("Score {}", "スコア {}"),
+            Msg::SayWinner => ("Game over, player {} wins", "ゲームオーバー、プレイヤー{}の勝ち"),
+            Msg::SayFinalRecord => ("Game over, final score {}, new record", "ゲームオーバー、最終スコア {}、新記録"),
+            Msg::SayFinal => ("Game over, final score {}", "ゲームオーバー、最終スコア {}"),
+            Msg::SayStage => ("Stage {}: {}", "ステージ {}: {}"),
+            Msg::SayAchievement => ("Achievement unlocked: {}", "実績を解除: {}"),
+        };
+        match self { Lang::En => en, Lang::Ja => ja }
+    }
+
+    // 天候の表示名（JS に渡すイベントの weather は Weather::label のまま）
+    pub fn weather(self, w: Weather) -> &'static str {
+        match (self, w) {
+            (Lang::En, _) => w.label(),
+            (Lang::Ja, Weather::Clear) => "快晴",
+            (Lang::Ja, Weather::DustStorm) => "砂嵐",
+            (Lang::Ja, Weather::IonStorm) => "イオン嵐",
+        }
+    }
+
+    // 文言の {} を args で順に埋める
+    pub fn fill(self, m: Msg, args: &[&dyn Display]) -> String {
+        let mut out = String::new();
+        let mut args = args.iter();
+        for (i, part) in self.text(m).split("{}").enumerate() {
+            if i > 0 { if let Some(a) = args.next() { out.push_str(&a.to_string()); } }
+            out.push_str(part);
+        }
+        out
+    }
+}
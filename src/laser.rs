@@ -0,0 +1,39 @@
+// 縦のレーザー（ときどき出る障害物）。WARN 秒のあいだ撃つ列を点滅で知らせてから、FIRE 秒だけ画面の上から下まで撃つ。
+// 撃っている間に列の中にいると被弾（エネルギーで持ちこたえたらそのレーザーは消える）。落ちてくる隕石と違い、よけるのは場所より時機
+use crate::core::Rect;
+use serde::{Deserialize, Serialize};
+
+// 予告と撃っている時間（秒）
+pub(crate) const WARN: f64 = 1.0;
+const FIRE: f64 = 0.5;
+// 当たり判定の幅
+pub(crate) const WIDTH: f64 = 18.0;
+// 自機の中心からこの距離のうちを狙う
+pub(crate) const AIM: f64 = 120.0;
+// ランの始めから最初の1本までの秒数
+pub(crate) const FIRST: f64 = 40.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Laser {
+    // 列の中心
+    pub x: f64,
+    // 出てからの秒数
+    pub t: f64,
+}
+
+impl Laser {
+    pub fn new(x: f64) -> Self { Self { x, t: 0.0 } }
+
+    pub fn firing(&self) -> bool { self.t >= WARN }
+
+    pub fn done(&self) -> bool { self.t >= WARN + FIRE }
+
+    // 撃っている列（画面の高さいっぱい）
+    pub fn beam(&self, height: f64) -> Rect { Rect { x: self.x - WIDTH * 0.5, y: 0.0, w: WIDTH, h: height } }
+
+    // 見た目の太さの割合（0..1）。撃ち始めに一気に太くなり、終わりにかけて細くなる
+    pub fn power(&self) -> f64 {
+        if !self.firing() { return 0.0; }
+        (1.0 - (self.t - WARN) / FIRE).clamp(0.0, 1.0).sqrt()
+    }
+}
@@ -0,0 +1,74 @@
+// リモートのランキング（任意）。失敗してもゲームには影響させず、状態表示だけ変える
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Request, RequestInit, Response};
+
+const TOP_N: usize = 10;
+
+pub(crate) struct Entry { pub name: String, pub score: i64 }
+
+pub(crate) enum Status { Idle, Sending, Done(Vec<Entry>), Failed }
+
+pub(crate) struct Leaderboard {
+    endpoint: String,
+    player_name: String,
+    // fetch の完了は非同期なので Game とは別に共有する
+    status: Rc<RefCell<Status>>,
+}
+
+impl Leaderboard {
+    pub fn new(endpoint: String, player_name: String) -> Self {
+        Self { endpoint, player_name, status: Rc::new(RefCell::new(Status::Idle)) }
+    }
+
+    pub fn status(&self) -> std::cell::Ref<'_, Status> { self.status.borrow() }
+
+    // スコアを送り、返ってきた上位リストを保持する
+    pub fn submit(&self, score: f64, mode: &'static str, assisted: bool) {
+        *self.status.borrow_mut() = Status::Sending;
+        let status = self.status.clone();
+        let endpoint = self.endpoint.clone();
+        let name = self.player_name.clone();
+        spawn_local(async move {
+            let result = post_score(&endpoint, &name, score as i64, mode, assisted).await;
+            *status.borrow_mut() = match result {
+                Ok(top) => Status::Done(top),
+                Err(_) => Status::Failed,
+            };
+        });
+    }
+}
+
+// POST {"name","score","mode","assisted"} → レスポンスは [{"name","score"}, ...] の JSON。
+// assisted はアシストを使ったランか（分けて集計するかはサーバー次第）
+async fn post_score(endpoint: &str, name: &str, score: i64, mode: &str, assisted: bool) -> Result<Vec<Entry>, JsValue> {
+    let body = js_sys::Object::new();
+    js_sys::Reflect::set(&body, &"name".into(), &name.into())?;
+    js_sys::Reflect::set(&body, &"score".into(), &(score as f64).into())?;
+    js_sys::Reflect::set(&body, &"mode".into(), &mode.into())?;
+    js_sys::Reflect::set(&body, &"assisted".into(), &assisted.into())?;
+
+    let init = RequestInit::new();
+    init.set_method("POST");
+    init.set_body(&js_sys::JSON::stringify(&body)?.into());
+    let req = Request::new_with_str_and_init(endpoint, &init)?;
+    req.headers().set("Content-Type", "application/json")?;
+
+    let resp: Response = JsFuture::from(crate::web::fetch(&req)).await?.dyn_into()?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("leaderboard responded {}", resp.status())));
+    }
+    let json = JsFuture::from(resp.json()?).await?;
+    let entries = js_sys::Array::from(&json).iter()
+        .filter_map(|v| {
+            let name = js_sys::Reflect::get(&v, &"name".into()).ok()?.as_string()?;
+            let score = js_sys::Reflect::get(&v, &"score".into()).ok()?.as_f64()? as i64;
+            Some(Entry { name, score })
+        })
+        .take(TOP_N)
+        .collect();
+    Ok(entries)
+}
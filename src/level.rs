@@ -0,0 +1,127 @@
+// レベルファイル：ウェーブ（長さ・天候・スポーン間隔と落下速度の曲線・隕石の種類・ボス）を並べたもの。
+// 読み込むとステージはスコアではなく経過時間で進み、間隔と速度も曲線から決める（wasm を作り直さずに調整できる）
+use crate::collide::HitboxShape;
+use crate::weather::Weather;
+use serde::Deserialize;
+
+// repeat が false なら最後のウェーブのまま続ける
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Level {
+    pub waves: Vec<Wave>,
+    #[serde(default = "yes")]
+    pub repeat: bool,
+}
+
+fn yes() -> bool { true }
+
+// weather は "Clear" / "DustStorm" / "IonStorm"。interval と speed は [[ウェーブ内の秒, 値], ...] の折れ線（空なら config の spawn どおり）
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Wave {
+    pub name: String,
+    pub duration: f64,
+    #[serde(default = "clear")]
+    pub weather: Weather,
+    #[serde(default)]
+    pub interval: Curve,
+    #[serde(default)]
+    pub speed: Curve,
+    // 空なら config の spawn の大きさで1種類
+    #[serde(default)]
+    pub meteors: Vec<MeteorKind>,
+    pub boss: Option<Boss>,
+}
+
+fn clear() -> Weather { Weather::Clear }
+
+// weight の比で選ぶ。speedScale はその時点の落下速度にかける
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MeteorKind {
+    #[serde(default = "one")]
+    pub weight: f64,
+    pub size_min: f64,
+    pub size_max: f64,
+    #[serde(default = "one")]
+    pub speed_scale: f64,
+    pub hitbox: Option<HitboxShape>,
+}
+
+fn one() -> f64 { 1.0 }
+
+// ウェーブが始まって at 秒で1つだけ落とす大きな隕石
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Boss {
+    pub at: f64,
+    pub size: f64,
+    pub speed: f64,
+    pub hitbox: Option<HitboxShape>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub(crate) struct Curve(Vec<[f64; 2]>);
+
+impl Curve {
+    // 点の間は直線でつなぎ、端より外は端の値
+    pub fn at(&self, t: f64) -> Option<f64> {
+        let pts = &self.0;
+        let first = pts.first()?;
+        if t <= first[0] { return Some(first[1]); }
+        for w in pts.windows(2) {
+            let ([t0, v0], [t1, v1]) = (w[0], w[1]);
+            if t <= t1 { return Some(if t1 > t0 { v0 + (v1 - v0) * (t - t0) / (t1 - t0) } else { v1 }); }
+        }
+        pts.last().map(|p| p[1])
+    }
+}
+
+impl Wave {
+    // r は 0..1 の乱数
+    pub fn pick(&self, r: f64) -> Option<&MeteorKind> {
+        let total: f64 = self.meteors.iter().map(|k| k.weight.max(0.0)).sum();
+        let mut r = r * total;
+        for k in &self.meteors {
+            r -= k.weight.max(0.0);
+            if r < 0.0 { return Some(k); }
+        }
+        self.meteors.last()
+    }
+}
+
+impl Level {
+    // JSON（ron 機能を有効にしていれば RON も）の文字列。JS のオブジェクトからは web.rs で読む
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let level: Level = match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(v) => serde_json::from_value(v).map_err(|e| format!("level: {}", e))?,
+            #[cfg(feature = "ron")]
+            Err(_) => ron::from_str(text).map_err(|e| format!("level: {}", e))?,
+            #[cfg(not(feature = "ron"))]
+            Err(e) => return Err(format!("level: {}", e)),
+        };
+        level.check()?;
+        Ok(level)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.waves.is_empty() { return Err("level has no waves".to_string()); }
+        if let Some(w) = self.waves.iter().find(|w| !w.duration.is_finite() || w.duration <= 0.0) {
+            return Err(format!("wave {:?} needs a positive duration", w.name));
+        }
+        Ok(())
+    }
+
+    // ランの経過時間 → (ステージ番号, ウェーブ, ウェーブ内の秒)。一周するごとにステージ番号は進み続ける
+    pub fn at(&self, elapsed: f64) -> (usize, &Wave, f64) {
+        let total: f64 = self.waves.iter().map(|w| w.duration).sum();
+        let (laps, mut t) = if self.repeat { ((elapsed / total) as usize, elapsed % total) } else { (0, elapsed) };
+        for (i, w) in self.waves.iter().enumerate() {
+            if t < w.duration { return (laps * self.waves.len() + i, w, t); }
+            t -= w.duration;
+        }
+        // 繰り返さないときの最後のウェーブ
+        let last = self.waves.len() - 1;
+        (last, &self.waves[last], self.waves[last].duration + t)
+    }
+}
@@ -0,0 +1,149 @@
+// 一時停止中に canvas に出す設定メニュー（音量・配色・操作・動きを減らす・ゲーム速度・アシスト・罠・機体）。HTML を置かなくても設定を変えられるように、
+// キーボード（矢印 / Enter / Esc）・ゲームパッド（web.rs でキー名に直して渡す）・タップのどれでも操作できる
+use crate::core::Game;
+use crate::i18n::Msg;
+use crate::settings::{ControlScheme, MIN_GAME_SPEED};
+use crate::ships::{ShipKind, SHIPS};
+use crate::heatmap;
+use crate::shop;
+use crate::theme::THEME_NAMES;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Item { Resume, Volume, Theme, Controls, ReducedMotion, GameSpeed, Assist, Traps, Ship }
+
+pub(crate) const ITEMS: [Item; 9] = [Item::Resume, Item::Volume, Item::Theme, Item::Controls, Item::ReducedMotion, Item::GameSpeed, Item::Assist, Item::Traps, Item::Ship];
+
+// 行の高さと幅（タップの当たりもこの並びで取る）
+const ROW_H: f64 = 30.0;
+const ROW_W: f64 = 280.0;
+// 右下の一時停止ボタンの大きさ
+const BUTTON: f64 = 36.0;
+
+#[derive(Default)]
+pub(crate) struct Menu { pub sel: usize }
+
+impl Item {
+    pub fn label(self) -> Msg {
+        match self {
+            Item::Resume => Msg::MenuResume,
+            Item::Volume => Msg::MenuVolume,
+            Item::Theme => Msg::MenuTheme,
+            Item::Controls => Msg::MenuControls,
+            Item::ReducedMotion => Msg::MenuReducedMotion,
+            Item::GameSpeed => Msg::MenuGameSpeed,
+            Item::Assist => Msg::MenuAssist,
+            Item::Traps => Msg::MenuTraps,
+            Item::Ship => Msg::MenuShip,
+        }
+    }
+
+    // 今の値の表示（Resume は無し）
+    pub fn value(self, g: &Game) -> String {
+        let s = &g.settings;
+        match self {
+            Item::Resume => String::new(),
+            Item::Volume => format!("< {:>3}% >", (s.volume * 100.0).round() as i32),
+            Item::Theme => format!("< {} >", if THEME_NAMES.contains(&s.theme.as_str()) { &s.theme } else { "custom" }),
+            Item::Controls => format!("< {} >", s.control_scheme.name()),
+            Item::ReducedMotion => g.lang.text(if s.reduced_motion { Msg::On } else { Msg::Off }).to_string(),
+            Item::Assist => g.lang.text(if s.assist { Msg::On } else { Msg::Off }).to_string(),
+            Item::Traps => g.lang.text(if s.trap_pickups { Msg::On } else { Msg::Off }).to_string(),
+            Item::Ship => format!("< {} >", s.ship.name()),
+            Item::GameSpeed => format!("< {:>3}% >", (s.game_speed * 100.0).round() as i32),
+        }
+    }
+}
+
+// Esc / Start / 一時停止ボタン。ゲームオーバー中は開かない
+pub(crate) fn toggle(g: &mut Game) {
+    if g.over { return; }
+    g.paused = !g.paused;
+    g.menu = Menu::default();
+    g.clear_input();
+}
+
+// 一時停止中のキー（ゲームパッドもここに来る）。メニューで使ったら true
+pub(crate) fn key(g: &mut Game, key: &str) -> bool {
+    let n = ITEMS.len();
+    match key {
+        "ArrowUp" => g.menu.sel = (g.menu.sel + n - 1) % n,
+        "ArrowDown" => g.menu.sel = (g.menu.sel + 1) % n,
+        "ArrowLeft" | "a" | "A" => change(g, ITEMS[g.menu.sel], -1),
+        "ArrowRight" | "d" | "D" => change(g, ITEMS[g.menu.sel], 1),
+        "Enter" | " " => activate(g, ITEMS[g.menu.sel]),
+        "Escape" | "Backspace" => toggle(g),
+        _ => return false,
+    }
+    true
+}
+
+// 選んだ項目を決定（Resume は再開、それ以外は1つ先の値へ）
+fn activate(g: &mut Game, item: Item) {
+    if item == Item::Resume { toggle(g); } else { change(g, item, 1); }
+}
+
+// 値を dir（-1 / 1）の向きに1つ動かして保存する
+fn change(g: &mut Game, item: Item, dir: i32) {
+    // 機体は解除済みのものだけを回る
+    let ships: Vec<ShipKind> = SHIPS.into_iter().filter(|k| k.unlocked(g)).collect();
+    let s = &mut g.settings;
+    match item {
+        Item::Resume => return,
+        Item::Volume => s.volume = ((s.volume * 10.0).round() + dir as f64).clamp(0.0, 10.0) / 10.0,
+        Item::Theme => {
+            // JSON の独自テーマからは組み込みの先頭へ
+            let n = THEME_NAMES.len() as i32;
+            let next = THEME_NAMES.iter().position(|&t| t == s.theme).map_or(0, |i| (i as i32 + dir).rem_euclid(n) as usize);
+            s.theme = THEME_NAMES[next].to_string();
+        }
+        Item::Controls => {
+            let all = [ControlScheme::Both, ControlScheme::Arrows, ControlScheme::Wasd];
+            let i = all.iter().position(|&c| c == s.control_scheme).unwrap_or(0) as i32;
+            s.control_scheme = all[(i + dir).rem_euclid(all.len() as i32) as usize];
+        }
+        Item::ReducedMotion => s.reduced_motion = !s.reduced_motion,
+        // これも次のランから
+        Item::Assist => s.assist = !s.assist,
+        Item::Traps => s.trap_pickups = !s.trap_pickups,
+        Item::Ship => {
+            let i = ships.iter().position(|&k| k == s.ship).unwrap_or(0) as i32;
+            s.ship = ships[(i + dir).rem_euclid(ships.len() as i32) as usize];
+        }
+        // 10% 刻み。今のランには効かない（次のランから）
+        Item::GameSpeed => s.game_speed = ((s.game_speed * 10.0).round() + dir as f64).clamp(MIN_GAME_SPEED * 10.0, 10.0) / 10.0,
+    }
+    g.save_settings();
+}
+
+// i 行目の矩形 (x, y, w, h)。描画とタップの両方で使う
+pub(crate) fn row(g: &Game, i: usize) -> (f64, f64, f64, f64) {
+    let w = ROW_W.min(g.width - 20.0);
+    let top = g.height * 0.5 - ITEMS.len() as f64 * ROW_H * 0.5 + 10.0;
+    (g.width * 0.5 - w * 0.5, top + i as f64 * ROW_H, w, ROW_H)
+}
+
+// 右下の一時停止ボタン (x, y, w, h)
+pub(crate) fn pause_button(g: &Game) -> (f64, f64, f64, f64) {
+    (g.width - BUTTON - 10.0, g.height - BUTTON - 10.0, BUTTON, BUTTON)
+}
+
+// canvas 上の (x, y) をタップした。一時停止中は行を選んで決定（値のある行は左半分で戻し、右半分で進める）
+pub(crate) fn tap(g: &mut Game, x: f64, y: f64) {
+    if g.wake() { return; }
+    if g.over && g.deathcam.take().is_some() { return; }
+    if shop::tap(g, x, y) { return; }
+    // 落ちた位置の分布はどこをタップしても閉じる
+    if heatmap::key(g, "Escape") { return; }
+    let inside = |(rx, ry, rw, rh): (f64, f64, f64, f64)| x >= rx && x < rx + rw && y >= ry && y < ry + rh;
+    if !g.paused {
+        if !g.over && inside(pause_button(g)) { toggle(g); }
+        return;
+    }
+    let Some(i) = (0..ITEMS.len()).find(|&i| inside(row(g, i))) else { return };
+    g.menu.sel = i;
+    let (rx, _, rw, _) = row(g, i);
+    match ITEMS[i] {
+        Item::Resume => toggle(g),
+        item => change(g, item, if x < rx + rw * 0.5 { -1 } else { 1 }),
+    }
+}
@@ -0,0 +1,33 @@
+// スコアの節目（STEP 点ごと）のお祝い。update_score が milestone イベントを出し、react がそれを見て
+// 音・パーティクル・HUD の「1000!」・背景の脈動をまとめて出す（見た目だけで、乱数やスコアには関わらない）
+
+pub(crate) const STEP: f64 = 1000.0;
+// 文字を出しておく秒数と、背景が光る秒数
+const DURATION: f64 = 1.2;
+const PULSE: f64 = 0.6;
+// 出始めに弾む時間と、そのときの大きさ（倍）
+const POP: f64 = 0.25;
+const POP_SCALE: f64 = 1.6;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Celebration { pub score: u64, t: f64 }
+
+impl Celebration {
+    pub fn new(score: u64) -> Self { Self { score, t: 0.0 } }
+
+    pub fn tick(&mut self, dt: f64) { self.t += dt; }
+
+    pub fn done(&self) -> bool { self.t >= DURATION }
+
+    // 文字の大きさの倍率。大きく出てから 1 に縮む
+    pub fn scale(&self) -> f64 {
+        let k = 1.0 - (self.t / POP).min(1.0);
+        1.0 + (POP_SCALE - 1.0) * k * k
+    }
+
+    // 終わりの 0.4 秒で消える
+    pub fn alpha(&self) -> f64 { ((DURATION - self.t) / 0.4).clamp(0.0, 1.0) }
+
+    // 背景の光り方（0..1）
+    pub fn pulse(&self) -> f64 { (1.0 - self.t / PULSE).max(0.0) }
+}
@@ -0,0 +1,169 @@
+// WebSocket 対戦：サーバーから共通シードをもらい、各プレイヤーの位置を流し合う
+//
+// プロトコル（すべて JSON テキスト）
+//   client → server: {"t":"hello","name":"..."}
+//                    {"t":"pos","x":0.42,"alive":true,"score":1234}   x は画面幅に対する割合
+//   server → client: {"t":"welcome","id":"...","seed":"123"}           seed は u64 を文字列で
+//                    {"t":"pos","id":"...","name":"...","x":0.5,"alive":true,"score":99}
+//                    {"t":"leave","id":"..."}
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CloseEvent, Event, MessageEvent, WebSocket};
+
+// 位置を送る間隔（秒）
+const SEND_INTERVAL: f64 = 0.05;
+
+#[derive(Clone, PartialEq)]
+pub(crate) enum ConnState { Connecting, Connected, Closed, Failed }
+
+impl ConnState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnState::Connecting => "connecting",
+            ConnState::Connected => "connected",
+            ConnState::Closed => "closed",
+            ConnState::Failed => "failed",
+        }
+    }
+}
+
+pub(crate) struct RemotePlayer { pub name: String, pub x: f64, pub alive: bool, pub score: f64 }
+
+// コールバックと Game で共有する部分
+struct Shared {
+    state: ConnState,
+    id: Option<String>,
+    // 受け取ったけれどまだ Game に反映していないシード
+    pending_seed: Option<u64>,
+    remotes: HashMap<String, RemotePlayer>,
+}
+
+pub(crate) struct Net {
+    ws: WebSocket,
+    shared: Rc<RefCell<Shared>>,
+    send_timer: f64,
+    // 最後にトーストで知らせた接続の状態
+    reported: Option<ConnState>,
+    // 切断時に外すため保持しておく
+    _on_open: Closure<dyn FnMut(Event)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+}
+
+impl Net {
+    pub fn connect(url: &str, name: &str) -> Result<Net, JsValue> {
+        let ws = WebSocket::new(url)?;
+        let shared = Rc::new(RefCell::new(Shared {
+            state: ConnState::Connecting, id: None, pending_seed: None, remotes: HashMap::new(),
+        }));
+
+        let on_open = {
+            let (ws, shared, name) = (ws.clone(), shared.clone(), name.to_string());
+            Closure::<dyn FnMut(Event)>::new(move |_| {
+                shared.borrow_mut().state = ConnState::Connected;
+                let hello = object(&[("t", "hello".into()), ("name", name.as_str().into())]);
+                let _ = ws.send_with_str(&hello);
+            })
+        };
+        let on_message = {
+            let shared = shared.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+                if let Some(text) = e.data().as_string() {
+                    handle_message(&mut shared.borrow_mut(), &text);
+                }
+            })
+        };
+        let on_close = {
+            let shared = shared.clone();
+            Closure::<dyn FnMut(CloseEvent)>::new(move |_| {
+                let mut s = shared.borrow_mut();
+                if s.state != ConnState::Failed { s.state = ConnState::Closed; }
+                s.remotes.clear();
+            })
+        };
+        let on_error = {
+            let shared = shared.clone();
+            Closure::<dyn FnMut(Event)>::new(move |_| shared.borrow_mut().state = ConnState::Failed)
+        };
+        ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        Ok(Net { ws, shared, send_timer: 0.0, reported: None, _on_open: on_open, _on_message: on_message, _on_close: on_close, _on_error: on_error })
+    }
+
+    pub fn state(&self) -> ConnState { self.shared.borrow().state.clone() }
+
+    // 前に知らせてから接続の状態が変わっていれば、その状態を1回だけ返す
+    pub fn take_state_change(&mut self) -> Option<ConnState> {
+        let s = self.state();
+        if self.reported.as_ref() == Some(&s) { return None; }
+        self.reported = Some(s.clone());
+        Some(s)
+    }
+
+    // 新しく届いたシードを1回だけ取り出す
+    pub fn take_pending_seed(&mut self) -> Option<u64> { self.shared.borrow_mut().pending_seed.take() }
+
+    pub fn remotes(&self) -> std::cell::Ref<'_, HashMap<String, RemotePlayer>> {
+        std::cell::Ref::map(self.shared.borrow(), |s| &s.remotes)
+    }
+
+    // 自分の位置を一定間隔で送る
+    pub fn tick(&mut self, dt: f64, x_ratio: f64, alive: bool, score: f64) {
+        self.send_timer -= dt;
+        if self.send_timer > 0.0 || self.state() != ConnState::Connected { return; }
+        self.send_timer = SEND_INTERVAL;
+        let msg = object(&[("t", "pos".into()), ("x", x_ratio.into()), ("alive", alive.into()), ("score", score.floor().into())]);
+        let _ = self.ws.send_with_str(&msg);
+    }
+}
+
+impl Drop for Net {
+    fn drop(&mut self) {
+        self.ws.set_onopen(None);
+        self.ws.set_onmessage(None);
+        self.ws.set_onclose(None);
+        self.ws.set_onerror(None);
+        let _ = self.ws.close();
+    }
+}
+
+fn object(fields: &[(&str, JsValue)]) -> String {
+    let o = js_sys::Object::new();
+    for (k, v) in fields { let _ = js_sys::Reflect::set(&o, &(*k).into(), v); }
+    js_sys::JSON::stringify(&o).map(String::from).unwrap_or_default()
+}
+
+// 壊れたメッセージは黙って捨てる
+fn handle_message(s: &mut Shared, text: &str) {
+    let Ok(msg) = js_sys::JSON::parse(text) else { return };
+    let get = |k: &str| js_sys::Reflect::get(&msg, &k.into()).unwrap_or(JsValue::UNDEFINED);
+    let id = get("id").as_string();
+    match get("t").as_string().as_deref() {
+        Some("welcome") => {
+            s.id = id;
+            if let Some(seed) = get("seed").as_string().and_then(|v| v.parse().ok()) {
+                s.pending_seed = Some(seed);
+            }
+        }
+        Some("pos") => {
+            let Some(id) = id else { return };
+            if s.id.as_ref() == Some(&id) { return; }
+            let p = s.remotes.entry(id).or_insert_with(|| RemotePlayer { name: String::new(), x: 0.5, alive: true, score: 0.0 });
+            if let Some(name) = get("name").as_string() { p.name = name; }
+            if let Some(x) = get("x").as_f64() { p.x = x.clamp(0.0, 1.0); }
+            if let Some(alive) = get("alive").as_bool() { p.alive = alive; }
+            if let Some(score) = get("score").as_f64() { p.score = score; }
+        }
+        Some("leave") => {
+            if let Some(id) = id { s.remotes.remove(&id); }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,100 @@
+// パーティクル：被弾の爆発・隕石の燃え尽き・自機の噴射。
+// 見た目だけなのでゲームの乱数とは別の乱数で散らす（リプレイやデイリーの隕石列に影響させない）
+use crate::rng::{Rng, XorShift};
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum ParticleKind { Spark, Ember, Exhaust, Flame }
+
+impl ParticleKind {
+    pub fn color(self) -> &'static str {
+        match self { ParticleKind::Spark => "#ffd166", ParticleKind::Ember => "#ff7b39", ParticleKind::Exhaust => "#9fd8ff", ParticleKind::Flame => "#ffa94d" }
+    }
+
+    // 飛び方：(最大の速さ, 向きの始まり, 広がり（ラジアン）, 寿命)。y は下向きが正
+    fn spray(self) -> (f64, f64, f64, f64) {
+        match self {
+            // 全方向に飛び散って落ちる
+            ParticleKind::Spark => (180.0, 0.0, TAU, 0.9),
+            // 画面の下端から上に舞う
+            ParticleKind::Ember => (80.0, -PI, PI, 0.6),
+            // 真下に細く
+            ParticleKind::Exhaust => (60.0, FRAC_PI_2 - 0.3, 0.6, 0.3),
+            // 横移動の噴射：下向きに短く
+            ParticleKind::Flame => (90.0, FRAC_PI_2 - 0.2, 0.4, 0.15),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Particle { pub x: f64, pub y: f64, vx: f64, vy: f64, life: f64, ttl: f64, pub size: f64, pub kind: ParticleKind }
+
+impl Particle {
+    // 残り寿命に合わせて薄くする
+    pub fn alpha(&self) -> f64 { (self.ttl / self.life).clamp(0.0, 1.0) }
+}
+
+// 重力（爆発の火花だけ落ちる）
+const GRAVITY: f64 = 240.0;
+
+pub(crate) struct Particles {
+    // 生きているのは先頭の live 個。消えたら末尾の生きているものと入れ替え、配列は確保し直さない
+    pool: Vec<Particle>,
+    live: usize,
+    cap: usize,
+    rng: XorShift,
+}
+
+impl Particles {
+    pub fn new(cap: usize) -> Self { Self { pool: Vec::with_capacity(cap), live: 0, cap, rng: XorShift::new(0x9e37_79b9_7f4a_7c15) } }
+
+    // 上限を変える（超えている分は捨てる）
+    pub fn set_cap(&mut self, cap: usize) {
+        self.cap = cap;
+        self.live = self.live.min(cap);
+        self.pool.truncate(cap);
+        self.pool.shrink_to(cap);
+    }
+
+    pub fn clear(&mut self) { self.live = 0; }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> { self.pool[..self.live].iter() }
+
+    pub fn chance(&mut self, p: f64) -> bool { self.rng.next_f64() < p }
+
+    // 上限に達していたら出さない
+    fn spawn(&mut self, p: Particle) {
+        if self.live >= self.cap { return; }
+        if self.live < self.pool.len() { self.pool[self.live] = p; } else { self.pool.push(p); }
+        self.live += 1;
+    }
+
+    // (x, y) から n 個を種類ごとの向きに散らす
+    pub fn burst(&mut self, kind: ParticleKind, x: f64, y: f64, n: usize) {
+        let (speed, angle, spread, life) = kind.spray();
+        for _ in 0..n {
+            let a = angle + spread * self.rng.next_f64();
+            let v = speed * (0.3 + 0.7 * self.rng.next_f64());
+            let life = life * (0.6 + 0.4 * self.rng.next_f64());
+            let size = 1.0 + 2.0 * self.rng.next_f64();
+            self.spawn(Particle { x, y, vx: a.cos() * v, vy: a.sin() * v, life, ttl: life, size, kind });
+        }
+    }
+
+    pub fn update(&mut self, dt: f64) {
+        let mut i = 0;
+        while i < self.live {
+            let p = &mut self.pool[i];
+            p.ttl -= dt;
+            if p.ttl <= 0.0 {
+                self.live -= 1;
+                self.pool.swap(i, self.live);
+                continue;
+            }
+            if p.kind == ParticleKind::Spark { p.vy += GRAVITY * dt; }
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// フレーム時間の計測（F3 の表示用）。rAF のタイムスタンプの間隔を直近1秒ぶん持つ
+use std::collections::VecDeque;
+
+const WINDOW_MS: f64 = 1000.0;
+
+#[derive(Default)]
+pub(crate) struct FrameStats {
+    last: Option<f64>,
+    // (タイムスタンプ, 前のフレームからの ms)
+    frames: VecDeque<(f64, f64)>,
+}
+
+impl FrameStats {
+    pub fn record(&mut self, t: f64) {
+        if let Some(last) = self.last { self.frames.push_back((t, t - last)); }
+        self.last = Some(t);
+        while self.frames.front().is_some_and(|&(ft, _)| ft < t - WINDOW_MS) { self.frames.pop_front(); }
+    }
+
+    pub fn fps(&self) -> usize { self.frames.len() }
+
+    pub fn average_ms(&self) -> f64 {
+        if self.frames.is_empty() { return 0.0; }
+        self.frames.iter().map(|f| f.1).sum::<f64>() / self.frames.len() as f64
+    }
+
+    pub fn worst_ms(&self) -> f64 { self.frames.iter().map(|f| f.1).fold(0.0, f64::max) }
+}
+
+// 1フレームの dt をならす。直近のフレーム時間の中央値を基準に進め、引っかかり（GC など）で余った時間は
+// 借りとして数フレームに分けて返す（1フレームで一気に進めて、避ける間もなく当たることがないように）
+const SMOOTH_FRAMES: usize = 5;
+// これより長い間隔（タブの切り替えなど）は追いつかずに捨てる
+const MAX_GAP: f64 = 0.25;
+// 溜めておく借りの上限（秒）
+const MAX_DEBT: f64 = 0.1;
+// 1フレームで進める上限（Game::step が1回で追いつける分）
+pub(crate) const MAX_DT: f64 = 0.05;
+
+#[derive(Default)]
+pub(crate) struct DtSmoother {
+    recent: VecDeque<f64>,
+    debt: f64,
+}
+
+impl DtSmoother {
+    pub fn next(&mut self, raw: f64) -> f64 {
+        if raw > MAX_GAP { return 0.0; }
+        if self.recent.len() == SMOOTH_FRAMES { self.recent.pop_front(); }
+        self.recent.push_back(raw);
+        let mut sorted = [0.0; SMOOTH_FRAMES];
+        let n = self.recent.len();
+        for (s, &d) in sorted.iter_mut().zip(&self.recent) { *s = d; }
+        sorted[..n].sort_by(f64::total_cmp);
+        let median = sorted[n / 2];
+        // 中央値との差を借りにして、1フレームあたり中央値の半分ずつ返す（短いフレームでは逆に借りが減る）
+        self.debt = (self.debt + raw - median).min(MAX_DEBT);
+        let pay = self.debt.clamp(-median * 0.5, median * 0.5);
+        self.debt -= pay;
+        (median + pay).min(MAX_DT)
+    }
+}
@@ -0,0 +1,80 @@
+// 拾いもの。ときどき落ちてくる宝石に自機で触れると拾える。金色の宝石はスコア（近接回避とコンボがつながり、倍率もかかる）。
+// 罠（設定の trapPickups で出さなくできる）は形が同じで色が赤く、中の印が違う。拾うと少しの間だけ不利になる：
+//   invert 左右が逆になる / fog 自機の周りしか見えない / rush 隕石が速くなる
+// 拾うと powerup イベント（{kind, trap, x}）を出し、罠なら警告のトーストと音を出す
+use crate::core::Rect;
+use serde::{Deserialize, Serialize};
+
+// 大きさと落ちる速さ（px/秒）
+pub(crate) const SIZE: f64 = 18.0;
+const FALL: f64 = 110.0;
+// 宝石のスコア（コンボの倍率をかける前）
+pub(crate) const BONUS: f64 = 200.0;
+// 出たものが罠である割合
+pub(crate) const TRAP_CHANCE: f64 = 0.35;
+// 罠で隕石が速くなる倍率
+pub(crate) const RUSH: f64 = 1.35;
+// ランの始めから最初の1つまでの秒数
+pub(crate) const FIRST: f64 = 15.0;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum PickupKind { Gem, Invert, Fog, Rush }
+
+pub(crate) const TRAPS: [PickupKind; 3] = [PickupKind::Invert, PickupKind::Fog, PickupKind::Rush];
+
+impl PickupKind {
+    pub fn name(self) -> &'static str {
+        match self { PickupKind::Gem => "gem", PickupKind::Invert => "invert", PickupKind::Fog => "fog", PickupKind::Rush => "rush" }
+    }
+    pub fn trap(self) -> bool { self != PickupKind::Gem }
+    // 罠の効く秒数
+    pub fn seconds(self) -> f64 {
+        match self { PickupKind::Gem => 0.0, PickupKind::Invert => 3.0, PickupKind::Fog => 5.0, PickupKind::Rush => 4.0 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Pickup {
+    pub x: f64,
+    pub y: f64,
+    // 直前の update() 前の y（描画の補間用）
+    #[serde(skip)]
+    pub prev_y: f64,
+    pub kind: PickupKind,
+}
+
+impl Pickup {
+    // 画面の上端の外から（x は中心）
+    pub fn new(x: f64, kind: PickupKind) -> Self { Self { x, y: -SIZE, prev_y: -SIZE, kind } }
+
+    pub fn fall(&mut self, dt: f64) {
+        self.prev_y = self.y;
+        self.y += FALL * dt;
+    }
+
+    pub fn rect(&self) -> Rect { Rect { x: self.x - SIZE * 0.5, y: self.y - SIZE * 0.5, w: SIZE, h: SIZE } }
+
+    pub fn gone(&self, height: f64) -> bool { self.y - SIZE > height }
+}
+
+// 効いている罠の残り秒数（対戦では両方に効く）
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Traps { pub invert: f64, pub fog: f64, pub rush: f64 }
+
+impl Traps {
+    // 同じ罠を続けて拾ったら、長い方に揃える（足し合わせない）
+    pub fn start(&mut self, kind: PickupKind) {
+        let t = match kind { PickupKind::Gem => return, PickupKind::Invert => &mut self.invert, PickupKind::Fog => &mut self.fog, PickupKind::Rush => &mut self.rush };
+        *t = t.max(kind.seconds());
+    }
+
+    pub fn tick(&mut self, dt: f64) {
+        for t in [&mut self.invert, &mut self.fog, &mut self.rush] { *t = (*t - dt).max(0.0); }
+    }
+
+    // 隕石の速さにかける倍率
+    pub fn meteor_scale(&self) -> f64 { if self.rush > 0.0 { RUSH } else { 1.0 } }
+
+    // 霧の濃さ（0..1）。晴れ際の 0.5 秒で薄くする
+    pub fn fog_level(&self) -> f64 { (self.fog / 0.5).min(1.0) }
+}
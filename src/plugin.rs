@@ -0,0 +1,62 @@
+// JS のプラグイン：決まった時点（更新の前後・描画の前後）で呼ぶコールバック。
+// 渡すのは読み取り用の状態だけで、preUpdate は {"timeScale": n} を返すとそのフレームの進みを変えられる
+use crate::core::Game;
+use crate::web::js_object;
+use wasm_bindgen::JsValue;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Hook { PreUpdate, PostUpdate, PreDraw, PostDraw }
+
+impl Hook {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "preUpdate" => Some(Hook::PreUpdate),
+            "postUpdate" => Some(Hook::PostUpdate),
+            "preDraw" => Some(Hook::PreDraw),
+            "postDraw" => Some(Hook::PostDraw),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) type Plugins = Vec<(Hook, js_sys::Function)>;
+
+// プラグインに見せる状態（コピーなので書き換えてもゲームには効かない）
+pub(crate) fn view(g: &Game, dt: f64) -> JsValue {
+    let players = g.players.iter().map(|p| js_object(&[
+        ("x", p.r.x.into()), ("y", p.r.y.into()), ("w", p.r.w.into()), ("h", p.r.h.into()),
+        ("score", p.score.into()), ("alive", p.alive.into()),
+    ])).collect::<js_sys::Array>();
+    let meteors = g.meteors.iter().map(|m| js_object(&[
+        ("id", m.id.into()), ("x", m.r.x.into()), ("y", m.r.y.into()), ("w", m.r.w.into()), ("h", m.r.h.into()), ("vy", m.vy.into()),
+    ])).collect::<js_sys::Array>();
+    js_object(&[
+        ("dt", dt.into()),
+        ("elapsed", g.stats.elapsed.into()),
+        ("width", g.width.into()),
+        ("height", g.height.into()),
+        ("score", g.score.into()),
+        ("speed", g.speed.into()),
+        ("stage", (g.stage as u32 + 1).into()),
+        ("over", g.over.into()),
+        ("paused", g.paused.into()),
+        ("players", players.into()),
+        ("meteors", meteors.into()),
+    ])
+}
+
+// hook のコールバックを登録順に呼ぶ。投げた例外はコンソールに出して続ける。
+// preUpdate の timeScale はかけ合わせて返す（0〜4）
+pub(crate) fn run(plugins: &Plugins, hook: Hook, view: &JsValue) -> f64 {
+    let mut scale = 1.0;
+    for (_, f) in plugins.iter().filter(|(h, _)| *h == hook) {
+        match f.call1(&JsValue::NULL, view) {
+            Ok(ret) if hook == Hook::PreUpdate && ret.is_object() => {
+                if let Some(s) = js_sys::Reflect::get(&ret, &"timeScale".into()).ok().and_then(|v| v.as_f64()) { scale *= s.clamp(0.0, 4.0); }
+            }
+            Ok(_) => {}
+            Err(e) => web_sys::console::error_1(&e),
+        }
+    }
+    scale
+}
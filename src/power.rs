@@ -0,0 +1,89 @@
+// フレームレートの上限と省電力。上限があるときは rAF を間引き、電池が少なくなったら自動で lowPowerFps に落とす
+use crate::config::GameConfig;
+use crate::settings::Settings;
+use crate::web::{listen, Listener};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{window, BatteryManager};
+
+// 充電していなくて残りがこれ以下なら省電力にする
+const LOW_BATTERY: f64 = 0.2;
+
+// 今の上限（fps）。省電力（設定か電池残量）なら maxFps と lowPowerFps の低い方
+pub(crate) fn fps_cap(config: &GameConfig, settings: &Settings, low_battery: bool) -> Option<f64> {
+    if settings.battery_saver || low_battery {
+        return Some(config.max_fps.map_or(config.low_power_fps, |m| m.min(config.low_power_fps)));
+    }
+    config.max_fps
+}
+
+// rAF は画面の更新ごとに来るので、前に通した時刻から 1/fps 経つまでは飛ばす
+#[derive(Default)]
+pub(crate) struct FrameGate { next: f64 }
+
+impl FrameGate {
+    pub fn ready(&mut self, t: f64, cap: Option<f64>) -> bool {
+        let Some(fps) = cap.filter(|&f| f > 0.0) else { return true };
+        let budget = 1000.0 / fps;
+        // rAF の時刻は少し揺れるので、1ms 手前までは通す
+        if t < self.next - 1.0 { return false; }
+        // 大きく遅れたら（タブ復帰など）今から数え直す
+        self.next = if t - self.next > budget { t + budget } else { self.next + budget };
+        true
+    }
+}
+
+// 電池の状態（Battery Status API）。使えないブラウザでは low() がずっと false
+pub(crate) struct Battery {
+    low: Rc<Cell<bool>>,
+    listeners: Rc<RefCell<Vec<Listener>>>,
+}
+
+impl Battery {
+    // on_change は残量や充電の状態が変わって low() が変わったときに呼ぶ
+    pub fn watch(on_change: impl Fn(bool) + 'static) -> Self {
+        let battery = Self { low: Rc::default(), listeners: Rc::default() };
+        let Some(promise) = get_battery() else { return battery };
+        let (low, listeners) = (Rc::downgrade(&battery.low), Rc::downgrade(&battery.listeners));
+        spawn_local(async move {
+            let Ok(Ok(manager)) = JsFuture::from(promise).await.map(|m| m.dyn_into::<BatteryManager>()) else { return };
+            let on_change = Rc::new(on_change);
+            let update = {
+                let (manager, low) = (manager.clone(), low.clone());
+                move || {
+                    let Some(low) = low.upgrade() else { return };
+                    let now = !manager.charging() && manager.level() <= LOW_BATTERY;
+                    if low.replace(now) != now { on_change(now); }
+                }
+            };
+            update();
+            // 待っている間に落とされていたら付けない
+            let Some(listeners) = listeners.upgrade() else { return };
+            for name in ["levelchange", "chargingchange"] {
+                let update = update.clone();
+                if let Ok(l) = listen(&manager, name, Closure::new(move |_e: web_sys::Event| update())) { listeners.borrow_mut().push(l); }
+            }
+        });
+        battery
+    }
+
+    pub fn low(&self) -> bool { self.low.get() }
+}
+
+impl Drop for Battery {
+    fn drop(&mut self) {
+        for (target, name, cb) in self.listeners.borrow().iter() {
+            let _ = target.remove_event_listener_with_callback(name, cb.as_ref().unchecked_ref());
+        }
+    }
+}
+
+// navigator.getBattery()（web-sys には無いので JS から引く）
+fn get_battery() -> Option<js_sys::Promise> {
+    let nav = window()?.navigator();
+    let f = js_sys::Reflect::get(&nav, &"getBattery".into()).ok()?.dyn_into::<js_sys::Function>().ok()?;
+    f.call0(&nav).ok()?.dyn_into().ok()
+}
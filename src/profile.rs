@@ -0,0 +1,25 @@
+// DevTools の Performance パネルに区間を出す（performance.mark / measure）。config の profile か、ページの URL の ?profile で有効。
+// 名前は "meteor-dodge:update" / ":spawn" / ":draw"。トレースには残るので、測ったら performance のバッファからは消す（積もらないように）
+use crate::web::performance;
+
+const PREFIX: &str = "meteor-dodge:";
+
+// 作ったときに mark し、スコープを抜けたら measure する。off なら何もしない（ヘッドレスでも呼べる）
+pub(crate) struct Span(Option<&'static str>);
+
+pub(crate) fn span(on: bool, name: &'static str) -> Span {
+    if !on { return Span(None); }
+    if let Some(p) = performance() { let _ = p.mark(&format!("{}{}:start", PREFIX, name)); }
+    Span(Some(name))
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(name) = self.0 else { return };
+        let Some(p) = performance() else { return };
+        let (measure, start) = (format!("{}{}", PREFIX, name), format!("{}{}:start", PREFIX, name));
+        let _ = p.measure_with_start_mark(&measure, &start);
+        p.clear_marks_with_mark_name(&start);
+        p.clear_measures_with_measure_name(&measure);
+    }
+}
@@ -0,0 +1,308 @@
+// 描画の差し替え口：Canvas2D（既定）と WebGL2。どちらも Frame を受け取って1枚描く
+use crate::assets::{Assets, GAME_FONT};
+use crate::barrier::Barrier;
+use crate::canvas2d::Canvas2dRenderer;
+use crate::config::GameConfig;
+use crate::core::{Game, Rect};
+use crate::error::GameError;
+use crate::gravity::{self, Well};
+use crate::laser::{self, Laser};
+use crate::pickup::{self, Pickup};
+use crate::leaderboard::Leaderboard;
+use crate::net::Net;
+use crate::perf::FrameStats;
+use crate::settings::Palette;
+use crate::theme::Theme;
+use crate::webgl::WebGl2Renderer;
+use serde::Deserialize;
+use web_sys::HtmlCanvasElement;
+
+pub(crate) const MONO: &str = "ui-monospace, Menlo, Consolas, monospace";
+pub(crate) const SANS: &str = "ui-sans-serif, system-ui";
+
+// デバッグ表示の当たり判定（赤）と近接回避の感知範囲（黄）
+pub(crate) const DEBUG_HITBOX: &str = "#ff3b3b";
+pub(crate) const DEBUG_SENSE: &str = "#ffe14d";
+
+// ブラックホールの渦の色と中心の色
+pub(crate) const WELL_GLOW: &str = "#b48cff";
+pub(crate) const WELL_CORE: &str = "#000000";
+
+#[derive(Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RendererKind { #[default] Canvas2d, Webgl2 }
+
+pub(crate) trait Renderer {
+    fn name(&self) -> &'static str;
+    fn draw(&mut self, f: &Frame);
+    // canvas の解像度を変えた後に呼ぶ（サイズを設定するとコンテキストの状態が初期化される）
+    fn resize(&mut self, dpr: f64);
+    // コンテキストロストから戻ったとき。GPU 側の資源はすべて作り直す
+    fn restore(&mut self);
+}
+
+// 1フレームを描くのに要るもの
+pub(crate) struct Frame<'a> {
+    pub game: &'a Game,
+    pub config: &'a GameConfig,
+    pub net: Option<&'a Net>,
+    pub leaderboard: Option<&'a Leaderboard>,
+    pub assets: &'a Assets,
+    pub theme: &'a Theme,
+    pub perf: &'a FrameStats,
+    // 向きが合わず回転を促している
+    pub rotate: bool,
+}
+
+impl Frame<'_> {
+    // 1P は機体の色（色覚用・高コントラストの配色ではテーマの色のまま）
+    pub fn player_color(&self, i: usize) -> &str {
+        let ship = if self.shapes() || self.game.settings.high_contrast { None } else { self.game.ship.color() };
+        match (i, ship) { (0, Some(c)) => c, (0, None) => &self.theme.player, _ => &self.theme.player2 }
+    }
+
+    // 固定ステップの間は前後の状態を補間して描く
+    pub fn lerp(&self, from: f64, to: f64) -> f64 { from + (to - from) * self.game.alpha() }
+
+    // 色覚用の配色のときは色だけに頼らず形でも見分けられるようにする
+    pub fn shapes(&self) -> bool { self.game.settings.palette != Palette::Normal }
+
+    // 高コントラストでは文字を大きく、線を太くする
+    pub fn font(&self, bold: bool, px: f64, family: &str) -> String {
+        let px = if self.game.settings.high_contrast { (px * 1.25).round() } else { px };
+        format!("{}{}px {}", if bold { "bold " } else { "" }, px, self.family(family))
+    }
+
+    // 読み込んだフォントがあれば先頭に置く（無い文字はその後ろのフォントで描かれる）
+    pub fn family(&self, family: &str) -> String {
+        if self.assets.font_ready() { format!("{}, {}", GAME_FONT, family) } else { family.to_string() }
+    }
+
+    pub fn line_width(&self) -> f64 { if self.game.settings.high_contrast { 3.0 } else { 1.0 } }
+}
+
+// 自機の形：1P は上向きの三角、2P はひし形
+pub(crate) fn ship_shape(i: usize, x: f64, y: f64, w: f64, h: f64) -> Vec<(f64, f64)> {
+    if i == 0 {
+        vec![(x + w * 0.5, y), (x + w, y + h), (x, y + h)]
+    } else {
+        vec![(x + w * 0.5, y), (x + w, y + h * 0.5), (x + w * 0.5, y + h), (x, y + h * 0.5)]
+    }
+}
+
+// 隕石の形：角を不揃いに削った8角形。削り方は隕石の番号から決めるので落ちている間は変わらない。
+// 形は out に書き直す（隕石ごとに確保し直さないよう、描く側が out を持ち回る）
+pub(crate) fn rock_shape(out: &mut Vec<(f64, f64)>, id: u32, x: f64, y: f64, w: f64, h: f64) {
+    let mut bits = id as u64;
+    out.clear();
+    out.extend((0..8).map(|i| {
+        bits = bits.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let r = 0.75 + 0.25 * ((bits >> 40) % 1000) as f64 / 1000.0;
+        let a = std::f64::consts::TAU * i as f64 / 8.0;
+        (x + w * 0.5 * (1.0 + r * a.cos()), y + h * 0.5 * (1.0 + r * a.sin()))
+    }));
+}
+
+pub(crate) fn box_shape(out: &mut Vec<(f64, f64)>, x: f64, y: f64, w: f64, h: f64) {
+    out.clear();
+    out.extend([(x, y), (x + w, y), (x + w, y + h), (x, y + h)]);
+}
+
+// 点の並びをその場で (cx, cy) のまわりに a だけ回す（隕石の回転）
+pub(crate) fn rotate(pts: &mut [(f64, f64)], cx: f64, cy: f64, a: f64) {
+    let (s, c) = a.sin_cos();
+    for p in pts { *p = (cx + (p.0 - cx) * c - (p.1 - cy) * s, cy + (p.0 - cx) * s + (p.1 - cy) * c); }
+}
+
+// デバッグ表示の文字（下から上へ並べる）
+pub(crate) fn debug_lines(g: &Game) -> Vec<String> {
+    let p = &g.players[0].r;
+    vec![
+        format!("Player: ({:.0}, {:.0})", p.x, p.y),
+        format!("Screen: {:.0}x{:.0}", g.width, g.height),
+        format!("Spawn: next {:.2}s / every {:.2}s  meteors {}  cap x{:.2}", g.spawn_timer.max(0.0), g.spawn_interval(), g.meteors.len(), g.cap_boost()),
+        format!("Speed: {:.0}  stage {}  {}", g.speed, g.stage + 1, g.weather.kind.label()),
+    ]
+}
+
+// 背景の星（位置は固定）
+pub(crate) fn stars(w: f64, h: f64) -> impl Iterator<Item = (f64, f64)> {
+    (0..30).map(move |i| { let x = (i * 53 % 997) as f64; ((x * 7.0) % w, (x * 13.0) % h) })
+}
+
+// 砂嵐で上から視界を遮る帯（y, 高さ, 不透明度）
+pub(crate) fn fog_bands(g: &Game) -> Vec<(f64, f64, f64)> {
+    let fog = 1.0 - g.weather.kind.visibility();
+    if fog <= 0.0 { return Vec::new(); }
+    let bands = 8;
+    let fog_h = (g.players[0].r.y - 60.0).max(0.0);
+    (0..bands).map(|i| {
+        let a = fog * (1.0 - i as f64 / bands as f64) + fog * 0.5;
+        (fog_h * i as f64 / bands as f64, fog_h / bands as f64 + 1.0, a.min(0.95))
+    }).collect()
+}
+
+// イオンストームを予告する画面のノイズ（x, y, 幅）。高さは 1px
+pub(crate) fn static_noise(g: &Game) -> Vec<(f64, f64, f64)> {
+    let noise = g.weather.static_level();
+    if noise <= 0.0 { return Vec::new(); }
+    let seed = (g.stats.elapsed * 60.0) as u64;
+    (0..(120.0 * noise) as u64).map(|i| {
+        let h = (seed.wrapping_mul(6364136223846793005).wrapping_add(i.wrapping_mul(1442695040888963407))) >> 16;
+        let x = (h % 1000) as f64 / 1000.0 * g.width;
+        let y = ((h >> 12) % 1000) as f64 / 1000.0 * g.height;
+        (x, y, 1.0 + (h % 24) as f64)
+    }).collect()
+}
+
+// 残像を出し始める落下速度（px/秒）と、残像1つぶんの時間差（秒）
+const TRAIL_SPEED: f64 = 260.0;
+const TRAIL_STEP: f64 = 0.018;
+
+// 速い隕石の残像（x, y, w, h, 不透明度）。後ろほど細く薄く、速いほど長い
+pub(crate) fn meteor_trails(f: &Frame) -> Vec<(f64, f64, f64, f64, f64)> {
+    let g = f.game;
+    if !g.settings.meteor_trails || g.settings.reduced_motion { return Vec::new(); }
+    let mut out = Vec::new();
+    for m in g.meteors.iter().filter(|m| m.vy > TRAIL_SPEED) {
+        let y = f.lerp(m.prev_y, m.r.y);
+        let n = ((m.vy - TRAIL_SPEED) / 80.0).clamp(1.0, 4.0) as usize;
+        for i in 1..=n {
+            let k = i as f64 / (n + 1) as f64;
+            let w = m.r.w * (1.0 - 0.5 * k);
+            out.push((m.r.x + (m.r.w - w) * 0.5, y - m.vy * TRAIL_STEP * i as f64, w, m.r.h, 0.35 * (1.0 - k)));
+        }
+    }
+    out
+}
+
+// ブラックホールの渦の腕の本数と、1本あたりの点の数
+const WELL_ARMS: usize = 3;
+const WELL_DOTS: usize = 20;
+
+// ブラックホールの渦（x, y, 大きさ, 不透明度）。外から中心へ巻き込む腕で、内側ほど大きく濃い。動きを減らす設定では回さない
+pub(crate) fn well_swirl(f: &Frame, w: &Well) -> Vec<(f64, f64, f64, f64)> {
+    let y = f.lerp(w.prev_y, w.y);
+    let spin = if f.game.settings.reduced_motion { 0.0 } else { w.angle };
+    let outer = gravity::REACH * 0.4;
+    let mut out = Vec::with_capacity(WELL_ARMS * WELL_DOTS);
+    for arm in 0..WELL_ARMS {
+        for i in 0..WELL_DOTS {
+            let t = i as f64 / WELL_DOTS as f64;
+            let r = gravity::CORE + (outer - gravity::CORE) * (1.0 - t);
+            let a = spin + arm as f64 * std::f64::consts::TAU / WELL_ARMS as f64 + t * 4.0;
+            out.push((w.x + r * a.cos(), y + r * a.sin(), 1.5 + 2.5 * t, 0.15 + 0.6 * t));
+        }
+    }
+    out
+}
+
+// 壁の色と、縁の明るい線の色
+pub(crate) const BARRIER: &str = "#5c6478";
+pub(crate) const BARRIER_EDGE: &str = "#c8d0e0";
+
+// 壁の左右の矩形（補間した位置）
+pub(crate) fn barrier_walls(f: &Frame, b: &Barrier) -> [Rect; 2] { b.walls(f.lerp(b.prev_y, b.y), f.game.width) }
+
+// レーザーの色（予告の列と、撃っている光の外側と芯）
+pub(crate) const LASER: &str = "#ff3b5c";
+pub(crate) const LASER_CORE: &str = "#ffffff";
+
+// レーザーの縦の帯（x, 幅, 色, 不透明度）。予告のあいだは列を薄く点滅させて中心に細い線を引き（動きを減らす設定では点滅させない）、
+// 撃っている間は太い外側に白い芯を重ねる
+pub(crate) fn laser_bands(f: &Frame, l: &Laser) -> Vec<(f64, f64, &'static str, f64)> {
+    if !l.firing() {
+        let blink = if f.game.settings.reduced_motion { 0.2 } else if (l.t * 8.0) as i32 % 2 == 0 { 0.3 } else { 0.12 };
+        return vec![(l.x - laser::WIDTH * 0.5, laser::WIDTH, LASER, blink), (l.x - 0.5, 1.0, LASER, 0.8)];
+    }
+    let (outer, core) = (laser::WIDTH * 1.6 * l.power(), laser::WIDTH * 0.4 * l.power());
+    vec![(l.x - outer * 0.5, outer, LASER, 0.6), (l.x - core * 0.5, core, LASER_CORE, 1.0)]
+}
+
+// 拾いものの色。罠は宝石と同じ形で、赤みがかった色と暗い芯で見分ける
+pub(crate) const GEM: &str = "#ffd34d";
+pub(crate) const GEM_CORE: &str = "#ffffff";
+pub(crate) const TRAP: &str = "#ff7a4d";
+pub(crate) const TRAP_CORE: &str = "#3a0a10";
+
+// 拾いものの菱形（外側と芯、それぞれの色）
+pub(crate) fn pickup_shape(f: &Frame, p: &Pickup) -> [(Vec<(f64, f64)>, &'static str); 2] {
+    let (x, y, r) = (p.x, f.lerp(p.prev_y, p.y), pickup::SIZE * 0.5);
+    let diamond = |r: f64| vec![(x, y - r), (x + r, y), (x, y + r), (x - r, y)];
+    let (outer, core) = if p.kind.trap() { (TRAP, TRAP_CORE) } else { (GEM, GEM_CORE) };
+    [(diamond(r), outer), (diamond(r * 0.4), core)]
+}
+
+// 落ちる直前の再生で、当てた隕石を囲む色
+pub(crate) const FATAL_COLOR: &str = "#ff4040";
+
+// 落ちた位置の分布の色：少ない列は青、多くなるにつれ黄を経て赤（level は 0..1）
+pub(crate) fn heat_color(level: f64) -> String {
+    let k = level.clamp(0.0, 1.0);
+    let stops = [(0.0, (40.0, 80.0, 200.0)), (0.5, (250.0, 210.0, 60.0)), (1.0, (235.0, 50.0, 40.0))];
+    let i = if k < 0.5 { 0 } else { 1 };
+    let ((a, ca), (b, cb)) = (stops[i], stops[i + 1]);
+    let t = (k - a) / (b - a);
+    let mix = |x: f64, y: f64| (x + (y - x) * t).round() as u8;
+    format!("rgb({},{},{})", mix(ca.0, cb.0), mix(ca.1, cb.1), mix(ca.2, cb.2))
+}
+
+// スコアの節目で背景をアクセントの色でうっすら光らせる（不透明度）。動きを減らす・高コントラストでは光らせない
+const PULSE_ALPHA: f64 = 0.12;
+
+pub(crate) fn milestone_pulse(g: &Game) -> f64 {
+    if g.settings.reduced_motion || g.settings.high_contrast { return 0.0; }
+    g.celebration.map_or(0.0, |c| PULSE_ALPHA * c.pulse())
+}
+
+// 罠の霧：自機のまわりの窓だけ残して画面を覆う矩形と不透明度
+const FOG_WINDOW: f64 = 90.0;
+pub(crate) const FOG_COLOR: &str = "#05060a";
+
+pub(crate) fn trap_fog(f: &Frame) -> Option<([Rect; 4], f64)> {
+    let g = f.game;
+    let level = g.traps.fog_level();
+    let p = g.players.iter().find(|p| p.alive)?;
+    if level <= 0.0 { return None; }
+    let (cx, cy) = (f.lerp(p.prev_x, p.r.x) + p.r.w * 0.5, p.r.y + p.r.h * 0.5);
+    let (l, r) = ((cx - FOG_WINDOW).max(0.0), (cx + FOG_WINDOW).min(g.width));
+    let (t, b) = ((cy - FOG_WINDOW).max(0.0), (cy + FOG_WINDOW).min(g.height));
+    Some(([
+        Rect { x: 0.0, y: 0.0, w: g.width, h: t },
+        Rect { x: 0.0, y: b, w: g.width, h: g.height - b },
+        Rect { x: 0.0, y: t, w: l, h: b - t },
+        Rect { x: r, y: t, w: g.width - r, h: b - t },
+    ], 0.92 * level))
+}
+
+// 円を近似した多角形（WebGL 版のブラックホールの中心）
+pub(crate) fn disc(x: f64, y: f64, r: f64) -> Vec<(f64, f64)> {
+    (0..16).map(|i| { let a = std::f64::consts::TAU * i as f64 / 16.0; (x + r * a.cos(), y + r * a.sin()) }).collect()
+}
+
+// 突風の筋の数と流れる速さ（px/秒）
+const STREAKS: u64 = 40;
+const STREAK_SPEED: f64 = 600.0;
+pub(crate) const STREAK_COLOR: &str = "#dcebff";
+
+// 突風の筋（x, y, 長さ, 不透明度）。高さは 1px。高さと長さは番号で決め、風下へ流す（動きを減らす設定では止めておく）
+pub(crate) fn wind_streaks(g: &Game) -> Vec<(f64, f64, f64, f64)> {
+    let a = g.wind.visibility();
+    if a <= 0.0 { return Vec::new(); }
+    let shift = if g.settings.reduced_motion { 0.0 } else { g.stats.elapsed * STREAK_SPEED * g.wind.dir };
+    let span = g.width + 80.0;
+    (0..(STREAKS as f64 * a) as u64).map(|i| {
+        let h = i.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407) >> 16;
+        let (len, y) = (20.0 + (h % 40) as f64, ((h >> 8) % 1000) as f64 / 1000.0 * g.height);
+        let x = ((h >> 20) % 1000) as f64 / 1000.0 * span + shift * (0.6 + (h % 5) as f64 * 0.1);
+        (x.rem_euclid(span) - 80.0, y, len, 0.5 * a)
+    }).collect()
+}
+
+// 指定の種類で作る。WebGL2 が使えない環境では Canvas2D にする
+pub(crate) fn create(canvas: &HtmlCanvasElement, kind: RendererKind, dpr: f64) -> Result<Box<dyn Renderer>, GameError> {
+    if kind == RendererKind::Webgl2 {
+        if let Some(r) = WebGl2Renderer::new(canvas, dpr) { return Ok(Box::new(r)); }
+    }
+    Ok(Box::new(Canvas2dRenderer::new(canvas, dpr)?))
+}
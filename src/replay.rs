@@ -0,0 +1,100 @@
+// リプレイ：シード + 毎フレームの (dt, 入力) を記録し、同じ順で update() を回して再現する
+use crate::settings::Difficulty;
+use crate::ships::ShipKind;
+use crate::shop::Owned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// dt は 10µs 単位で記録する（シミュレーションの固定ステップもこの単位の整数倍なので、再生時とズレない）
+pub(crate) const DT_UNIT: f64 = 1e-5;
+// v2 で難易度を追加（v1 は normal として読む）、v3 で店の強化を追加（それより前は強化なし）、v4 で機体を追加（それより前は classic）
+const VERSION: u32 = 4;
+
+// 入力ビット
+const LEFT: u32 = 1;
+const RIGHT: u32 = 2;
+
+fn pack(dt: f64, left: bool, right: bool) -> u32 {
+    let units = ((dt / DT_UNIT).round() as u32).min(0xFF_FFFF);
+    (units << 8) | if left { LEFT } else { 0 } | if right { RIGHT } else { 0 }
+}
+
+fn unpack(v: u32) -> (f64, bool, bool) {
+    ((v >> 8) as f64 * DT_UNIT, v & LEFT != 0, v & RIGHT != 0)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    // そのランに効いていた店の強化（shop.rs）
+    #[serde(default)]
+    pub upgrades: Owned,
+    #[serde(default)]
+    pub ship: ShipKind,
+    // 同じフレームが続く分はまとめる（フレーム値, 連続数）
+    frames: Vec<(u32, u32)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self { Self { seed, difficulty, upgrades: Owned::default(), ship: ShipKind::Classic, frames: Vec::new() } }
+
+    pub fn record(&mut self, dt: f64, left: bool, right: bool) {
+        let v = pack(dt, left, right);
+        match self.frames.last_mut() {
+            Some((last, n)) if *last == v => *n += 1,
+            _ => self.frames.push((v, 1)),
+        }
+    }
+
+    // {"version":4,"seed":"...","difficulty":"normal","upgrades":[エネルギー,速さ],"ship":"classic","frames":[値,連続数,...]}（u64 は JS の数値に収まらないので文字列）
+    pub fn to_json(&self) -> String {
+        let frames: Vec<String> = self.frames.iter().map(|(v, n)| format!("{},{}", v, n)).collect();
+        format!("{{\"version\":{},\"seed\":\"{}\",\"difficulty\":\"{}\",\"upgrades\":[{},{}],\"ship\":\"{}\",\"frames\":[{}]}}",
+            VERSION, self.seed, self.difficulty.name(), self.upgrades.energy, self.upgrades.speed, self.ship.name(), frames.join(","))
+    }
+
+    pub fn from_json(json: &str) -> Result<Replay, String> {
+        let obj: Value = serde_json::from_str(json).map_err(|e| format!("replay: {}", e))?;
+        let version = obj["version"].as_f64().unwrap_or(0.0) as u32;
+        if !(1..=VERSION).contains(&version) {
+            return Err("unsupported replay version".to_string());
+        }
+        let difficulty = match obj["difficulty"].as_str() {
+            Some(d) => Difficulty::parse(d).ok_or("unknown replay difficulty")?,
+            None => Difficulty::Normal,
+        };
+        let seed = obj["seed"].as_str()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or("replay seed is missing or invalid")?;
+        let level = |i: usize| obj["upgrades"][i].as_f64().unwrap_or(0.0) as u32;
+        let upgrades = Owned { energy: level(0), speed: level(1) };
+        let ship = obj["ship"].as_str().and_then(ShipKind::parse).unwrap_or_default();
+        let flat: Vec<u32> = obj["frames"].as_array().map_or(&[][..], |a| a).iter()
+            .map(|v| v.as_f64().map(|f| f as u32))
+            .collect::<Option<_>>()
+            .ok_or("replay frames must be numbers")?;
+        if !flat.len().is_multiple_of(2) {
+            return Err("replay frames are truncated".to_string());
+        }
+        Ok(Replay { seed, difficulty, upgrades, ship, frames: flat.chunks(2).map(|c| (c[0], c[1])).collect() })
+    }
+}
+
+// 再生位置
+pub(crate) struct Playback { replay: Replay, run: usize, used: u32 }
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self { Self { replay, run: 0, used: 0 } }
+
+    // 次のフレームの (dt, left, right)。最後まで再生したら None
+    pub fn peek(&self) -> Option<(f64, bool, bool)> {
+        self.replay.frames.get(self.run).map(|&(v, _)| unpack(v))
+    }
+
+    pub fn advance(&mut self) {
+        let Some(&(_, n)) = self.replay.frames.get(self.run) else { return };
+        self.used += 1;
+        if self.used >= n { self.run += 1; self.used = 0; }
+    }
+}
@@ -0,0 +1,33 @@
+// 乱数源。Game が1つずつ持ち、コンストラクタから差し替えられる（ヘッドレス版では外からも渡せる）
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+    // 同じシードを渡したら同じ列を返すこと（デイリーやリプレイの再現に使う）
+    fn reseed(&mut self, seed: u64);
+    // 途中保存用：内部状態をそのまま出し入れする
+    fn state(&self) -> u64;
+    fn set_state(&mut self, state: u64);
+
+    fn next_f64(&mut self) -> f64 { ((self.next_u64() & 0xFFFF_FFFF) as f64) / (u32::MAX as f64) }
+    fn rand_between(&mut self, a: f64, b: f64) -> f64 { a + (b - a) * self.next_f64() }
+}
+
+// シンプルなXorshift（JSのMath.randomを呼ばずにRust側で）
+pub(crate) const DEFAULT_SEED: u64 = 0x1234_5678_90ab_cdef;
+
+pub struct XorShift { state: u64 }
+
+impl XorShift {
+    // 0 だと回らないので 1 に寄せる
+    pub fn new(seed: u64) -> Self { Self { state: seed.max(1) } }
+}
+
+impl Rng for XorShift {
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        *s ^= *s << 7; *s ^= *s >> 9; *s ^= *s << 8;
+        *s
+    }
+    fn reseed(&mut self, seed: u64) { self.state = seed.max(1); }
+    fn state(&self) -> u64 { self.state }
+    fn set_state(&mut self, state: u64) { self.state = state.max(1); }
+}
@@ -0,0 +1,217 @@
+// ランの途中保存：盤面・スコア・乱数の状態まで丸ごと JsValue にして、ページを読み直しても続きから遊べるようにする
+// （ヘッドレス版は JSON の文字列で）
+use crate::barrier::Barrier;
+use crate::daily::DailyRun;
+use crate::ghost::GhostTrack;
+use crate::laser::Laser;
+use crate::pickup::{Pickup, Traps};
+use crate::replay::Replay;
+use crate::settings::Difficulty;
+use crate::ships::ShipKind;
+use crate::shop::Owned;
+use crate::weather::WeatherState;
+use crate::wind::Wind;
+use crate::core::{Game, Meteor, Player, RunStats, SCORE_EVENT_STEP};
+use crate::gravity::Well;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedRun {
+    version: u32,
+    seed: u64,
+    rng_state: u64,
+    difficulty: Difficulty,
+    #[serde(default = "full_speed")]
+    game_speed: f64,
+    #[serde(default)]
+    assist: bool,
+    versus: bool,
+    // デイリーは日付だけ持てばシードとベストは復元できる
+    daily_date: Option<String>,
+    // 練習モードの開始スコアと固定シード（読み込んでも練習のままで、記録やランキングに残さない）
+    #[serde(default)]
+    practice: Option<f64>,
+    #[serde(default)]
+    fixed_seed: Option<u64>,
+    // チュートリアルとデモの途中は続きから遊ぶものではないので、読み込みを断る
+    #[serde(default)]
+    tutorial: bool,
+    #[serde(default)]
+    demo: bool,
+    players: Vec<Player>,
+    meteors: Vec<Meteor>,
+    // 次に出す隕石の番号（一番新しい隕石がもう消えていても続きの番号がずれないように）。無い保存は残った隕石から数え直す
+    #[serde(default)]
+    next_id: Option<u32>,
+    #[serde(default)]
+    wells: Vec<Well>,
+    #[serde(default = "first_well")]
+    well_timer: f64,
+    #[serde(default)]
+    wind: Wind,
+    #[serde(default)]
+    barriers: Vec<Barrier>,
+    #[serde(default = "first_barrier")]
+    barrier_timer: f64,
+    #[serde(default)]
+    lasers: Vec<Laser>,
+    #[serde(default = "first_laser")]
+    laser_timer: f64,
+    #[serde(default)]
+    pickups: Vec<Pickup>,
+    #[serde(default = "first_pickup")]
+    pickup_timer: f64,
+    #[serde(default)]
+    traps: Traps,
+    #[serde(default)]
+    upgrades: Owned,
+    #[serde(default)]
+    ship: ShipKind,
+    spawn_timer: f64,
+    // 上限で続けて見送った出現の数（cap_boost の速さが読み込みで戻らないように）
+    #[serde(default)]
+    capped_spawns: u32,
+    score: f64,
+    speed: f64,
+    over: bool,
+    stage: usize,
+    weather: WeatherState,
+    stats: RunStats,
+    // 続きからでもリプレイとゴーストが最初から通しで残るように
+    recording: Replay,
+    ghost_run: GhostTrack,
+}
+
+impl SavedRun {
+    pub fn capture(g: &Game) -> Self {
+        Self {
+            version: VERSION,
+            seed: g.seed,
+            rng_state: g.rng.state(),
+            difficulty: g.difficulty,
+            game_speed: g.game_speed,
+            assist: g.assist,
+            versus: g.versus,
+            daily_date: g.daily.as_ref().map(|d| d.date.clone()),
+            practice: g.practice,
+            fixed_seed: g.fixed_seed,
+            tutorial: g.tutorial.is_some(),
+            demo: g.demo,
+            players: g.players.clone(),
+            meteors: g.meteors.clone(),
+            next_id: Some(g.next_id),
+            wells: g.wells.clone(),
+            well_timer: g.well_timer,
+            wind: g.wind.clone(),
+            barriers: g.barriers.clone(),
+            barrier_timer: g.barrier_timer,
+            lasers: g.lasers.clone(),
+            laser_timer: g.laser_timer,
+            pickups: g.pickups.clone(),
+            pickup_timer: g.pickup_timer,
+            traps: g.traps,
+            upgrades: g.upgrades,
+            ship: g.ship,
+            spawn_timer: g.spawn_timer,
+            capped_spawns: g.capped_spawns,
+            score: g.score,
+            speed: g.speed,
+            over: g.over,
+            stage: g.stage,
+            weather: g.weather.clone(),
+            stats: g.stats.clone(),
+            recording: g.recording.clone(),
+            ghost_run: g.ghost_run.clone(),
+        }
+    }
+
+    pub fn to_js(&self) -> Result<JsValue, JsValue> {
+        // u64（シードと乱数状態）は BigInt で出す
+        let ser = serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+        self.serialize(&ser).map_err(JsValue::from)
+    }
+
+    pub fn from_js(v: JsValue) -> Result<Self, JsValue> {
+        let saved: SavedRun = serde_wasm_bindgen::from_value(v)?;
+        saved.check().map_err(JsValue::from_str)
+    }
+
+    #[cfg(feature = "headless")]
+    pub fn to_json(&self) -> String { serde_json::to_string(self).unwrap_or_default() }
+
+    #[cfg(feature = "headless")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let saved: SavedRun = serde_json::from_str(json).map_err(|e| format!("save state: {}", e))?;
+        saved.check().map_err(str::to_string)
+    }
+
+    fn check(self) -> Result<Self, &'static str> {
+        if self.version != VERSION { return Err("unsupported save state version"); }
+        if self.players.is_empty() { return Err("save state has no players"); }
+        if self.tutorial || self.demo { return Err("save state is not from a normal run"); }
+        Ok(self)
+    }
+
+    pub fn apply(self, g: &mut Game) {
+        g.seed = self.seed;
+        g.rng.set_state(self.rng_state);
+        g.difficulty = self.difficulty;
+        g.game_speed = self.game_speed;
+        g.assist = self.assist;
+        g.versus = self.versus;
+        g.daily = self.daily_date.map(|date| DailyRun::for_date(&date, &*g.storage));
+        g.practice = self.practice;
+        g.fixed_seed = self.fixed_seed;
+        g.tutorial = None;
+        g.demo = false;
+        // 押しっぱなしのキーは持ち越さない
+        g.players = self.players;
+        for p in &mut g.players { p.input = Default::default(); }
+        g.next_id = self.next_id.unwrap_or_else(|| self.meteors.iter().map(|m| m.id.wrapping_add(1)).max().unwrap_or(0));
+        g.meteors = self.meteors;
+        g.wells = self.wells;
+        g.well_timer = self.well_timer;
+        g.wind = self.wind;
+        g.barriers = self.barriers;
+        g.barrier_timer = self.barrier_timer;
+        g.lasers = self.lasers;
+        g.laser_timer = self.laser_timer;
+        g.pickups = self.pickups;
+        g.pickup_timer = self.pickup_timer;
+        g.traps = self.traps;
+        g.upgrades = self.upgrades;
+        g.ship = self.ship;
+        g.spawn_timer = self.spawn_timer;
+        g.capped_spawns = self.capped_spawns;
+        g.score = self.score;
+        // 読み込んだ直前の節目から数え直す（読み込み直後に score / milestone を出さない）
+        g.last_score_step = (g.score / SCORE_EVENT_STEP) as i64;
+        g.speed = self.speed;
+        g.over = self.over;
+        g.stage = self.stage;
+        g.weather = self.weather;
+        g.stats = self.stats;
+        g.recording = self.recording;
+        g.ghost_run = self.ghost_run;
+        g.playback = None;
+        // 読み込む前のランで積まれたまま配られていないイベントは捨てる
+        g.events.clear();
+        g.settle();
+        g.popups.clear();
+        g.celebration = None;
+        g.clear_aftermath();
+        g.new_record = false;
+    }
+}
+
+// ゲーム速度が無い保存（速度の設定ができる前）は等速
+fn full_speed() -> f64 { 1.0 }
+
+// ブラックホールが無い保存は、ランの始めと同じ間をおく
+fn first_well() -> f64 { crate::gravity::FIRST }
+fn first_barrier() -> f64 { crate::barrier::FIRST }
+fn first_laser() -> f64 { crate::laser::FIRST }
+fn first_pickup() -> f64 { crate::pickup::FIRST }
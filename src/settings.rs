@@ -0,0 +1,184 @@
+// ユーザー設定。Storage（ブラウザでは localStorage）に {"version":N, ...} の JSON で保存し、起動時に読み込む
+use crate::ships::ShipKind;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+const STORAGE_KEY: &str = "meteor_dodge.settings";
+const VERSION: u32 = 1;
+// ゲーム速度の下限
+pub(crate) const MIN_GAME_SPEED: f64 = 0.5;
+
+// 古い形式を1つ新しい形式に直す関数（MIGRATIONS[i] が v(i+1) → v(i+2)）。
+// スキーマを変えたら VERSION を上げてここに足す
+type Migration = fn(&mut Map<String, Value>);
+const MIGRATIONS: &[Migration] = &[];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ControlScheme { Both, Arrows, Wasd }
+
+impl ControlScheme {
+    pub fn name(self) -> &'static str {
+        match self { ControlScheme::Both => "both", ControlScheme::Arrows => "arrows", ControlScheme::Wasd => "wasd" }
+    }
+    fn parse(s: &str) -> Option<Self> {
+        match s { "both" => Some(ControlScheme::Both), "arrows" => Some(ControlScheme::Arrows), "wasd" => Some(ControlScheme::Wasd), _ => None }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Difficulty { Easy, Normal, Hard }
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self { Difficulty::Easy => "easy", Difficulty::Normal => "normal", Difficulty::Hard => "hard" }
+    }
+    pub fn parse(s: &str) -> Option<Self> {
+        match s { "easy" => Some(Difficulty::Easy), "normal" => Some(Difficulty::Normal), "hard" => Some(Difficulty::Hard), _ => None }
+    }
+    // 落下速度とスポーン頻度にかける倍率
+    pub fn scale(self) -> f64 {
+        match self { Difficulty::Easy => 0.8, Difficulty::Normal => 1.0, Difficulty::Hard => 1.25 }
+    }
+}
+
+// 色覚の型に合わせた配色。normal 以外では形でも見分けられるように描く（自機は三角、隕石はごつごつした多角形）
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Palette { Normal, Deuteranopia, Protanopia, Tritanopia }
+
+impl Palette {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "normal" => Some(Palette::Normal),
+            "deuteranopia" => Some(Palette::Deuteranopia),
+            "protanopia" => Some(Palette::Protanopia),
+            "tritanopia" => Some(Palette::Tritanopia),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Settings {
+    // 全体の音量。BGM と効果音はさらにそれぞれの音量をかける
+    pub volume: f64,
+    pub music_volume: f64,
+    pub sfx_volume: f64,
+    // M キーで切り替える消音（音量は覚えたまま）
+    pub muted: bool,
+    pub control_scheme: ControlScheme,
+    pub theme: String,
+    pub difficulty: Difficulty,
+    // 画面の揺れ・残像・パーティクル・自機の傾きを止める（当たり判定や隕石の動きは同じ）
+    pub reduced_motion: bool,
+    pub show_ghost: bool,
+    // 速い隕石の後ろに残像を描く（重い端末や reducedMotion なら切る）
+    pub meteor_trails: bool,
+    pub palette: Palette,
+    // 真っ黒な背景・明るい輪郭・大きな文字。半透明の幕は使わない
+    pub high_contrast: bool,
+    // 被弾・近接回避・ゲームオーバーで端末を振動させる（対応していない端末では何も起きない）
+    pub vibration: bool,
+    // 省電力：描画を config の lowPowerFps（既定 30fps）までに抑える
+    pub battery_saver: bool,
+    // ゲーム全体の速さ（0.5〜1.0、次のランから）。1.0 未満はアシストとして結果画面に出す
+    pub game_speed: f64,
+    // アシストモード（次のランから）：難しくなるのを遅く、当たり判定を少し小さく、被弾しても減って戻るエネルギーのメーター（energy.rs）
+    pub assist: bool,
+    // 拾いものに罠を混ぜる（次のランから）
+    pub trap_pickups: bool,
+    // 使う機体（次のランから。まだ解除していなければ classic で出る）
+    pub ship: ShipKind,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 0.8,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            muted: false,
+            control_scheme: ControlScheme::Both,
+            theme: "default".to_string(),
+            difficulty: Difficulty::Normal,
+            reduced_motion: false,
+            show_ghost: true,
+            meteor_trails: true,
+            palette: Palette::Normal,
+            high_contrast: false,
+            vibration: true,
+            battery_saver: false,
+            game_speed: 1.0,
+            assist: false,
+            trap_pickups: true,
+            ship: ShipKind::Classic,
+        }
+    }
+}
+
+// 保存する形（版を先頭に足す）
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u32,
+    #[serde(flatten)]
+    settings: &'a Settings,
+}
+
+impl Settings {
+    // 保存がない・壊れている・未来の版なら defaults（保存にない項目も defaults のまま）
+    pub fn load(storage: &dyn Storage, defaults: Settings) -> Self {
+        let mut s = defaults;
+        if let Some(Value::Object(mut obj)) = storage.get(STORAGE_KEY).and_then(|json| serde_json::from_str(&json).ok()) {
+            let version = obj.get("version").and_then(Value::as_f64).unwrap_or(0.0) as u32;
+            if (1..=VERSION).contains(&version) {
+                for m in &MIGRATIONS[(version - 1) as usize..] { m(&mut obj); }
+                s.merge(&obj);
+            }
+        }
+        s
+    }
+
+    pub fn save(&self, storage: &dyn Storage) {
+        storage.set(STORAGE_KEY, &self.to_json());
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Stored { version: VERSION, settings: self }).unwrap_or_default()
+    }
+
+    // 一部だけの JSON も受け付ける（書かれていない項目は今の値のまま）
+    pub fn update_from_json(&mut self, json: &str) -> Result<(), String> {
+        match serde_json::from_str(json) {
+            Ok(Value::Object(obj)) => { self.merge(&obj); Ok(()) }
+            Ok(_) => Err("settings must be a JSON object".to_string()),
+            Err(e) => Err(format!("settings: {}", e)),
+        }
+    }
+
+    // 知らない値や型違いは無視する
+    fn merge(&mut self, obj: &Map<String, Value>) {
+        let get = |k: &str| obj.get(k).unwrap_or(&Value::Null);
+        if let Some(v) = get("volume").as_f64() { self.volume = v.clamp(0.0, 1.0); }
+        if let Some(v) = get("musicVolume").as_f64() { self.music_volume = v.clamp(0.0, 1.0); }
+        if let Some(v) = get("sfxVolume").as_f64() { self.sfx_volume = v.clamp(0.0, 1.0); }
+        if let Some(v) = get("muted").as_bool() { self.muted = v; }
+        if let Some(v) = get("controlScheme").as_str().and_then(ControlScheme::parse) { self.control_scheme = v; }
+        if let Some(v) = get("theme").as_str() { self.theme = v.to_string(); }
+        if let Some(v) = get("difficulty").as_str().and_then(Difficulty::parse) { self.difficulty = v; }
+        if let Some(v) = get("reducedMotion").as_bool() { self.reduced_motion = v; }
+        if let Some(v) = get("showGhost").as_bool() { self.show_ghost = v; }
+        if let Some(v) = get("meteorTrails").as_bool() { self.meteor_trails = v; }
+        if let Some(v) = get("palette").as_str().and_then(Palette::parse) { self.palette = v; }
+        if let Some(v) = get("highContrast").as_bool() { self.high_contrast = v; }
+        if let Some(v) = get("vibration").as_bool() { self.vibration = v; }
+        if let Some(v) = get("batterySaver").as_bool() { self.battery_saver = v; }
+        if let Some(v) = get("gameSpeed").as_f64() { self.game_speed = v.clamp(MIN_GAME_SPEED, 1.0); }
+        if let Some(v) = get("assist").as_bool() { self.assist = v; }
+        if let Some(v) = get("trapPickups").as_bool() { self.trap_pickups = v; }
+        if let Some(v) = get("ship").as_str().and_then(ShipKind::parse) { self.ship = v; }
+    }
+}
@@ -0,0 +1,59 @@
+// 自機の種類。設定の ship からランの始めに決まり、大きさ・速さ・色と能力が変わる。
+// classic は最初から、ほかはベストスコアか実績で解除する。店の強化と同じく、エンドレスの1人プレイ以外は classic に揃える
+use crate::core::Game;
+use serde::{Deserialize, Serialize};
+
+// dash：動き始めか向きを変えた瞬間に DASH 秒だけ DASH_BOOST 倍で動く（使ったら COOLDOWN 秒は出ない）
+pub(crate) const DASH: f64 = 0.15;
+pub(crate) const DASH_BOOST: f64 = 3.0;
+pub(crate) const COOLDOWN: f64 = 1.2;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ShipKind { #[default] Classic, Scout, Hauler, Dasher }
+
+pub(crate) const SHIPS: [ShipKind; 4] = [ShipKind::Classic, ShipKind::Scout, ShipKind::Hauler, ShipKind::Dasher];
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Unlock { Always, Best(f64), Achievement(&'static str) }
+
+impl ShipKind {
+    pub fn name(self) -> &'static str {
+        match self { ShipKind::Classic => "classic", ShipKind::Scout => "scout", ShipKind::Hauler => "hauler", ShipKind::Dasher => "dasher" }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> { SHIPS.into_iter().find(|k| k.name() == s) }
+
+    // 大きさ（幅・高さ）と移動の速さの倍率
+    pub fn size(self) -> f64 { match self { ShipKind::Scout => 0.75, ShipKind::Hauler => 1.3, _ => 1.0 } }
+    pub fn speed(self) -> f64 { match self { ShipKind::Scout => 0.85, ShipKind::Hauler => 1.2, _ => 1.0 } }
+
+    pub fn dash(self) -> bool { self == ShipKind::Dasher }
+
+    // 1P の色（標準の配色のときだけ。無ければテーマの色）
+    pub fn color(self) -> Option<&'static str> {
+        match self { ShipKind::Classic => None, ShipKind::Scout => Some("#7fe3ff"), ShipKind::Hauler => Some("#ffb347"), ShipKind::Dasher => Some("#c28bff") }
+    }
+
+    pub fn unlock(self) -> Unlock {
+        match self {
+            ShipKind::Classic => Unlock::Always,
+            ShipKind::Scout => Unlock::Best(2000.0),
+            ShipKind::Hauler => Unlock::Achievement("survive_60"),
+            ShipKind::Dasher => Unlock::Achievement("close_10"),
+        }
+    }
+
+    pub fn unlocked(self, g: &Game) -> bool {
+        match self.unlock() {
+            Unlock::Always => true,
+            Unlock::Best(score) => g.high_score.best >= score,
+            Unlock::Achievement(id) => g.achievements.is_unlocked(id),
+        }
+    }
+}
+
+// 解除済みの組（ビットは SHIPS の順）。ゲームオーバーで前と比べて、新しく解除されたものを知らせる
+pub(crate) fn unlocked_mask(g: &Game) -> u8 {
+    SHIPS.iter().enumerate().filter(|(_, k)| k.unlocked(g)).fold(0, |m, (i, _)| m | 1 << i)
+}
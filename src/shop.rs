@@ -0,0 +1,152 @@
+// コインと強化の店。記録に残るランで拾った宝石（pickup.rs）が1個1枚のコインになり、ランの合間（ゲームオーバー画面）に
+// U キーで開く店で永続の強化を買う。強化はエンドレスの1人プレイにだけ効き（デイリー・挑戦・対戦・練習は全員同じ条件）、
+// リプレイにも付けておいて再生で同じにする。保存は "版;コイン;エネルギー,速さ"（版が新しすぎる・壊れていれば無し）
+use crate::core::Game;
+use crate::i18n::Msg;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "meteor_dodge.shop";
+const VERSION: u32 = 1;
+// 強化1段あたり：エネルギーのメーターの大きさ、移動の速さの割合
+const ENERGY_STEP: f64 = 40.0;
+const SPEED_STEP: f64 = 0.05;
+// 行の高さと幅（描画とタップで同じ並び。最後の行は閉じる）
+const ROW_H: f64 = 30.0;
+const ROW_W: f64 = 320.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Upgrade { Energy, Speed }
+
+pub(crate) const UPGRADES: [Upgrade; 2] = [Upgrade::Energy, Upgrade::Speed];
+
+impl Upgrade {
+    pub fn label(self) -> Msg { match self { Upgrade::Energy => Msg::ShopEnergy, Upgrade::Speed => Msg::ShopSpeed } }
+
+    // 段ごとの値段（買える段の数もこれで決まる）
+    fn prices(self) -> &'static [u64] { match self { Upgrade::Energy => &[15, 30, 50], Upgrade::Speed => &[10, 20, 35] } }
+
+    pub fn max_level(self) -> u32 { self.prices().len() as u32 }
+
+    // 次の段の値段（もう上がらなければ None）
+    pub fn price(self, owned: &Owned) -> Option<u64> { self.prices().get(owned.level(self) as usize).copied() }
+}
+
+// 買った段
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Owned { pub energy: u32, pub speed: u32 }
+
+impl Owned {
+    pub fn level(&self, u: Upgrade) -> u32 { match u { Upgrade::Energy => self.energy, Upgrade::Speed => self.speed } }
+
+    // 最初から持つエネルギーのメーターの大きさ（energy.rs に足す）
+    pub fn energy(&self) -> f64 { self.energy as f64 * ENERGY_STEP }
+
+    // 移動の速さにかける倍率
+    pub fn speed_scale(&self) -> f64 { 1.0 + self.speed as f64 * SPEED_STEP }
+}
+
+#[derive(Default)]
+pub(crate) struct Shop {
+    pub coins: u64,
+    pub owned: Owned,
+    // 開いているか（ゲームオーバー画面の上に出す）と、選んでいる行
+    pub open: bool,
+    pub sel: usize,
+}
+
+impl Shop {
+    pub fn load(storage: &dyn Storage) -> Self {
+        let parse = |v: String| -> Option<Self> {
+            let mut parts = v.split(';');
+            let version: u32 = parts.next()?.parse().ok()?;
+            if !(1..=VERSION).contains(&version) { return None; }
+            let coins = parts.next()?.parse().ok()?;
+            let (energy, speed) = parts.next()?.split_once(',')?;
+            // 段を減らした版から戻ってきたときのために上限で切る
+            let owned = Owned {
+                energy: energy.parse::<u32>().ok()?.min(Upgrade::Energy.max_level()),
+                speed: speed.parse::<u32>().ok()?.min(Upgrade::Speed.max_level()),
+            };
+            Some(Self { coins, owned, ..Self::default() })
+        };
+        storage.get(STORAGE_KEY).and_then(parse).unwrap_or_default()
+    }
+
+    fn save(&self, storage: &dyn Storage) {
+        storage.set(STORAGE_KEY, &format!("{};{};{},{}", VERSION, self.coins, self.owned.energy, self.owned.speed));
+    }
+
+    pub fn earn(&mut self, coins: u64, storage: &dyn Storage) {
+        if coins == 0 { return; }
+        self.coins += coins;
+        self.save(storage);
+    }
+
+    // 買えたら true
+    pub fn buy(&mut self, u: Upgrade, storage: &dyn Storage) -> bool {
+        let Some(price) = u.price(&self.owned) else { return false };
+        if self.coins < price { return false; }
+        self.coins -= price;
+        match u { Upgrade::Energy => self.owned.energy += 1, Upgrade::Speed => self.owned.speed += 1 }
+        self.save(storage);
+        true
+    }
+}
+
+// i 行目の矩形 (x, y, w, h)（UPGRADES の後に「閉じる」）
+pub(crate) fn row(g: &Game, i: usize) -> (f64, f64, f64, f64) {
+    let w = ROW_W.min(g.width - 20.0);
+    let top = g.height * 0.5 - (UPGRADES.len() + 1) as f64 * ROW_H * 0.5 + 10.0;
+    (g.width * 0.5 - w * 0.5, top + i as f64 * ROW_H, w, ROW_H)
+}
+
+// i 行目の右側に出す値（段と次の値段）
+pub(crate) fn value(g: &Game, i: usize) -> String {
+    let Some(&u) = UPGRADES.get(i) else { return String::new() };
+    let level = format!("{}/{}", g.shop.owned.level(u), u.max_level());
+    match u.price(&g.shop.owned) {
+        Some(p) => g.lang.fill(Msg::ShopPrice, &[&level, &p]),
+        None => g.lang.fill(Msg::ShopMaxed, &[&level]),
+    }
+}
+
+// ゲームオーバー画面の U で開く・閉じる（対戦の結果画面では開かない）
+pub(crate) fn toggle(g: &mut Game) {
+    if !g.over || g.versus { return; }
+    g.shop.open = !g.shop.open;
+    g.shop.sel = 0;
+    g.heatmap.open = false;
+}
+
+fn activate(g: &mut Game, i: usize) {
+    let Some(&u) = UPGRADES.get(i) else { g.shop.open = false; return };
+    if g.shop.buy(u, &*g.storage) {
+        g.toasts.push(g.lang.text(Msg::ShopBought).to_string(), 1.5);
+    }
+}
+
+// 開いている間のキー。開いていれば他のキー（R でやり直しなど）は効かせない
+pub(crate) fn key(g: &mut Game, key: &str) -> bool {
+    if !g.shop.open { return false; }
+    let n = UPGRADES.len() + 1;
+    match key {
+        "ArrowUp" => g.shop.sel = (g.shop.sel + n - 1) % n,
+        "ArrowDown" => g.shop.sel = (g.shop.sel + 1) % n,
+        "Enter" | " " => activate(g, g.shop.sel),
+        "Escape" | "Backspace" | "u" | "U" => g.shop.open = false,
+        _ => {}
+    }
+    true
+}
+
+// 開いている間のタップ。行を選んで決定する
+pub(crate) fn tap(g: &mut Game, x: f64, y: f64) -> bool {
+    if !g.shop.open { return false; }
+    let inside = |(rx, ry, rw, rh): (f64, f64, f64, f64)| x >= rx && x < rx + rw && y >= ry && y < ry + rh;
+    if let Some(i) = (0..=UPGRADES.len()).find(|&i| inside(row(g, i))) {
+        g.shop.sel = i;
+        activate(g, i);
+    }
+    true
+}
@@ -0,0 +1,116 @@
+// ゲームの進行だけに使う設定（web_sys / js_sys の型を持たないので、ヘッドレスやベンチでもそのまま Game::new に渡せる）。
+// ページからは GameConfig に平らに混ぜて渡す（config.rs）
+use crate::anim::AnimationConfig;
+use crate::collide::HitboxShape;
+use crate::i18n::Lang;
+use crate::level::Level;
+use crate::settings::{ControlScheme, Palette};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct SimConfig {
+    // 最初のランのシード（省略時は既定のシード）
+    pub seed: Option<u64>,
+    pub player: PlayerConfig,
+    pub spawn: SpawnConfig,
+    // ウェーブの並び（level.rs）。省略時はスコアで進む組み込みのステージ表。あとから GameCell::set_level でも差し替えられる
+    pub level: Option<Level>,
+    // 指定するとユーザー設定より優先（保存はしない）
+    pub control_scheme: Option<ControlScheme>,
+    // 色覚の型に合わせた配色（"deuteranopia" / "protanopia" / "tritanopia"）。指定するとユーザー設定より優先
+    pub palette: Option<Palette>,
+    // 画面と読み上げの言語（"en" / "ja"）。省略時はブラウザの言語（対応していなければ英語）
+    pub language: Option<Lang>,
+    // タイトル（ゲームオーバー画面）で 15 秒放っておくと、ボットが遊ぶデモを始める
+    pub attract: bool,
+    // update / 隕石の出現 / 描画を DevTools の Performance パネルに区間として出す（ページの URL に ?profile を付けても有効）
+    pub profile: bool,
+    // OS の prefers-reduced-motion（JS からは渡さない。起動時にページ側で調べて入れる）
+    #[serde(skip)]
+    pub prefers_reduced_motion: bool,
+    // アトラスのコマ送り（ページからは assets.animations に書く。GameConfig::from_js がここへ写す）
+    #[serde(skip)]
+    pub animations: AnimationConfig,
+}
+
+// hitboxShrink は当たり判定を描いた大きさから縮める割合（0〜0.9、0.2 なら幅・高さとも 80%。中心はそのまま）。
+// energy はエネルギーのメーターの大きさ（energy.rs。0 なら1回の被弾で終わり、アシストモードは少なくとも 100）
+#[derive(Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct PlayerConfig { pub width: f64, pub height: f64, pub speed: f64, pub hitbox_shrink: f64, pub energy: f64 }
+
+// 間隔 = max(intervalMin, interval - スコア * intervalRamp)、落下速度 = baseSpeed + スコア * speedRamp (+ 0..speedSpread)
+#[derive(Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct SpawnConfig {
+    pub interval: f64,
+    pub interval_min: f64,
+    pub interval_ramp: f64,
+    pub base_speed: f64,
+    pub speed_ramp: f64,
+    pub speed_spread: f64,
+    pub size_min: f64,
+    pub size_max: f64,
+    // 隕石の当たり判定："circle"（既定）か、回転に合わせた "box"
+    pub hitbox: HitboxShape,
+    // 同時に出ている隕石の上限と、1秒あたりの出現数の上限（遅い端末向け。省略時は無し）。
+    // 上限で減らした分は、新しく落とす隕石を速くして埋め合わせる（最大 1.5 倍）
+    pub max_meteors: Option<usize>,
+    pub max_rate: Option<f64>,
+    // ブラックホールが出るおよその間隔（秒、この 0.5〜1.5 倍でばらつく。0 で出さない）。最初の1つはランの 30 秒後
+    pub well_interval: f64,
+    // スコア 4000 からの突風のおよその間隔（秒、この 0.5〜1.5 倍でばらつく。0 で吹かせない）
+    pub wind_interval: f64,
+    // すき間の開いた壁が出るおよその間隔（秒、この 0.5〜1.5 倍でばらつく。0 で出さない）。最初の1つはランの 20 秒後
+    pub barrier_interval: f64,
+    // 予告つきのレーザーが出るおよその間隔（秒、この 0.5〜1.5 倍でばらつく。0 で出さない）。最初の1本はランの 40 秒後
+    pub laser_interval: f64,
+    // 拾いもの（宝石と罠）が落ちてくるおよその間隔（秒、この 0.5〜1.5 倍でばらつく。0 で出さない）
+    pub pickup_interval: f64,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            player: PlayerConfig::default(),
+            spawn: SpawnConfig::default(),
+            level: None,
+            control_scheme: None,
+            palette: None,
+            language: None,
+            attract: true,
+            profile: false,
+            prefers_reduced_motion: false,
+            animations: AnimationConfig::default(),
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self { Self { width: 30.0, height: 20.0, speed: 220.0, hitbox_shrink: 0.2, energy: 0.0 } }
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self {
+            interval: 1.2,
+            interval_min: 0.8,
+            interval_ramp: 0.001,
+            base_speed: 120.0,
+            speed_ramp: 0.6,
+            speed_spread: 160.0,
+            size_min: 10.0,
+            size_max: 24.0,
+            hitbox: HitboxShape::Circle,
+            max_meteors: None,
+            max_rate: None,
+            well_interval: 45.0,
+            wind_interval: 20.0,
+            barrier_interval: 30.0,
+            laser_interval: 25.0,
+            pickup_interval: 12.0,
+        }
+    }
+}
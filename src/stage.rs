@@ -0,0 +1,21 @@
+// ステージ表：スコアで進み、表を一周したら先頭に戻る
+use crate::i18n::Msg;
+use crate::weather::Weather;
+
+// 1ステージあたりのスコア
+pub(crate) const STAGE_SCORE: f64 = 1500.0;
+
+// name は Lang::text で引く表示名
+pub(crate) struct Stage { pub name: Msg, pub weather: Weather }
+
+pub(crate) const STAGES: &[Stage] = &[
+    Stage { name: Msg::StageLowOrbit, weather: Weather::Clear },
+    Stage { name: Msg::StageDustBelt, weather: Weather::DustStorm },
+    Stage { name: Msg::StageOuterRing, weather: Weather::Clear },
+    Stage { name: Msg::StageIonField, weather: Weather::IonStorm },
+];
+
+// スコアから何ステージ目か（0 始まり）
+pub(crate) fn stage_index(score: f64) -> usize { (score / STAGE_SCORE) as usize }
+
+pub(crate) fn stage(index: usize) -> &'static Stage { &STAGES[index % STAGES.len()] }
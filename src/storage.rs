@@ -0,0 +1,22 @@
+// 保存先。Game が1つずつ持つ（ブラウザでは localStorage、ヘッドレスとベンチマークではメモリ上）
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// このゲームが使うキーはすべてこれで始まる
+pub(crate) const KEY_PREFIX: &str = "meteor_dodge.";
+
+pub(crate) trait Storage {
+    // 使えない・未保存なら None
+    fn get(&self, key: &str) -> Option<String>;
+    // 書けなくても黙って諦める（プライベートモードや容量超過）
+    fn set(&self, key: &str, value: &str);
+}
+
+// そのインスタンスの間だけ覚える
+#[derive(Default)]
+pub(crate) struct MemoryStorage { items: RefCell<HashMap<String, String>> }
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> { self.items.borrow().get(key).cloned() }
+    fn set(&self, key: &str, value: &str) { self.items.borrow_mut().insert(key.to_string(), value.to_string()); }
+}
@@ -0,0 +1,93 @@
+// 効果音の合成（ファイルなしで配るとき用）。ノイズとオシレーターに音量の包絡をかけて、その場で鳴らす
+use crate::core::Sound;
+use crate::rng::{Rng, XorShift};
+use web_sys::{AudioBuffer, AudioContext, AudioNode, AudioScheduledSourceNode, BiquadFilterType, GainNode, OscillatorType};
+
+// ノイズの長さ（秒）。鳴らすたびに使い回す
+const NOISE_SECONDS: f64 = 0.5;
+
+// unlock のときに1度だけ作る白色ノイズ
+pub(crate) fn noise_buffer(ctx: &AudioContext) -> Option<AudioBuffer> {
+    let rate = ctx.sample_rate();
+    let len = (rate as f64 * NOISE_SECONDS) as usize;
+    let buf = ctx.create_buffer(1, len as u32, rate).ok()?;
+    let mut rng = XorShift::new(0x5eed_f0a0_d1ce);
+    let samples: Vec<f32> = (0..len).map(|_| rng.next_f64() as f32 * 2.0 - 1.0).collect();
+    buf.copy_to_channel(&samples, 0).ok()?;
+    Some(buf)
+}
+
+pub(crate) fn play(ctx: &AudioContext, out: &AudioNode, noise: &AudioBuffer, sound: Sound) {
+    let t = ctx.current_time();
+    match sound {
+        // 落ちてくる隕石の風切り音：高い方から下がる帯域ノイズ
+        Sound::Spawn => { let _ = noise_burst(ctx, out, noise, t, 0.25, 0.15, (2400.0, 600.0)); }
+        // 近接回避：短く上がる音
+        Sound::NearMiss => { let _ = tone(ctx, out, OscillatorType::Sine, t, 0.12, 0.25, (660.0, 1320.0)); }
+        // 宝石：明るく上がる2音
+        Sound::Pickup => {
+            let _ = tone(ctx, out, OscillatorType::Sine, t, 0.1, 0.25, (880.0, 880.0));
+            let _ = tone(ctx, out, OscillatorType::Sine, t + 0.08, 0.16, 0.25, (1318.5, 1318.5));
+        }
+        // 罠：濁って下がるブザー2回
+        Sound::Trap => {
+            for i in 0..2 {
+                let _ = tone(ctx, out, OscillatorType::Sawtooth, t + 0.14 * i as f64, 0.12, 0.18, (330.0, 165.0));
+            }
+        }
+        // 節目：澄んだ3音の上がる和音
+        Sound::Milestone => {
+            for (i, f) in [784.0, 987.8, 1568.0].into_iter().enumerate() {
+                let _ = tone(ctx, out, OscillatorType::Triangle, t + 0.07 * i as f64, 0.4, 0.2, (f, f));
+            }
+        }
+        // 被弾：低いノイズの破裂と、落ちていく矩形波
+        Sound::Hit => {
+            let _ = noise_burst(ctx, out, noise, t, 0.45, 0.6, (1200.0, 80.0));
+            let _ = tone(ctx, out, OscillatorType::Square, t, 0.35, 0.2, (220.0, 40.0));
+        }
+        // ゲームオーバー：下がっていく4音
+        Sound::GameOver => {
+            for (i, f) in [659.3, 523.3, 440.0, 349.2].into_iter().enumerate() {
+                let _ = tone(ctx, out, OscillatorType::Square, t + 0.18 * i as f64, 0.16, 0.15, (f, f));
+            }
+        }
+    }
+}
+
+// 鳴らし始めに peak、len 秒で消える音量
+fn envelope(ctx: &AudioContext, out: &AudioNode, t: f64, len: f64, peak: f32) -> Option<GainNode> {
+    let gain = ctx.create_gain().ok()?;
+    let param = gain.gain();
+    param.set_value_at_time(peak, t).ok()?;
+    param.exponential_ramp_to_value_at_time(0.001, t + len).ok()?;
+    gain.connect_with_audio_node(out).ok()?;
+    Some(gain)
+}
+
+// freq は (始め, 終わり) の周波数
+fn tone(ctx: &AudioContext, out: &AudioNode, kind: OscillatorType, t: f64, len: f64, peak: f32, freq: (f32, f32)) -> Option<()> {
+    let gain = envelope(ctx, out, t, len, peak)?;
+    let osc = ctx.create_oscillator().ok()?;
+    osc.set_type(kind);
+    osc.frequency().set_value_at_time(freq.0, t).ok()?;
+    osc.frequency().exponential_ramp_to_value_at_time(freq.1, t + len).ok()?;
+    osc.connect_with_audio_node(&gain).ok()?;
+    AudioScheduledSourceNode::start_with_when(&osc, t).ok()?;
+    AudioScheduledSourceNode::stop_with_when(&osc, t + len).ok()
+}
+
+// cutoff は (始め, 終わり) のバンドパスの中心周波数
+fn noise_burst(ctx: &AudioContext, out: &AudioNode, noise: &AudioBuffer, t: f64, len: f64, peak: f32, cutoff: (f32, f32)) -> Option<()> {
+    let gain = envelope(ctx, out, t, len, peak)?;
+    let filter = ctx.create_biquad_filter().ok()?;
+    filter.set_type(BiquadFilterType::Bandpass);
+    filter.frequency().set_value_at_time(cutoff.0, t).ok()?;
+    filter.frequency().exponential_ramp_to_value_at_time(cutoff.1, t + len).ok()?;
+    filter.connect_with_audio_node(&gain).ok()?;
+    let src = ctx.create_buffer_source().ok()?;
+    src.set_buffer(Some(noise));
+    src.connect_with_audio_node(&filter).ok()?;
+    AudioScheduledSourceNode::start_with_when(&src, t).ok()?;
+    AudioScheduledSourceNode::stop_with_when(&src, t + len).ok()
+}
@@ -0,0 +1,585 @@
+// update() の中身をシステムごとに分けたもの。Game::update が決まった順に呼ぶ
+// （動かす → 当たり判定 → ボーナス → 掃除 → スコアと進行、の順に意味がある）。
+// 物は種類ごとの Vec（Meteor・Player・Well・Laser・Barrier・Pickup）に丸ごと持ち、位置・速さ・当たり判定・見た目を
+// 別々の入れ物（コンポーネント）には分けていない。並びの順が乱数を引く順と途中保存の形をそのまま決めるので、
+// 新しい種類は Vec と tick_* を1つずつ足す。隕石だけはランの中で一意な id を持つ（形・回り方・デスカメラの目印）
+use crate::anim::Animation;
+use crate::barrier::{self, Barrier};
+use crate::core::{swap_retain, Cue, Game, GameEvent, Meteor, Popup, Rect, Sample, Sound, DEATH_SCALE, DEATH_SLOW, DEATH_ZOOM, FIXED_DT, NEAR_MISS_BONUS, SCORE_EVENT_STEP, TILT_RATE};
+use crate::director::Spawn;
+use crate::energy::{self, Energy};
+use crate::gravity::{self, Well};
+use crate::i18n::Msg;
+use crate::laser::{self, Laser};
+use crate::milestone::{self, Celebration};
+use crate::tutorial::{self, Lesson};
+use crate::particles::ParticleKind;
+use crate::pickup::{self, Pickup, PickupKind};
+use crate::profile;
+use crate::ships;
+use crate::stage;
+use crate::wind::{self, Phase};
+
+impl Game {
+    // 入力（イオンストームのスタッター中は効かない）
+    pub(crate) fn move_players(&mut self, dt: f64) {
+        let move_speed = self.player_config.speed * self.ship.speed() * self.upgrades.speed_scale();
+        let dash = self.ship.dash();
+        let blocked = self.weather.input_blocked();
+        let invert = self.traps.invert > 0.0;
+        for p in self.players.iter_mut().filter(|p| p.alive) {
+            let mut dir = 0.0;
+            if !blocked {
+                // 罠で左右が逆になっている間は入れ替える
+                let (left, right) = if invert { (p.input.right, p.input.left) } else { (p.input.left, p.input.right) };
+                if left { p.r.x -= move_speed * dt; dir -= 1.0; }
+                if right { p.r.x += move_speed * dt; dir += 1.0; }
+            }
+            // dasher は動き始めか向きを変えた瞬間に飛び出す
+            if dash {
+                p.dash_cd = (p.dash_cd - dt).max(0.0);
+                if dir != 0.0 && dir != p.last_dir && p.dash_cd == 0.0 { p.dash = ships::DASH; p.dash_cd = ships::COOLDOWN; }
+                if p.dash > 0.0 {
+                    p.r.x += dir * move_speed * (ships::DASH_BOOST - 1.0) * dt;
+                    p.dash = (p.dash - dt).max(0.0);
+                }
+            }
+            p.last_dir = dir;
+            // ブラックホールに流される分（引かれていなければ 0）
+            p.r.x += p.drift * dt;
+            p.drift -= p.drift * (gravity::DRAG * dt).min(1.0);
+            p.r.x = p.r.x.clamp(0.0, self.width - p.r.w);
+            // 壁が同じ段にあれば、すき間の外へは出られない
+            for b in &self.barriers { if b.in_row(&p.r) { b.confine(&mut p.r); } }
+            p.tilt += (dir - p.tilt) * (TILT_RATE * dt).min(1.0);
+            p.energy.tick(dt);
+        }
+        let input = self.players[0].input;
+        if input.left || input.right { self.stats.moved = true; }
+    }
+
+    pub(crate) fn spawn_meteors(&mut self, dt: f64) {
+        // チュートリアル中は tick_tutorial が落とす
+        if self.tutorial.is_some() { return; }
+        let _span = profile::span(self.profile, "spawn");
+        self.spawn_timer -= dt;
+        self.spawn_boss(dt);
+        if self.spawn_timer > 0.0 { return; }
+        // ページが出し方を決めているならそちらに任せる（呼んでいる間は Game を見せるので取り出しておく）
+        if let Some(mut d) = self.director.take() {
+            let plan = d.next(self);
+            self.director = Some(d);
+            self.spawn_timer = plan.next.unwrap_or_else(|| self.spawn_interval()).max(FIXED_DT);
+            for s in plan.meteors {
+                if self.crowded() { self.capped_spawns += 1; } else { self.capped_spawns = 0; self.spawn_meteor(s); }
+            }
+            return;
+        }
+        self.spawn_timer = self.spawn_interval();
+        // 上限まで出ていたら見送る（その分は cap_boost で速くする）
+        if self.crowded() { self.capped_spawns += 1; return; }
+        self.capped_spawns = 0;
+        let x = self.rng.next_f64();
+        // レベルにウェーブの隕石の種類があればその中から選ぶ
+        let kind = match &self.level {
+            Some(l) => l.at(self.stats.elapsed).1.pick(self.rng.next_f64()),
+            None => None,
+        };
+        let s = match kind {
+            Some(k) => Spawn {
+                x,
+                size: Some(self.rng.rand_between(k.size_min, k.size_max)),
+                speed: Some(self.rng.rand_between(self.speed, self.speed + self.spawn_config.speed_spread) * k.speed_scale),
+                hitbox: k.hitbox,
+            },
+            None => Spawn { x, size: None, speed: None, hitbox: None },
+        };
+        self.spawn_meteor(s);
+    }
+
+    // レベルのボス：ウェーブの at 秒をまたいだステップで1つだけ落とす（はみ出さない範囲で横位置は乱数）
+    fn spawn_boss(&mut self, dt: f64) {
+        let Some((wave, t)) = self.wave() else { return };
+        let Some(b) = wave.boss.as_ref().filter(|b| t <= b.at && b.at < t + dt) else { return };
+        let (size, speed, hitbox) = (b.size, b.speed, b.hitbox);
+        let room = 1.0 - (size * self.size_scale() / self.width).min(1.0);
+        let x = self.rng.next_f64() * room;
+        self.spawn_meteor(Spawn { x, size: Some(size), speed: Some(speed), hitbox });
+    }
+
+    fn spawn_meteor(&mut self, s: Spawn) {
+        let sp = &self.spawn_config;
+        let scale = self.size_scale();
+        let x = s.x * (self.width - 14.0 * scale);
+        let size = s.size.unwrap_or_else(|| self.rng.rand_between(sp.size_min, sp.size_max)) * scale;
+        let vy = s.speed.unwrap_or_else(|| self.rng.rand_between(self.speed, self.speed + sp.speed_spread));
+        let mut m = Meteor::new(self.next_id, Rect { x, y: -size, w: size, h: size }, vy, s.hitbox.unwrap_or(sp.hitbox));
+        self.next_id = self.next_id.wrapping_add(1);
+        if let Some(c) = &self.clips.meteor { m.anim = Animation::starting_at(x as usize % c.frames.len().max(1)); }
+        self.meteors.push(m);
+        self.events.push(GameEvent::MeteorSpawned { x: x + size * 0.5 });
+    }
+
+    // ベンチマーク用：隕石が n 個になるまで、画面の上から下までばらばらの高さに置く（出現のイベントは出さない）
+    pub(crate) fn fill_meteors(&mut self, n: usize) {
+        let first_event = self.events.len();
+        while self.meteors.len() < n {
+            let x = self.rng.next_f64();
+            self.spawn_meteor(Spawn { x, size: None, speed: None, hitbox: None });
+            let y = (self.rng.next_f64() * 1.5 - 0.5) * self.height;
+            if let Some(m) = self.meteors.last_mut() { m.r.y = y; m.prev_y = y; }
+        }
+        self.events.truncate(first_event);
+    }
+
+    // ブラックホール：まれに出して落とし、自機と隕石に引く力を速さとしてためる（動かすのは move_players / move_meteors）。
+    // チュートリアルとページが出し方を決めているときは出さない
+    pub(crate) fn tick_wells(&mut self, dt: f64) {
+        let interval = self.spawn_config.well_interval;
+        if interval > 0.0 && self.tutorial.is_none() && self.director.is_none() {
+            self.well_timer -= dt;
+            if self.well_timer <= 0.0 {
+                self.well_timer = self.rng.rand_between(interval * 0.5, interval * 1.5);
+                let margin = gravity::CORE * 3.0;
+                let x = self.rng.rand_between(margin, (self.width - margin).max(margin));
+                self.wells.push(Well::new(x));
+                self.toasts.push(self.lang.text(Msg::BlackHole).to_string(), 2.0);
+            }
+        }
+        if self.wells.is_empty() { return; }
+        for w in &mut self.wells { w.fall(dt); }
+        let height = self.height;
+        swap_retain(&mut self.wells, |w| !w.gone(height));
+        // 自機は横にだけ流される
+        for p in self.players.iter_mut().filter(|p| p.alive) {
+            p.drift += gravity::total_pull(&self.wells, p.r.x + p.r.w * 0.5, p.r.y + p.r.h * 0.5).0 * dt;
+        }
+        for m in &mut self.meteors {
+            let (ax, ay) = gravity::total_pull(&self.wells, m.r.x + m.r.w * 0.5, m.r.y + m.r.h * 0.5);
+            m.vx += ax * dt;
+            m.vy = (m.vy + ay * dt).max(gravity::MIN_FALL);
+        }
+    }
+
+    // 突風（wind.rs）：終盤に間をおいて予告してから吹かせ、吹いている間は自機と隕石に横向きの力をかける（ブラックホールと同じく速さにためる）
+    pub(crate) fn tick_wind(&mut self, dt: f64) {
+        let interval = self.spawn_config.wind_interval;
+        let calm = self.wind.phase == Phase::Calm;
+        if calm && (interval <= 0.0 || self.score < wind::FROM_SCORE || self.tutorial.is_some() || self.director.is_some()) { return; }
+        if self.wind.tick(dt) { self.wind.next = self.rng.rand_between(interval * 0.5, interval * 1.5); }
+        if self.wind.phase == Phase::Calm && self.wind.next <= 0.0 {
+            let dir = if self.rng.next_f64() < 0.5 { -1.0 } else { 1.0 };
+            self.wind.warn(dir);
+        }
+        let force = self.wind.force();
+        if force == 0.0 { return; }
+        for p in self.players.iter_mut().filter(|p| p.alive) { p.drift += force * wind::PLAYER_PUSH * dt; }
+        for m in &mut self.meteors { m.vx += force * wind::METEOR_PUSH * dt; }
+    }
+
+    // すき間の開いた壁（barrier.rs）：出して落とす。すき間の外にいる自機の上まで来たら、そこで止める
+    pub(crate) fn tick_barriers(&mut self, dt: f64) {
+        let interval = self.spawn_config.barrier_interval;
+        if interval > 0.0 && self.tutorial.is_none() && self.director.is_none() {
+            self.barrier_timer -= dt;
+            // 一度に1つまで（出ている間は、消えてから出す）
+            if self.barrier_timer <= 0.0 && self.barriers.is_empty() {
+                self.barrier_timer = self.rng.rand_between(interval * 0.5, interval * 1.5);
+                let w = self.players.iter().find(|p| p.alive).map_or(self.player_config.width, |p| p.r.w);
+                let gap_w = (w * barrier::GAP).min(self.width);
+                let row = self.players.iter().filter(|p| p.alive).map(|p| p.r.y).fold(self.height, f64::min);
+                let xs = &mut self.scratch.aims;
+                xs.clear();
+                xs.extend(self.players.iter().filter(|p| p.alive).map(|p| p.r.x + p.r.w * 0.5));
+                let reach = self.player_config.speed * Barrier::travel(row);
+                let t = self.rng.next_f64();
+                self.barriers.push(Barrier::new(barrier::place(xs, reach, gap_w, self.width, t), gap_w));
+            }
+        }
+        if self.barriers.is_empty() { return; }
+        for b in &mut self.barriers {
+            let stop = self.players.iter()
+                .filter(|p| p.alive && !b.fits(&p.r) && b.y + barrier::THICK <= p.r.y + 1e-6)
+                .map(|p| p.r.y).reduce(f64::min);
+            b.fall(dt, stop);
+        }
+        let height = self.height;
+        swap_retain(&mut self.barriers, |b| !b.gone(height));
+    }
+
+    // 予告つきのレーザー（laser.rs）：自機の近くの列を狙って出し、時間で進める。当たり判定は collide で
+    pub(crate) fn tick_lasers(&mut self, dt: f64) {
+        let interval = self.spawn_config.laser_interval;
+        if interval > 0.0 && self.tutorial.is_none() && self.director.is_none() {
+            self.laser_timer -= dt;
+            if self.laser_timer <= 0.0 {
+                self.laser_timer = self.rng.rand_between(interval * 0.5, interval * 1.5);
+                // 対戦ではどちらかを狙う
+                let alive = &mut self.scratch.aims;
+                alive.clear();
+                alive.extend(self.players.iter().filter(|p| p.alive).map(|p| p.r.x + p.r.w * 0.5));
+                let k = self.rng.next_f64();
+                if let Some(&cx) = alive.get((k * alive.len() as f64) as usize) {
+                    let half = laser::WIDTH * 0.5;
+                    let x = (cx + self.rng.rand_between(-laser::AIM, laser::AIM)).clamp(half, (self.width - half).max(half));
+                    self.lasers.push(Laser::new(x));
+                }
+            }
+        }
+        if self.lasers.is_empty() { return; }
+        for l in &mut self.lasers { l.t += dt; }
+        swap_retain(&mut self.lasers, |l| !l.done());
+    }
+
+    // 拾いもの（pickup.rs）：出して落とし、自機が触れたら効かせる。罠は trap_pickups のランだけ
+    pub(crate) fn tick_pickups(&mut self, dt: f64) {
+        self.traps.tick(dt);
+        let interval = self.spawn_config.pickup_interval;
+        if interval > 0.0 && self.tutorial.is_none() && self.director.is_none() {
+            self.pickup_timer -= dt;
+            if self.pickup_timer <= 0.0 {
+                self.pickup_timer = self.rng.rand_between(interval * 0.5, interval * 1.5);
+                let half = pickup::SIZE * 0.5;
+                let x = self.rng.rand_between(half, (self.width - half).max(half));
+                // 罠を出さないランでも乱数の使い方は同じにする
+                let (roll, which) = (self.rng.next_f64(), self.rng.next_f64());
+                let kind = if self.trap_pickups && roll < pickup::TRAP_CHANCE {
+                    pickup::TRAPS[((which * pickup::TRAPS.len() as f64) as usize).min(pickup::TRAPS.len() - 1)]
+                } else { PickupKind::Gem };
+                self.pickups.push(Pickup::new(x, kind));
+            }
+        }
+        if self.pickups.is_empty() { return; }
+        for p in &mut self.pickups { p.fall(dt); }
+        let taken = &mut self.scratch.taken;
+        taken.clear();
+        for (k, item) in self.pickups.iter().enumerate() {
+            let r = item.rect();
+            let Some((i, p)) = self.players.iter_mut().enumerate().find(|(_, p)| p.alive && p.r.intersects(&r)) else { continue };
+            taken.push(k);
+            let mut bonus = 0.0;
+            if item.kind == PickupKind::Gem {
+                // 近接回避と同じくコンボをつなぎ、その倍率をかける
+                bonus = pickup::BONUS * p.combo.hit();
+                p.score += bonus;
+                if i == 0 { self.stats.gems += 1; }
+            }
+            self.traps.start(item.kind);
+            let text = match item.kind {
+                PickupKind::Gem => self.lang.fill(Msg::GemBonus, &[&bonus]),
+                PickupKind::Invert => self.lang.text(Msg::TrapInvert).to_string(),
+                PickupKind::Fog => self.lang.text(Msg::TrapFog).to_string(),
+                PickupKind::Rush => self.lang.text(Msg::TrapRush).to_string(),
+            };
+            // 罠は効いている間ずっと出しておく
+            self.toasts.push(text, if item.kind.trap() { item.kind.seconds() } else { 1.5 });
+            self.events.push(GameEvent::PowerUp { kind: item.kind.name(), trap: item.kind.trap(), x: item.x });
+        }
+        for &k in taken.iter().rev() { self.pickups.swap_remove(k); }
+        let height = self.height;
+        swap_retain(&mut self.pickups, |p| !p.gone(height));
+    }
+
+    pub(crate) fn move_meteors(&mut self, dt: f64) {
+        // 罠の rush の間は速く落とす
+        let k = self.traps.meteor_scale();
+        for m in &mut self.meteors { m.r.y += m.vy * k * dt; m.r.x += m.vx * dt; m.angle += m.spin * dt; }
+    }
+
+    // 見た目だけのパーティクル（reducedMotion では出さない）
+    pub(crate) fn emit_effects(&mut self) {
+        if self.settings.reduced_motion { return; }
+        // 下端に届いた隕石は燃え尽きる
+        for m in self.meteors.iter().filter(|m| m.prev_y + m.r.h < self.height && m.r.y + m.r.h >= self.height) {
+            self.particles.burst(ParticleKind::Ember, m.r.x + m.r.w * 0.5, self.height, 8);
+        }
+        for p in self.players.iter().filter(|p| p.alive) {
+            if self.particles.chance(0.5) { self.particles.burst(ParticleKind::Exhaust, p.r.x + p.r.w * 0.5, p.r.y + p.r.h, 1); }
+            // 横に動いている間は、後ろ側の角から小さな炎
+            if p.tilt.abs() > 0.3 {
+                let x = if p.tilt > 0.0 { p.r.x + 2.0 } else { p.r.x + p.r.w - 2.0 };
+                self.particles.burst(ParticleKind::Flame, x, p.r.y + p.r.h, 1);
+            }
+            if p.dash > 0.0 { self.particles.burst(ParticleKind::Exhaust, p.r.x + p.r.w * 0.5, p.r.y + p.r.h * 0.5, 3); }
+        }
+    }
+
+    pub(crate) fn tick_animations(&mut self, dt: f64) {
+        if let Some(c) = &self.clips.player { for p in &mut self.players { p.anim.tick(dt, c); } }
+        if let Some(c) = &self.clips.meteor { for m in &mut self.meteors { m.anim.tick(dt, c); } }
+    }
+
+    // 自機の近くの隕石だけ調べる（感知範囲は当たり判定を含むので、被弾もニアミスもこの候補で足りる）
+    pub(crate) fn collide(&mut self) {
+        self.grid.clear();
+        for (i, m) in self.meteors.iter().enumerate() {
+            // このステップで通った範囲ごと入れる
+            let s = m.sense_rect();
+            let d = (m.r.y - m.prev_y).max(0.0);
+            self.grid.insert(i, &Rect { y: s.y - d, h: s.h + d, ..s });
+        }
+        let shrink = self.hitbox_shrink();
+        let scale = self.size_scale();
+        let effects = !self.settings.reduced_motion;
+        // エネルギーで持ちこたえた隕石（ループの後で消す）
+        self.scratch.absorbed.clear();
+        // 決着した後のゆっくりの間は、残った方にも当たらない
+        let settled = self.dying.is_some();
+        for (i, p) in self.players.iter_mut().enumerate() {
+            if !p.alive || settled { continue; }
+            self.grid.query(&p.r, &mut self.nearby);
+            let bit = 1 << i;
+            let (hits, wells, lasers) = (&mut self.scratch.hits, &mut self.scratch.wells, &mut self.scratch.lasers);
+            hits.clear();
+            let hitbox = p.hitbox(shrink);
+            for &j in &self.nearby {
+                let m = &mut self.meteors[j as usize];
+                if m.swept_hits(&hitbox) { hits.push((m.id, m.r.w / scale)); }
+                if m.near_miss_awarded & bit == 0 && m.sense_rect().intersects(&p.r) { m.grazed |= bit; }
+            }
+            // ブラックホールの中心と、撃っているレーザーの列も被弾
+            wells.clear();
+            wells.extend((0..self.wells.len()).filter(|&k| self.wells[k].core().intersects_rect(&hitbox)));
+            let height = self.height;
+            lasers.clear();
+            lasers.extend((0..self.lasers.len()).filter(|&k| self.lasers[k].firing() && self.lasers[k].beam(height).intersects(&hitbox)));
+            if hits.is_empty() && wells.is_empty() && lasers.is_empty() { continue; }
+            // 減る量は隕石の大きさの合計と、ブラックホール・レーザーの決まった量
+            let damage = hits.iter().map(|&(_, w)| Energy::meteor_damage(w)).sum::<f64>()
+                + wells.len() as f64 * energy::WELL + lasers.len() as f64 * energy::LASER;
+            if i == 0 { self.stats.damage += damage.min(p.energy.value); }
+            if p.energy.absorb(damage) {
+                // 持ちこたえたら、当たったものは消す
+                self.scratch.absorbed.extend(hits.iter().map(|&(id, _)| id));
+                for &k in wells.iter().rev() { self.wells.swap_remove(k); }
+                for &k in lasers.iter().rev() { self.lasers.swap_remove(k); }
+                if effects {
+                    self.particles.burst(ParticleKind::Spark, p.r.x + p.r.w * 0.5, p.r.y, 12);
+                    self.camera.kick(4.0);
+                }
+            } else {
+                if i == 0 { self.last_moments.fatal = hits.first().map(|&(id, _)| id); }
+                p.alive = false;
+                p.combo.reset();
+                // 同時に当たったら隕石・ブラックホール・レーザーの順
+                let cause = if !hits.is_empty() { "meteor" } else if !wells.is_empty() { "well" } else { "laser" };
+                self.events.push(GameEvent::Collision { player: i as u32 + 1, x: p.r.x + p.r.w * 0.5, y: p.r.y + p.r.h * 0.5, cause });
+            }
+        }
+        let absorbed = &self.scratch.absorbed;
+        if !absorbed.is_empty() { swap_retain(&mut self.meteors, |m| !absorbed.contains(&m.id)); }
+    }
+
+    // チュートリアルの進行（tutorial.rs）。被弾はここで取り消すので check_game_over より前に回す
+    pub(crate) fn tick_tutorial(&mut self, dt: f64) {
+        let scale = self.size_scale();
+        let Some(tut) = &mut self.tutorial else { return };
+        tut.t += dt;
+        tut.drop_timer -= dt;
+        let p = &mut self.players[0];
+        if !p.alive {
+            p.alive = true;
+            self.meteors.clear();
+            tut.retry();
+            self.toasts.push(self.lang.text(Msg::TryAgain).to_string(), 1.5);
+            return;
+        }
+        let r = p.r;
+        // 落とす隕石の左端（None なら今は落とさない）
+        let drop = match tut.lesson {
+            // 自機の真上に1つ
+            Lesson::Dodge if tut.dropped == 0 && tut.drop_timer <= 0.0 => Some(r.x + r.w * 0.5 - 10.0 * scale),
+            // 動かなければ当たらない、すぐ横をかすめる位置に左右交互に
+            Lesson::NearMiss if tut.drop_timer <= 0.0 => {
+                Some(if tut.dropped % 2 == 0 { r.x + r.w + 3.0 } else { r.x - 20.0 * scale - 3.0 })
+            }
+            _ => None,
+        };
+        if let Some(x) = drop {
+            tut.dropped += 1;
+            tut.drop_timer = 2.0;
+            let x = (x / (self.width - 14.0 * scale)).clamp(0.0, 1.0);
+            let speed = self.spawn_config.base_speed;
+            self.spawn_meteor(Spawn { x, size: Some(20.0), speed: Some(speed), hitbox: None });
+        }
+        let Some(tut) = &mut self.tutorial else { return };
+        let done = match tut.lesson {
+            Lesson::Move => self.stats.moved && tut.t >= 1.0,
+            Lesson::Dodge => tut.dropped > 0 && self.meteors.is_empty(),
+            Lesson::NearMiss => self.stats.near_misses > tut.near_misses,
+            Lesson::Done => tut.t >= 2.5,
+        };
+        if !done { return; }
+        if tut.lesson == Lesson::Done {
+            // 終えたら覚えておき、ふつうのランを始める
+            tutorial::mark_done(&*self.storage);
+            self.tutorial = None;
+            self.reset();
+            return;
+        }
+        tut.advance(self.stats.near_misses);
+        self.meteors.clear();
+    }
+
+    // 1人なら被弾で終わり、対戦なら残りが1人以下になったら決着
+    pub(crate) fn check_game_over(&mut self, solo: bool, dt: f64) {
+        let alive = self.players.iter().filter(|p| p.alive).count();
+        if alive != 0 && !(self.versus && alive <= 1) { return; }
+        // 決着したらまず少しのあいだゆっくり流し、落ちた自機へ寄る（寄るのは reducedMotion でなければ）
+        match self.dying {
+            None => {
+                self.dying = Some(DEATH_SLOW * DEATH_SCALE);
+                if let Some(p) = self.players.iter().find(|p| !p.alive).filter(|_| !self.settings.reduced_motion) {
+                    self.camera.focus(p.r.x + p.r.w * 0.5, p.r.y + p.r.h * 0.5, DEATH_ZOOM);
+                }
+                return;
+            }
+            Some(t) if t > 0.0 => { self.dying = Some(t - dt); return; }
+            Some(_) => self.dying = None,
+        }
+        self.camera.release();
+        self.over = true;
+        if solo {
+            self.finish_run();
+            self.deathcam = self.last_moments.take();
+        }
+        // 対戦なら勝った方の番号（引き分け・1人プレイは null）
+        let winner = match (self.versus, self.players.iter().position(|p| p.alive)) {
+            (true, Some(i)) => Some(i as u32 + 1),
+            _ => None,
+        };
+        self.events.push(GameEvent::GameOver { score: self.score.floor(), new_record: self.new_record, winner, solo, game_speed: self.game_speed, assisted: self.assisted() });
+    }
+
+    // ニアミス：感知範囲をかすめて（grazed は collide で付ける）、当たらずにプレイヤーの下まで抜けたらボーナス
+    pub(crate) fn award_near_misses(&mut self) {
+        for m in &mut self.meteors {
+            for (i, p) in self.players.iter_mut().enumerate() {
+                let bit = 1 << i;
+                if !p.alive || m.near_miss_awarded & bit != 0 { continue; }
+                if m.grazed & bit != 0 && !self.over && m.r.y > p.r.y + p.r.h {
+                    m.near_miss_awarded |= bit;
+                    p.score += NEAR_MISS_BONUS * p.combo.hit();
+                    self.events.push(GameEvent::NearMiss { player: i as u32 + 1, combo: p.combo.count, x: m.r.x + m.r.w * 0.5 });
+                    if self.popups.len() < self.budgets.max_popups {
+                        self.popups.push(Popup { text: self.lang.text(Msg::Close), x: m.r.x, y: p.r.y - 10.0, ttl: 0.8 });
+                    }
+                }
+            }
+        }
+    }
+
+    // from 以降に積まれた出来事から、効果音・パーティクル・画面の揺れ・統計を出す
+    pub(crate) fn react(&mut self, from: usize) {
+        let effects = !self.settings.reduced_motion;
+        for i in from..self.events.len() {
+            let cue = match self.events[i] {
+                GameEvent::MeteorSpawned { x } => Cue { sound: Sound::Spawn, pan: self.pan(x) },
+                GameEvent::NearMiss { player, x, .. } => {
+                    if player == 1 { self.stats.near_misses += 1; }
+                    Cue { sound: Sound::NearMiss, pan: self.pan(x) }
+                }
+                GameEvent::Collision { x, y, .. } => {
+                    if effects {
+                        self.particles.burst(ParticleKind::Spark, x, y, 40);
+                        self.camera.kick(10.0);
+                    }
+                    Cue { sound: Sound::Hit, pan: 0.0 }
+                }
+                GameEvent::GameOver { .. } => Cue { sound: Sound::GameOver, pan: 0.0 },
+                GameEvent::PowerUp { trap, x, .. } => Cue { sound: if trap { Sound::Trap } else { Sound::Pickup }, pan: self.pan(x) },
+                GameEvent::Milestone { score } => {
+                    self.celebration = Some(Celebration::new(score as u64));
+                    if effects {
+                        for p in self.players.iter().filter(|p| p.alive) {
+                            self.particles.burst(ParticleKind::Spark, p.r.x + p.r.w * 0.5, p.r.y, 24);
+                        }
+                    }
+                    Cue { sound: Sound::Milestone, pan: 0.0 }
+                }
+                _ => continue,
+            };
+            self.sounds.push(cue);
+        }
+    }
+
+    // 画面外を掃除（当たらずに抜けた分は回避数に数える）
+    pub(crate) fn cleanup(&mut self, dt: f64) {
+        let before = self.meteors.len();
+        let bottom = self.height + 60.0;
+        swap_retain(&mut self.meteors, |m| m.r.y < bottom);
+        if !self.over { self.stats.dodged += (before - self.meteors.len()) as u32; }
+        // ブラックホールの中心に吸い込まれた隕石（避けたうちには数えない）
+        let wells = &self.wells;
+        if !wells.is_empty() { swap_retain(&mut self.meteors, |m| !wells.iter().any(|w| w.swallows(m.r.x + m.r.w * 0.5, m.r.y + m.r.h * 0.5))); }
+        for p in &mut self.popups { p.ttl -= dt; p.y -= 40.0 * dt; }
+        swap_retain(&mut self.popups, |p| p.ttl > 0.0);
+        if let Some(c) = &mut self.celebration {
+            c.tick(dt);
+            if c.done() { self.celebration = None; }
+        }
+        self.toasts.tick(dt);
+    }
+
+    // スコア & 難易度（生き残っている間だけ加算）
+    pub(crate) fn update_score(&mut self, dt: f64) {
+        for p in self.players.iter_mut().filter(|p| p.alive) { p.score += dt * 100.0; }
+        self.score = self.players.iter().map(|p| p.score).fold(0.0, f64::max);
+        let sp = &self.spawn_config;
+        let speed = self.wave().and_then(|(w, t)| w.speed.at(t)).unwrap_or(sp.base_speed + self.score * sp.speed_ramp * self.ramp());
+        self.speed = speed * self.difficulty.scale() * self.cap_boost();
+        let score_step = (self.score / SCORE_EVENT_STEP) as i64;
+        if score_step != self.last_score_step {
+            // 節目をまたいだら（score イベントの刻みは節目を割り切る）
+            let per = (milestone::STEP / SCORE_EVENT_STEP) as i64;
+            if score_step / per > self.last_score_step / per {
+                self.events.push(GameEvent::Milestone { score: (score_step / per) as f64 * milestone::STEP });
+            }
+            self.last_score_step = score_step;
+            self.events.push(GameEvent::Score { score: self.score.floor() });
+        }
+    }
+
+    // ステージ & 天候（レベルがあれば経過時間のウェーブ、無ければスコアのステージ表）
+    pub(crate) fn update_stage(&mut self) {
+        let (stage, name, weather) = match &self.level {
+            Some(l) => {
+                let (i, w, _) = l.at(self.stats.elapsed);
+                if i == self.stage { return; }
+                (i, w.name.clone(), w.weather)
+            }
+            None => {
+                let i = stage::stage_index(self.score);
+                if i == self.stage { return; }
+                let st = stage::stage(i);
+                (i, self.lang.text(st.name).to_string(), st.weather)
+            }
+        };
+        self.stage = stage;
+        self.weather.set(weather);
+        self.toasts.push(self.lang.fill(Msg::Stage, &[&(stage + 1), &name, &self.lang.weather(weather)]), 2.5);
+        self.events.push(GameEvent::Wave { stage: stage as u32 + 1, name, weather: weather.label() });
+    }
+
+    // 実績（リプレイ再生では解除しない）
+    pub(crate) fn update_stats(&mut self, dt: f64, solo: bool) {
+        // 決着した後のゆっくりの間はランに数えない
+        if self.dying.is_some() { return; }
+        // 秒の変わり目ごとにスコアと速度を残す
+        if (self.stats.elapsed + dt).floor() > self.stats.elapsed.floor() {
+            self.stats.samples.push(Sample { score: self.score, speed: self.speed });
+        }
+        self.stats.elapsed += dt;
+        self.stats.score = self.score;
+        if solo && self.budgets.history {
+            self.ghost_run.record(self.stats.elapsed, self.players[0].r.x / self.width);
+        }
+        if solo {
+            for a in self.achievements.check(&self.stats, &*self.storage) {
+                let title = self.lang.text(a.title);
+                self.toasts.push(self.lang.fill(Msg::Achievement, &[&title]), 3.0);
+                self.events.push(GameEvent::Achievement { id: a.id, title });
+            }
+        }
+    }
+}
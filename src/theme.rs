@@ -0,0 +1,88 @@
+// 配色（テーマ）。config の colors が "default" で、ほかに組み込みのテーマを名前で選べる。
+// JSON で渡した場合、書かなかった色は "default"（config の colors）のまま
+use crate::settings::{Palette, Settings};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Theme {
+    pub background: String,
+    pub stars: String,
+    pub player: String,
+    pub player2: String,
+    pub meteor: String,
+    // スコアなどの HUD の文字
+    pub hud: String,
+    // ポップアップ・コンボ・トースト・新記録
+    pub accent: String,
+    // 自機の輪郭とオーバーレイ上の文字
+    pub text: String,
+    pub remote: String,
+    pub ghost: String,
+    // ゲームオーバー・一時停止などで盤面に重ねる幕
+    pub overlay: String,
+    pub toast: String,
+}
+
+pub(crate) const THEME_NAMES: &[&str] = &["default", "midnight", "sunset", "paper"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::palette(["#0b1020", "#111a33", "#00ff88", "#ffb347", "#e85d75", "#cce1ff", "#ffd166", "#ffffff", "#9fa8ff", "#8ecbff", "rgba(0,0,0,0.5)", "rgba(20,30,60,0.85)"])
+    }
+}
+
+impl Theme {
+    // フィールドの順に並べた12色から
+    fn palette(c: [&str; 12]) -> Self {
+        let [background, stars, player, player2, meteor, hud, accent, text, remote, ghost, overlay, toast] = c.map(String::from);
+        Self { background, stars, player, player2, meteor, hud, accent, text, remote, ghost, overlay, toast }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "default" => Self::default(),
+            "midnight" => Self::palette(["#05060f", "#1c2340", "#7df9ff", "#ff9ff3", "#ff6b6b", "#dfe6ff", "#feca57", "#ffffff", "#a29bfe", "#74b9ff", "rgba(0,0,0,0.6)", "rgba(10,12,30,0.9)"]),
+            "sunset" => Self::palette(["#2b1331", "#4a2545", "#ffe66d", "#4ecdc4", "#ff6b35", "#ffe8d6", "#ffd166", "#fff8f0", "#f7a072", "#c3a6ff", "rgba(30,0,20,0.55)", "rgba(60,20,50,0.85)"]),
+            "paper" => Self::palette(["#f4f1ea", "#d8d2c4", "#1b6e3a", "#b5541c", "#3d3d3d", "#222222", "#b8860b", "#111111", "#4a55a2", "#6c8ebf", "rgba(255,255,255,0.6)", "rgba(255,255,255,0.9)"]),
+            _ => return None,
+        })
+    }
+
+    // 組み込みの名前か、Theme の JSON。"default" は config の colors（base）
+    pub fn parse(name_or_json: &str, base: &Theme) -> Result<Self, JsValue> {
+        if name_or_json == "default" { return Ok(base.clone()); }
+        if let Some(t) = Self::builtin(name_or_json) { return Ok(t); }
+        let obj = js_sys::JSON::parse(name_or_json)
+            .map_err(|_| JsValue::from_str(&format!("unknown theme: {} (built-in: {})", name_or_json, THEME_NAMES.join(", "))))?;
+        if !obj.is_object() { return Err(JsValue::from_str("theme must be a name or a JSON object")); }
+        // 書かれていない色は base から埋める
+        let merged = js_sys::Object::assign(&serde_wasm_bindgen::to_value(base)?.into(), &obj.into());
+        Ok(serde_wasm_bindgen::from_value(merged.into())?)
+    }
+
+    // 保存されている設定から（テーマの上に色覚用の配色と高コントラストを重ねる）。テーマが読めなければ base
+    pub fn from_settings(settings: &Settings, base: &Theme) -> Self {
+        let theme = Self::parse(&settings.theme, base).unwrap_or_else(|_| base.clone()).with_palette(settings.palette);
+        if settings.high_contrast { theme.high_contrast() } else { theme }
+    }
+
+    // 背景と文字を黒と白に寄せ、幕とトーストは不透明にする（見分ける色はそのまま）
+    fn high_contrast(self) -> Self {
+        let [background, stars, hud, text, overlay, toast] = ["#000000", "#555555", "#ffffff", "#ffffff", "#000000", "#000000"].map(String::from);
+        Self { background, stars, hud, text, overlay, toast, ..self }
+    }
+
+    // 色覚の型ごとに、見分けたいもの（自機・2P・隕石・強調・他プレイヤー・ゴースト）の色だけ差し替える
+    fn with_palette(mut self, palette: Palette) -> Self {
+        let colors = match palette {
+            Palette::Normal => return self,
+            Palette::Deuteranopia => ["#56b4e9", "#f0e442", "#d55e00", "#f0e442", "#cc79a7", "#56b4e9"],
+            Palette::Protanopia => ["#56b4e9", "#f0e442", "#e69f00", "#f0e442", "#cc79a7", "#56b4e9"],
+            Palette::Tritanopia => ["#5fd3d3", "#ffffff", "#ff5a7a", "#ff9aa2", "#b0b0b0", "#5fd3d3"],
+        };
+        [self.player, self.player2, self.meteor, self.accent, self.remote, self.ghost] = colors.map(String::from);
+        self
+    }
+}
@@ -0,0 +1,59 @@
+// トースト：画面の右下に積む小さな通知。実績・記録更新・接続状態・拾いもの・ステージなどはみな push で順番待ちに入れ、
+// 出す数・時間・滑らせ方・積み方はここでまとめて決める（見た目だけで、乱数やスコアには関わらない）
+use std::collections::VecDeque;
+
+// 同時に出す数（あふれた分は順番待ち）と、出入りに滑らせる秒数
+const SHOWN: usize = 3;
+const SLIDE: f64 = 0.25;
+// 前が消えて詰めるときに、段へ寄せる速さ（1秒あたり）
+const SETTLE: f64 = 12.0;
+// 1件の大きさと間隔。右下の一時停止ボタンの上から上へ積む
+pub(crate) const W: f64 = 260.0;
+pub(crate) const H: f64 = 26.0;
+const GAP: f64 = 6.0;
+const MARGIN: f64 = 10.0;
+const BOTTOM: f64 = 56.0;
+
+pub(crate) struct Toast {
+    pub text: String,
+    // 出しておく秒数と、出てからの秒数・時間が来て抜け始めてからの秒数
+    ttl: f64,
+    t: f64,
+    out: f64,
+    // 今いる段（0 が一番下）。詰めるときに少しずつ寄せる
+    slot: f64,
+}
+
+#[derive(Default)]
+pub(crate) struct Toasts { queue: VecDeque<Toast> }
+
+// 出入りの動き（速く出て、ゆっくり止まる）
+fn ease(k: f64) -> f64 { 1.0 - (1.0 - k.clamp(0.0, 1.0)).powi(3) }
+
+impl Toasts {
+    pub fn push(&mut self, text: String, ttl: f64) { self.queue.push_back(Toast { text, ttl, t: 0.0, out: 0.0, slot: 0.0 }); }
+
+    pub fn clear(&mut self) { self.queue.clear(); }
+
+    // 出ている分だけ時間を進め、抜け切ったものを外す（順番待ちは止めたまま）
+    pub fn tick(&mut self, dt: f64) {
+        for (i, t) in self.queue.iter_mut().take(SHOWN).enumerate() {
+            if t.t == 0.0 { t.slot = i as f64; }
+            t.t += dt;
+            if t.t >= t.ttl { t.out += dt; }
+            t.slot += (i as f64 - t.slot) * (SETTLE * dt).min(1.0);
+        }
+        self.queue.retain(|t| t.out < SLIDE);
+    }
+
+    // 出ている分の (トースト, x, y, 幅, 不透明度)。motion が無ければ滑らせずに薄くするだけ
+    pub fn layout(&self, width: f64, height: f64, motion: bool) -> impl Iterator<Item = (&Toast, f64, f64, f64, f64)> {
+        let w = W.min(width - MARGIN * 2.0);
+        self.queue.iter().take(SHOWN).filter(|t| t.t > 0.0).map(move |t| {
+            let shown = ease(t.t / SLIDE) * (1.0 - ease(t.out / SLIDE));
+            let x = width - MARGIN - w + if motion { (1.0 - shown) * (w + MARGIN) } else { 0.0 };
+            let y = height - BOTTOM - H - t.slot * (H + GAP);
+            (t, x, y, w, shown)
+        })
+    }
+}
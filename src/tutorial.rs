@@ -0,0 +1,43 @@
+// チュートリアル：課題を1つずつ出し、できるまで先へ進まない。課題の間はふつうのスポーンを止めて、
+// 自機に合わせた位置に隕石を落とす。被弾してもゲームオーバーにはせず、その課題をやり直す
+use crate::i18n::Msg;
+use crate::storage::Storage;
+
+const STORAGE_KEY: &str = "meteor_dodge.tutorial";
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Lesson { Move, Dodge, NearMiss, Done }
+
+impl Lesson {
+    pub fn prompt(self) -> Msg {
+        match self { Lesson::Move => Msg::LessonMove, Lesson::Dodge => Msg::LessonDodge, Lesson::NearMiss => Msg::LessonNearMiss, Lesson::Done => Msg::LessonDone }
+    }
+
+    fn next(self) -> Self {
+        match self { Lesson::Move => Lesson::Dodge, Lesson::Dodge => Lesson::NearMiss, _ => Lesson::Done }
+    }
+}
+
+pub(crate) struct Tutorial {
+    pub lesson: Lesson,
+    // 今の課題を始めてからの秒数と、次に隕石を落とすまでの秒数
+    pub t: f64,
+    pub drop_timer: f64,
+    // 今の課題で落とした数
+    pub dropped: u32,
+    // 課題を始めたときのニアミス数（増えたらできた）
+    pub near_misses: u32,
+}
+
+impl Tutorial {
+    pub fn new() -> Self { Self { lesson: Lesson::Move, t: 0.0, drop_timer: 1.0, dropped: 0, near_misses: 0 } }
+
+    pub fn advance(&mut self, near_misses: u32) { *self = Self { lesson: self.lesson.next(), near_misses, ..Self::new() }; }
+
+    pub fn retry(&mut self) { self.t = 0.0; self.drop_timer = 1.0; self.dropped = 0; }
+}
+
+// 最後まで終えたか（終えていれば初回起動でも出さない）
+pub(crate) fn done(storage: &dyn Storage) -> bool { storage.get(STORAGE_KEY).is_some() }
+
+pub(crate) fn mark_done(storage: &dyn Storage) { storage.set(STORAGE_KEY, "done"); }
@@ -0,0 +1,57 @@
+// 天候：ステージごとに切り替わり、視界・入力・スポーンのどれかに作用する
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Weather { Clear, DustStorm, IonStorm }
+
+// イオンストーム：周期の終わりに砂嵐で予告してから操作がガタつく
+const ION_PERIOD: f64 = 5.0;
+const ION_WARN: f64 = 0.8;
+const ION_STUTTER: f64 = 0.6;
+
+impl Weather {
+    pub fn label(self) -> &'static str {
+        match self {
+            Weather::Clear => "CLEAR",
+            Weather::DustStorm => "DUST STORM",
+            Weather::IonStorm => "ION STORM",
+        }
+    }
+
+    // 視界の良さ（1.0 で遮るものなし）
+    pub fn visibility(self) -> f64 {
+        match self { Weather::DustStorm => 0.45, _ => 1.0 }
+    }
+
+    // スポーン間隔の倍率（視界や操作が悪い分だけ少し緩める）
+    pub fn spawn_interval_scale(self) -> f64 {
+        match self { Weather::Clear => 1.0, Weather::DustStorm => 1.1, Weather::IonStorm => 1.15 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct WeatherState { pub kind: Weather, t: f64 }
+
+impl WeatherState {
+    pub fn new(kind: Weather) -> Self { Self { kind, t: 0.0 } }
+
+    pub fn set(&mut self, kind: Weather) { *self = Self::new(kind); }
+
+    pub fn tick(&mut self, dt: f64) { self.t = (self.t + dt) % ION_PERIOD; }
+
+    // 予告の砂嵐の強さ（0..1）
+    pub fn static_level(&self) -> f64 {
+        if self.kind != Weather::IonStorm { return 0.0; }
+        let warn_from = ION_PERIOD - ION_STUTTER - ION_WARN;
+        if self.t < warn_from { 0.0 }
+        else if self.t < ION_PERIOD - ION_STUTTER { (self.t - warn_from) / ION_WARN }
+        else { 1.0 }
+    }
+
+    // スタッター中は 0.1 秒おきに入力が途切れる
+    pub fn input_blocked(&self) -> bool {
+        self.kind == Weather::IonStorm
+            && self.t >= ION_PERIOD - ION_STUTTER
+            && ((self.t * 10.0) as i32) % 2 == 0
+    }
+}
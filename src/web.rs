@@ -0,0 +1,1007 @@
+// ブラウザ側：canvas への描画、DOM イベントの配線、JS に見せる GameCell
+use crate::analytics::Analytics;
+use crate::announce::{self, Announcer};
+use crate::assets::Assets;
+use crate::audio::{Audio, Mix, Track};
+use crate::bench::{self, BenchKind};
+use crate::challenge::Challenge;
+use crate::clip::ClipRecorder;
+use crate::collide::HitboxShape;
+use crate::crash;
+use crate::config::GameConfig;
+use crate::core::{Game, GameEvent};
+use crate::director::{Plan, Spawn, SpawnDirector};
+use crate::error::GameError;
+use crate::gamepad::Gamepads;
+use crate::haptics;
+use crate::heatmap;
+use crate::i18n::{Lang, Msg};
+use crate::leaderboard::Leaderboard;
+use crate::level::Level;
+use crate::menu;
+use crate::shop;
+use crate::simconfig::SimConfig;
+use crate::net::Net;
+use crate::perf::{DtSmoother, FrameStats};
+use crate::plugin::{self, Hook, Plugins};
+use crate::power::{self, Battery, FrameGate};
+use crate::profile;
+use crate::render::{self, Frame, Renderer};
+use crate::replay::Replay;
+use crate::rng::{Rng, XorShift, DEFAULT_SEED};
+use crate::savestate::SavedRun;
+use crate::storage::Storage;
+use crate::theme::Theme;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{window, CustomEvent, CustomEventInit, HtmlAnchorElement, HtmlCanvasElement, KeyboardEvent, MouseEvent};
+
+// GameCell::on() で購読できるイベント（powerup は拾いものを拾ったとき）
+pub(crate) const EVENT_NAMES: &[&str] = &["start", "spawn", "hit", "gameover", "score", "nearmiss", "wave", "achievement", "powerup", "milestone"];
+
+pub(crate) fn js_object(fields: &[(&str, JsValue)]) -> JsValue {
+    let o = js_sys::Object::new();
+    for (k, v) in fields { let _ = js_sys::Reflect::set(&o, &(*k).into(), v); }
+    o.into()
+}
+
+// コールバックに渡す詳細オブジェクト
+fn event_detail(e: &GameEvent) -> JsValue {
+    match e {
+        GameEvent::Start => js_object(&[]),
+        GameEvent::MeteorSpawned { x } => js_object(&[("x", (*x).into())]),
+        GameEvent::Collision { player, x, y, cause } => js_object(&[("player", (*player).into()), ("x", (*x).into()), ("y", (*y).into()), ("cause", (*cause).into())]),
+        GameEvent::GameOver { score, new_record, winner, game_speed, assisted, .. } => js_object(&[
+            ("score", (*score).into()),
+            ("newRecord", (*new_record).into()),
+            ("winner", winner.map_or(JsValue::NULL, JsValue::from)),
+            // アシストモードかゲーム速度を下げた（gameSpeed < 1）ラン
+            ("gameSpeed", (*game_speed).into()),
+            ("assisted", (*assisted).into()),
+        ]),
+        GameEvent::Score { score } => js_object(&[("score", (*score).into())]),
+        GameEvent::NearMiss { player, combo, x } => js_object(&[("player", (*player).into()), ("combo", (*combo).into()), ("x", (*x).into())]),
+        GameEvent::Wave { stage, name, weather } => js_object(&[("stage", (*stage).into()), ("name", name.as_str().into()), ("weather", (*weather).into())]),
+        GameEvent::Achievement { id, title } => js_object(&[("id", (*id).into()), ("title", (*title).into())]),
+        GameEvent::Milestone { score } => js_object(&[("score", (*score).into())]),
+        GameEvent::PowerUp { kind, trap, x } => js_object(&[("kind", (*kind).into()), ("trap", (*trap).into()), ("x", (*x).into())]),
+    }
+}
+
+// 溜まったイベントを取り出し、ソロのゲームオーバーならランキングに送り、集計に渡し、読み上げる文を say に渡す。
+// wanted なものだけ (名前, 詳細) にする
+pub(crate) fn drain_events(game: &mut Game, leaderboard: Option<&Leaderboard>, analytics: &mut Analytics, wanted: impl Fn(&str) -> bool, mut say: impl FnMut(String)) -> Vec<(&'static str, JsValue)> {
+    let mut out = Vec::new();
+    let events = std::mem::take(&mut game.events);
+    for e in events {
+        if let (GameEvent::GameOver { score, solo: true, assisted, .. }, Some(lb)) = (&e, leaderboard) {
+            lb.submit(*score, game.run_mode(), *assisted);
+        }
+        analytics.observe(game, &e);
+        if let Some(text) = announce::message(&e, game.lang) { say(text); }
+        if wanted(e.name()) { out.push((e.name(), event_detail(&e))); }
+    }
+    analytics.finish(game);
+    out
+}
+
+// localStorage に読み書きする（使えない環境では何も覚えない）
+pub(crate) struct LocalStorage;
+
+impl Storage for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> { local_storage()?.get_item(key).ok().flatten() }
+    fn set(&self, key: &str, value: &str) {
+        if let Some(s) = local_storage() { let _ = s.set_item(key, value); }
+    }
+}
+
+// Game に canvas・JS の購読・通信をかぶせたもの
+struct WebGame {
+    game: Game,
+    config: GameConfig,
+    renderer: Box<dyn Renderer>,
+    canvas: HtmlCanvasElement,
+    dpr: f64,
+    // コンテキストロスト中は更新も描画も止める
+    context_lost: bool,
+    // JS のイベント購読
+    listeners: Vec<(String, js_sys::Function)>,
+    plugins: Plugins,
+    // ランキングの送信先（設定されていなければ送らない）
+    leaderboard: Option<Leaderboard>,
+    // 遊ばれ方の集計の送り先（既定では無し）
+    analytics: Analytics,
+    assets: Assets,
+    // 今の配色（settings.theme から作る）
+    theme: Theme,
+    // スクリーンリーダー向けの読み上げ（destroy() で外す）
+    announcer: Option<Announcer>,
+    perf: FrameStats,
+    smoother: DtSmoother,
+    // fps の上限で rAF を間引く。電池が少ないと上限を下げる
+    gate: FrameGate,
+    battery: Battery,
+    audio: Audio,
+    // ランの録画（config.clipSeconds があって MediaRecorder が使えるときだけ）
+    clip: Option<ClipRecorder>,
+    // オンライン対戦の接続（つながっていれば全員同じシードで遊ぶ）
+    net: Option<Net>,
+    // ホストページにキー入力を譲っている間は false
+    exclusive_input: bool,
+    // 譲ったときにこちらで止めたか（メニューやユーザーの一時停止は、取り戻しても解かない）
+    paused_by_input: bool,
+    gamepads: Gamepads,
+    last_t: f64,
+    // run() で付けたリスナーとループ（destroy() で外す）
+    hooks: Option<Hooks>,
+}
+
+impl WebGame {
+    fn new(renderer: Box<dyn Renderer>, canvas: &HtmlCanvasElement, dpr: f64, config: GameConfig, rng: Box<dyn Rng>) -> Self {
+        let (width, height) = (canvas.client_width() as f64, canvas.client_height() as f64);
+        let mut game = Game::new(width, height, &config.sim, Box::new(LocalStorage), rng);
+        if config.tutorial && game.first_time() { game.start_tutorial(); }
+        let leaderboard = config.leaderboard.as_ref().map(|l| Leaderboard::new(l.endpoint.clone(), l.player_name.clone()));
+        let analytics = Analytics::new(config.analytics_endpoint.clone());
+        let assets = Assets::load(&config.assets);
+        let theme = Theme::from_settings(&game.settings, &config.colors);
+        let audio = Audio::load(&config.audio);
+        let clip = config.clip_seconds.and_then(|s| ClipRecorder::new(canvas, s));
+        Self {
+            game,
+            config,
+            renderer,
+            canvas: canvas.clone(),
+            dpr,
+            context_lost: false,
+            listeners: Vec::new(),
+            plugins: Vec::new(),
+            leaderboard,
+            analytics,
+            assets,
+            theme,
+            announcer: Announcer::new(canvas),
+            perf: FrameStats::default(),
+            smoother: DtSmoother::default(),
+            gate: FrameGate::default(),
+            battery: Battery::watch(|_| {}),
+            audio,
+            clip,
+            net: None,
+            exclusive_input: true,
+            paused_by_input: false,
+            gamepads: Gamepads::default(),
+            last_t: now_ms(),
+            hooks: None,
+        }
+    }
+
+    // コンテキスト復帰時：状態はすべて初期化されているので、描画側の資源を作り直す
+    fn restore_context(&mut self) {
+        self.renderer.restore();
+        self.context_lost = false;
+        self.last_t = now_ms();
+    }
+
+    // canvas の表示サイズや DPR が変わったら、解像度を合わせ直して盤面も追従させる
+    fn resize(&mut self) {
+        let (w, h) = (self.canvas.client_width() as f64, self.canvas.client_height() as f64);
+        let dpr = window().map_or(self.dpr, |w| w.device_pixel_ratio());
+        if w <= 0.0 || h <= 0.0 || (w, h, dpr) == (self.game.width, self.game.height, self.dpr) { return; }
+        self.dpr = dpr;
+        self.canvas.set_width((w * dpr).round() as u32);
+        self.canvas.set_height((h * dpr).round() as u32);
+        self.renderer.resize(dpr);
+        self.game.resize(w, h);
+    }
+
+    // キー入力の占有を切り替える（変化があったら true）
+    fn set_exclusive_input(&mut self, on: bool) -> bool {
+        if self.exclusive_input == on { return false; }
+        self.exclusive_input = on;
+        if !on && !self.game.paused {
+            self.game.paused = true;
+            self.paused_by_input = true;
+        } else if on && self.paused_by_input {
+            self.game.paused = false;
+            self.paused_by_input = false;
+        }
+        // 押しっぱなしのキーが残らないように
+        self.game.clear_input();
+        true
+    }
+
+    // 1フレーム進め、購読されているイベントを (名前, 詳細) で返す（配るのは借用を外した後の GameCell::tick()）
+    fn step(&mut self, dt: f64) -> Vec<(&'static str, JsValue)> {
+        if self.exclusive_input {
+            for (key, down) in self.gamepads.poll() {
+                self.input(|g| if down { key_down(g, key) } else { g.set_key(key, false) });
+            }
+        }
+        self.step_net(dt);
+        if !self.context_lost && !self.needs_rotation() { self.game.step(dt); }
+        if let Some(c) = &mut self.clip { c.update(now_ms() / 1000.0, self.game.over); }
+        self.audio.set_mix(Mix::of(&self.game.settings));
+        for cue in self.game.sounds.drain(..) {
+            self.audio.play(cue);
+            if self.game.settings.vibration { haptics::vibrate(cue.sound); }
+        }
+        self.audio.set_music(Track::for_game(&self.game));
+        let (listeners, announcer) = (&self.listeners, &self.announcer);
+        let say = |text: String| if let Some(a) = announcer { a.say(&text) };
+        drain_events(&mut self.game, self.leaderboard.as_ref(), &mut self.analytics, |name| listeners.iter().any(|(n, _)| n == name), say)
+    }
+
+    // キーやタップを渡す。メニューで配色が変わったら作り直す
+    fn input(&mut self, f: impl FnOnce(&mut Game)) {
+        let theme = self.game.settings.theme.clone();
+        f(&mut self.game);
+        if self.game.settings.theme != theme { self.theme = Theme::from_settings(&self.game.settings, &self.config.colors); }
+    }
+
+    // 設定された向きと今の画面の向きが違う
+    fn needs_rotation(&self) -> bool {
+        self.config.orientation.is_some_and(|o| o != self.game.orientation)
+    }
+
+    // オンライン：接続の状態が変わったら知らせ、シードが届いたらそのシードでやり直し、自分の位置を送る
+    fn step_net(&mut self, dt: f64) {
+        let Some(net) = &mut self.net else { return };
+        if let Some(s) = net.take_state_change() { self.game.toasts.push(self.game.lang.fill(Msg::NetStatus, &[&s.label()]), 2.5); }
+        if let Some(seed) = net.take_pending_seed() { self.game.follow_seed(seed); }
+        let p = &self.game.players[0];
+        net.tick(dt, p.r.x / self.game.width, p.alive, p.score);
+    }
+
+    fn draw(&mut self) {
+        let rotate = self.needs_rotation();
+        let frame = Frame { game: &self.game, config: &self.config, net: self.net.as_ref(), leaderboard: self.leaderboard.as_ref(), assets: &self.assets, theme: &self.theme, perf: &self.perf, rotate };
+        let _span = profile::span(self.config.sim.profile, "draw");
+        self.renderer.draw(&frame);
+    }
+
+    // 今のフレームを HUD ごと PNG の data URL に。WebGL は描いた直後でないと中身が読めないので描き直してから取る
+    fn screenshot(&mut self) -> Result<String, JsValue> {
+        if !self.context_lost { self.draw(); }
+        self.canvas.to_data_url_with_type("image/png")
+    }
+
+    // 別の盤面でベンチマークを回して結果を返す（bench.rs）
+    fn bench(&mut self, meteors: u32, kind: BenchKind) -> JsValue {
+        let mut g = bench::board(&self.config.sim, self.game.width, self.game.height, meteors);
+        let (renderer, config, assets, theme, perf) = (&mut self.renderer, &self.config, &self.assets, &self.theme, &self.perf);
+        let r = bench::run(&mut g, meteors, kind, |g| renderer.draw(&Frame { game: g, config, net: None, leaderboard: None, assets, theme, perf, rotate: false }));
+        bench::report(&r, self.renderer.name())
+    }
+
+    // スクリーンショットをファイルとして保存させる
+    fn download_screenshot(&mut self) -> Result<(), JsValue> {
+        let url = self.screenshot()?;
+        download(&url, &format!("meteor-dodge-{}.png", self.game.score as u64))
+    }
+}
+
+// 見えない <a download> を押して url を保存させる
+fn download(url: &str, name: &str) -> Result<(), JsValue> {
+    let doc = window().and_then(|w| w.document()).ok_or(GameError::NoDocument)?;
+    let a: HtmlAnchorElement = doc.create_element("a")?.dyn_into()?;
+    a.set_href(url);
+    a.set_download(name);
+    a.click();
+    Ok(())
+}
+
+// navigator.deviceMemory（GB, 対応ブラウザのみ）が小さい端末か
+fn device_memory_is_low() -> bool {
+    let Some(nav) = window().map(|w| w.navigator()) else { return false };
+    js_sys::Reflect::get(&nav, &"deviceMemory".into()).ok()
+        .and_then(|v| v.as_f64())
+        .is_some_and(|gb| gb <= 2.0)
+}
+
+// OS で「視差効果を減らす」が有効か（worker には init で渡す）
+pub(crate) fn prefers_reduced_motion() -> bool {
+    window().and_then(|w| w.match_media("(prefers-reduced-motion: reduce)").ok().flatten()).is_some_and(|m| m.matches())
+}
+
+// ページの URL に ?debug や ?profile（=0 / =false 以外）が付いているか
+pub(crate) fn query_flag(key: &str) -> bool {
+    let Some(search) = window().and_then(|w| w.location().search().ok()) else { return false };
+    search.trim_start_matches('?').split('&').any(|kv| {
+        let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+        k == key && v != "0" && v != "false"
+    })
+}
+
+// ブラウザの言語（window でも worker でも navigator.language を見る）
+pub(crate) fn navigator_language() -> Option<String> {
+    let nav = js_sys::Reflect::get(&js_sys::global(), &"navigator".into()).ok()?;
+    js_sys::Reflect::get(&nav, &"language".into()).ok()?.as_string()
+}
+
+// 今日の日付（UTC, "YYYY-MM-DD"）。デイリーの挑戦内容がタイムゾーンでずれないよう UTC で揃える
+pub(crate) fn today() -> String {
+    let d = js_sys::Date::new_0();
+    format!("{:04}-{:02}-{:02}", d.get_utc_full_year(), d.get_utc_month() + 1, d.get_utc_date())
+}
+
+// localStorage（プライベートモード等で使えなければ None）
+pub(crate) fn local_storage() -> Option<web_sys::Storage> {
+    window()?.local_storage().ok().flatten()
+}
+
+// window と worker のどちらでも使えるよう global から取る
+pub(crate) fn performance() -> Option<web_sys::Performance> {
+    js_sys::Reflect::get(&js_sys::global(), &"performance".into()).ok()?.dyn_into().ok()
+}
+
+// performance が無ければ Date.now() で代用する（精度が落ちるだけ）
+pub(crate) fn now_ms() -> f64 { performance().map_or_else(js_sys::Date::now, |p| p.now()) }
+
+// fetch と createImageBitmap は window と WorkerGlobalScope の両方にある（worker 版からも使えるように）
+pub(crate) fn fetch(req: &web_sys::Request) -> js_sys::Promise {
+    let global = js_sys::global();
+    match global.dyn_ref::<web_sys::Window>() {
+        Some(win) => win.fetch_with_request(req),
+        None => global.unchecked_into::<web_sys::WorkerGlobalScope>().fetch_with_request(req),
+    }
+}
+
+pub(crate) fn create_image_bitmap(blob: &web_sys::Blob) -> Result<js_sys::Promise, JsValue> {
+    let global = js_sys::global();
+    match global.dyn_ref::<web_sys::Window>() {
+        Some(win) => win.create_image_bitmap_with_blob(blob),
+        None => global.unchecked_into::<web_sys::WorkerGlobalScope>().create_image_bitmap_with_blob(blob),
+    }
+}
+
+pub(crate) type Listener = (web_sys::EventTarget, &'static str, Closure<dyn FnMut(web_sys::Event)>);
+
+pub(crate) fn listen(target: &web_sys::EventTarget, name: &'static str, cb: Closure<dyn FnMut(web_sys::Event)>) -> Result<Listener, GameError> {
+    target.add_event_listener_with_callback(name, cb.as_ref().unchecked_ref())
+        .map_err(|e| GameError::dom(&format!("addEventListener({:?})", name), e))?;
+    Ok((target.clone(), name, cb))
+}
+
+// キーを押したときの操作（worker 版でも同じものを使う）
+pub(crate) fn key_down(game: &mut Game, key: &str) {
+    // デモ中はどのキーでもタイトルに戻るだけ
+    if game.wake() { return; }
+    // 落ちる直前の再生中は、どのキーでも飛ばして結果画面へ
+    if game.over && game.deathcam.take().is_some() { return; }
+    // 一時停止中の矢印・Enter・Esc はメニューの操作
+    if game.paused && menu::key(game, key) { return; }
+    // 店が開いている間はその操作だけ
+    if shop::key(game, key) { return; }
+    if heatmap::key(game, key) { return; }
+    match key {
+        "ArrowLeft" | "ArrowRight" | "a" | "A" | "d" | "D" => game.set_key(key, true),
+        "Escape" => menu::toggle(game),
+        "r" | "R" | "Enter" if game.over => game.reset(),
+        "v" | "V" if game.over => { let on = !game.versus; game.set_versus(on); }
+        "t" | "T" if game.over => game.start_tutorial(),
+        "p" | "P" if game.over => game.next_practice(),
+        "u" | "U" if game.over => shop::toggle(game),
+        "h" | "H" if game.over => heatmap::toggle(game),
+        "g" | "G" => {
+            game.settings.show_ghost = !game.settings.show_ghost;
+            game.save_settings();
+        }
+        "F3" => game.show_perf = !game.show_perf,
+        "m" | "M" => {
+            game.settings.muted = !game.settings.muted;
+            game.save_settings();
+        }
+        "c" | "C" if game.over => { let date = game.daily.is_none().then(today); game.set_daily(date.as_deref()); }
+        _ => {}
+    }
+}
+
+fn add_key_listeners(game_rc: &GameCell) -> Result<Vec<Listener>, GameError> {
+    let win = window().ok_or(GameError::NoWindow)?;
+    let handler_down = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::new(move |e: web_sys::Event| {
+            let Some(e) = e.dyn_ref::<KeyboardEvent>() else { return };
+            let mut inner = g.0.borrow_mut();
+            inner.audio.unlock();
+            // 入力を手放している間はホストのショートカットを邪魔しない
+            if !inner.exclusive_input { return; }
+            if ["ArrowLeft", "ArrowRight", "ArrowUp", "ArrowDown", "Space", "F3"].contains(&e.key().as_str()) { e.prevent_default(); }
+            if matches!(e.key().as_str(), "s" | "S") {
+                if let Err(err) = inner.download_screenshot() { web_sys::console::error_1(&err); }
+                return;
+            }
+            // ゲームオーバー画面の K：直前のランの録画を保存
+            if matches!(e.key().as_str(), "k" | "K") && inner.game.over {
+                if let Some(url) = inner.clip.as_ref().and_then(|c| c.url()) {
+                    if let Err(err) = download(&url, &format!("meteor-dodge-{}.webm", inner.game.score as u64)) { web_sys::console::error_1(&err); }
+                }
+                return;
+            }
+            inner.input(|g| key_down(g, &e.key()));
+        })
+    };
+    let handler_up = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::new(move |e: web_sys::Event| {
+            let Some(e) = e.dyn_ref::<KeyboardEvent>() else { return };
+            let mut inner = g.0.borrow_mut();
+            if !inner.exclusive_input { return; }
+            inner.game.set_key(&e.key(), false);
+        })
+    };
+
+    // 音はユーザー操作の後でないと鳴らせないので、タップでも有効にする。canvas の上なら一時停止ボタンとメニューへ
+    let handler_pointer = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::new(move |e: web_sys::Event| {
+            let mut inner = g.0.borrow_mut();
+            inner.audio.unlock();
+            let Some(e) = e.dyn_ref::<MouseEvent>().filter(|_| inner.exclusive_input) else { return };
+            let rect = inner.canvas.get_bounding_client_rect();
+            let (x, y) = (e.client_x() as f64 - rect.left(), e.client_y() as f64 - rect.top());
+            inner.input(|g| menu::tap(g, x, y));
+        })
+    };
+
+    Ok(vec![listen(&win, "keydown", handler_down)?, listen(&win, "keyup", handler_up)?, listen(&win, "pointerdown", handler_pointer)?])
+}
+
+// GPU リセットやバックグラウンド化でコンテキストが失われたら一時停止し、復帰時に作り直す
+fn add_context_listeners(game_rc: &GameCell) -> Result<Vec<Listener>, GameError> {
+    let (canvas, webgl) = {
+        let g = game_rc.0.borrow();
+        (g.canvas.clone(), g.renderer.name() == "webgl2")
+    };
+    let on_lost = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::new(move |e: web_sys::Event| {
+            // WebGL は preventDefault しないと復帰しない。2D では逆に復帰しなくなるので呼ばない
+            if webgl { e.prevent_default(); }
+            g.0.borrow_mut().context_lost = true;
+        })
+    };
+    let on_restored = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::new(move |_e: web_sys::Event| {
+            g.0.borrow_mut().restore_context();
+        })
+    };
+    let (lost, restored) = if webgl { ("webglcontextlost", "webglcontextrestored") } else { ("contextlost", "contextrestored") };
+    Ok(vec![listen(&canvas, lost, on_lost)?, listen(&canvas, restored, on_restored)?])
+}
+
+pub(crate) type ResizeWatch = (web_sys::ResizeObserver, Closure<dyn FnMut()>);
+
+// canvas の大きさの変化（画面回転やウィンドウのリサイズ）を見る
+fn observe_resize(game_rc: &GameCell) -> Result<ResizeWatch, GameError> {
+    let canvas = game_rc.0.borrow().canvas.clone();
+    let on_resize = {
+        let g = game_rc.clone();
+        Closure::<dyn FnMut()>::new(move || g.0.borrow_mut().resize())
+    };
+    let observer = web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())
+        .map_err(|e| GameError::dom("ResizeObserver", e))?;
+    observer.observe(&canvas);
+    Ok((observer, on_resize))
+}
+
+// run() で付けたもの一式。落とすとリスナーを外し、次のフレームを取り消す
+struct Hooks {
+    listeners: Vec<Listener>,
+    resize: Option<ResizeWatch>,
+    // ループのクロージャを生かしておくためだけに持つ
+    _frame: FrameCallback,
+    raf_id: Rc<std::cell::Cell<i32>>,
+}
+
+impl Drop for Hooks {
+    fn drop(&mut self) {
+        for (target, name, cb) in &self.listeners {
+            let _ = target.remove_event_listener_with_callback(name, cb.as_ref().unchecked_ref());
+        }
+        if let Some((observer, _)) = &self.resize { observer.disconnect(); }
+        if let Some(win) = window() { let _ = win.cancel_animation_frame(self.raf_id.get()); }
+    }
+}
+
+// GameCell::set_level に渡されたレベルを読む。オブジェクトか、JSON（ron 機能付きなら RON も）の文字列
+fn level_from_js(v: JsValue) -> Result<Level, JsValue> {
+    let level: Level = match v.as_string() {
+        Some(text) => return Level::parse(&text).map_err(|e| JsValue::from_str(&e)),
+        None => serde_wasm_bindgen::from_value(v)?,
+    };
+    level.check().map_err(|e| JsValue::from_str(&e))?;
+    Ok(level)
+}
+
+// 隕石の出し方（director.rs）をページから渡された関数に任せる。プラグインと同じ読み取り用の状態を渡し、
+// [{x, size, speed, hitbox}, ...] か {meteors: [...], next: 秒} を返してもらう（何も返さなければ出さない）
+struct JsDirector(js_sys::Function);
+
+impl SpawnDirector for JsDirector {
+    fn next(&mut self, g: &Game) -> Plan {
+        match self.0.call1(&JsValue::NULL, &plugin::view(g, 0.0)) {
+            Ok(ret) => parse_plan(&ret),
+            Err(e) => { web_sys::console::error_1(&e); Plan::default() }
+        }
+    }
+}
+
+// 関数の戻り値を読む（配列だけなら次の間隔はいつも通り。それ以外の形なら何も出さない）
+fn parse_plan(ret: &JsValue) -> Plan {
+    if js_sys::Array::is_array(ret) { return Plan { meteors: parse_meteors(ret), next: None }; }
+    if !ret.is_object() { return Plan::default(); }
+    let get = |k: &str| js_sys::Reflect::get(ret, &k.into()).unwrap_or(JsValue::UNDEFINED);
+    Plan { meteors: parse_meteors(&get("meteors")), next: get("next").as_f64().filter(|n| n.is_finite() && *n >= 0.0) }
+}
+
+// 隕石の配列を読む（x は 0〜1 の横位置。書かなかった・おかしな値の項目はいつも通りに決める）
+fn parse_meteors(v: &JsValue) -> Vec<Spawn> {
+    if !js_sys::Array::is_array(v) { return Vec::new(); }
+    js_sys::Array::from(v).iter().filter(|m| m.is_object()).map(|m| {
+        let num = |k: &str| js_sys::Reflect::get(&m, &k.into()).ok().and_then(|v| v.as_f64()).filter(|n| n.is_finite());
+        let hitbox = js_sys::Reflect::get(&m, &"hitbox".into()).ok().and_then(|v| v.as_string()).and_then(|s| match s.as_str() {
+            "circle" => Some(HitboxShape::Circle),
+            "box" => Some(HitboxShape::Box),
+            _ => None,
+        });
+        Spawn { x: num("x").unwrap_or(0.5).clamp(0.0, 1.0), size: num("size").filter(|s| *s > 0.0), speed: num("speed").filter(|s| *s > 0.0), hitbox }
+    }).collect()
+}
+
+// RefCell を JS 側に乗せるためのラッパ（clone しても同じゲームを指すハンドル）
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct GameCell(Rc<RefCell<WebGame>>);
+
+// start() で自動起動したインスタンス（ホストページから game() で取得）
+thread_local! {
+    static RUNNING: RefCell<Option<GameCell>> = const { RefCell::new(None) };
+}
+
+#[wasm_bindgen]
+pub fn game() -> Option<GameCell> {
+    RUNNING.with(|r| r.borrow().clone())
+}
+
+#[wasm_bindgen]
+impl GameCell {
+    // config は GameConfig のオブジェクト（省略可、書かなかった項目は既定値）。
+    // canvas 要素か CSS セレクタだけを渡してもよい
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: JsValue) -> Result<GameCell, JsValue> {
+        Ok(Self::with_config(GameConfig::from_js(config)?)?)
+    }
+
+    // 最初のランを指定シードで始める（seed() の値を渡せば同じランを再現できる）
+    pub fn new_with_seed(seed: u64) -> Result<GameCell, JsValue> {
+        Ok(Self::with_config(GameConfig { sim: SimConfig { seed: Some(seed), ..SimConfig::default() }, ..GameConfig::default() })?)
+    }
+}
+
+impl GameCell {
+    fn with_config(mut config: GameConfig) -> Result<GameCell, GameError> {
+        config.sim.prefers_reduced_motion = prefers_reduced_motion();
+        config.debug |= query_flag("debug");
+        config.sim.profile |= query_flag("profile");
+        config.sim.language = config.sim.language.or_else(|| Some(Lang::detect(navigator_language().as_deref())));
+        let canvas = config.resolve_canvas()?;
+        // デバイスピクセル比に応じてリサイズ（高DPIディスプレイでクッキリ）
+        let dpr = window().ok_or(GameError::NoWindow)?.device_pixel_ratio();
+        let client_width = canvas.client_width() as f64;
+        let client_height = canvas.client_height() as f64;
+        let w = (client_width * dpr).round() as u32;
+        let h = (client_height * dpr).round() as u32;
+        canvas.set_width(w);
+        canvas.set_height(h);
+        let renderer = render::create(&canvas, config.renderer, dpr)?;
+        crash::register(&canvas.clone().into(), config.sim.language.unwrap_or_default(), config.on_error());
+
+        let seed = config.sim.seed.unwrap_or(DEFAULT_SEED);
+        let mut g = WebGame::new(renderer, &canvas, dpr, config, Box::new(XorShift::new(seed)));
+        // 挑戦リンクから開かれたら、そのランから始める
+        let today = today();
+        if let Some(c) = window().and_then(|w| w.location().search().ok()).and_then(|s| Challenge::from_query(&s, &today)) { c.apply(&mut g.game, &today); }
+        Ok(GameCell(Rc::new(RefCell::new(g))))
+    }
+}
+
+#[wasm_bindgen]
+impl GameCell {
+
+    // ベンチマーク：隕石 meteors 個（省略時 2000）の盤面で mode（"update" / "draw" / "both"、省略時 both）を回し、
+    // 1回あたりの平均 ms をコンソールに出して {meteors, frames, updateMs, drawMs, renderer} で返す。遊んでいるランには触らない
+    pub fn bench(&self, meteors: Option<u32>, mode: Option<String>) -> Result<JsValue, JsValue> {
+        let kind = match mode.as_deref() {
+            None => BenchKind::Both,
+            Some(m) => BenchKind::parse(m).ok_or_else(|| JsValue::from_str(&format!("unknown bench mode: {} (update / draw / both)", m)))?,
+        };
+        Ok(self.0.borrow_mut().bench(meteors.unwrap_or(bench::DEFAULT_METEORS), kind))
+    }
+
+    // 低メモリモードの切り替え（古い Android WebView など向け）
+    pub fn set_low_memory(&self, on: bool) {
+        self.0.borrow_mut().game.set_low_memory(on);
+    }
+
+    pub fn low_memory(&self) -> bool {
+        self.0.borrow().game.low_memory
+    }
+
+    // 端末メモリが少ないときに true（ホスト側で低メモリモードを勧める目安）
+    pub fn low_memory_suggested() -> bool {
+        device_memory_is_low()
+    }
+
+    // false でキー入力をホストに譲り、ゲームは自動で一時停止する
+    // 切り替え時は canvas から "meteordodge:input"（detail: bool）を発火する
+    pub fn request_exclusive_input(&self, exclusive: bool) {
+        let canvas = {
+            let mut g = self.0.borrow_mut();
+            if !g.set_exclusive_input(exclusive) { return; }
+            g.canvas.clone()
+        };
+        // リスナーから GameCell を触れるよう、借用を解放してから通知
+        let init = CustomEventInit::new();
+        init.set_bubbles(true);
+        init.set_detail(&JsValue::from_bool(exclusive));
+        if let Ok(ev) = CustomEvent::new_with_event_init_dict("meteordodge:input", &init) {
+            let _ = canvas.dispatch_event(&ev);
+        }
+    }
+
+    // 今のランの記録を JSON で取り出す（低メモリモードでは記録しない）
+    pub fn export_replay(&self) -> String {
+        self.0.borrow().game.recording.to_json()
+    }
+
+    // JSON のリプレイを読み込んで最初から再生する（R で通常プレイに戻る）
+    pub fn play_replay(&self, json: &str) -> Result<(), JsValue> {
+        let replay = Replay::from_json(json).map_err(|e| JsValue::from_str(&e))?;
+        self.0.borrow_mut().game.start_playback(replay);
+        Ok(())
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.0.borrow().game.playback.is_some()
+    }
+
+    // ランキングの送信先とプレイヤー名（endpoint が空なら送信しない）
+    pub fn set_leaderboard(&self, endpoint: String, player_name: String) {
+        self.0.borrow_mut().leaderboard = (!endpoint.is_empty()).then(|| Leaderboard::new(endpoint, player_name));
+    }
+
+    // 遊ばれ方の集計をこの関数にも渡す（runStart / runEnd のオブジェクトが1つ、詳しくは analytics.rs）。null で外す
+    pub fn set_analytics(&self, callback: Option<js_sys::Function>) {
+        self.0.borrow_mut().analytics.set_callback(callback);
+    }
+
+    // WebSocket サーバーにつなぎ、配られたシードで他のプレイヤーと同じ隕石列を遊ぶ
+    pub fn connect(&self, url: &str, player_name: &str) -> Result<(), JsValue> {
+        let net = Net::connect(url, player_name)?;
+        self.0.borrow_mut().net = Some(net);
+        Ok(())
+    }
+
+    pub fn disconnect(&self) {
+        let mut g = self.0.borrow_mut();
+        g.net = None;
+        g.game.fixed_seed = None;
+    }
+
+    // 実際に使っている描画方式（"canvas2d" / "webgl2"。webgl2 を指定しても使えなければ canvas2d）
+    pub fn renderer(&self) -> String {
+        self.0.borrow().renderer.name().to_string()
+    }
+
+    // 画像アセットの読み込み具合（0.0..1.0。指定がなければ 1.0、失敗したものも済みに数える）
+    pub fn asset_progress(&self) -> f64 {
+        let (done, total) = self.0.borrow().assets.progress();
+        if total == 0 { 1.0 } else { done as f64 / total as f64 }
+    }
+
+    // "offline" / "connecting" / "connected" / "closed" / "failed"
+    pub fn net_state(&self) -> String {
+        self.0.borrow().net.as_ref().map_or("offline", |n| n.state().label()).to_string()
+    }
+
+    // ローカル2人対戦（1P: A/D, 2P: ←/→）
+    pub fn set_versus(&self, on: bool) {
+        self.0.borrow_mut().game.set_versus(on);
+    }
+
+    // 練習モード：start_score の速さと密度から始める（"5000 点から" など）。null で通常に戻す。
+    // 練習のスコアはハイスコアやランキングに残さない
+    pub fn set_practice(&self, start_score: Option<f64>) {
+        self.0.borrow_mut().game.set_practice(start_score);
+    }
+
+    // 今のフレーム（HUD・結果画面込み）の PNG を data URL で返す（S キーならそのままダウンロード。worker 版では使えない）
+    pub fn screenshot(&self) -> Result<String, JsValue> {
+        self.0.borrow_mut().screenshot()
+    }
+
+    // 直前のランの録画（webm の object URL）。config.clipSeconds が無い・まだ録れていなければ null。
+    // 次のゲームオーバーで新しい録画に替わると古い URL は使えなくなる
+    pub fn clip_url(&self) -> Option<String> {
+        self.0.borrow().clip.as_ref().and_then(|c| c.url())
+    }
+
+    // 文言の言語を切り替える（"en" / "ja"、"ja-JP" のような形でもよい）
+    pub fn set_language(&self, lang: &str) -> Result<(), JsValue> {
+        let lang = Lang::parse(lang).ok_or_else(|| JsValue::from_str(&format!("unsupported language: {} (en / ja)", lang)))?;
+        self.0.borrow_mut().game.lang = lang;
+        Ok(())
+    }
+
+    // チュートリアルを最初から（終えると通常のランが始まる）
+    pub fn start_tutorial(&self) {
+        self.0.borrow_mut().game.start_tutorial();
+    }
+
+    // ベストランのゴースト表示
+    pub fn set_ghost(&self, on: bool) {
+        let game = &mut self.0.borrow_mut().game;
+        game.settings.show_ghost = on;
+        game.save_settings();
+    }
+
+    // 設定を JSON で取り出す
+    pub fn settings(&self) -> String {
+        self.0.borrow().game.settings.to_json()
+    }
+
+    // 設定の一部または全部を JSON で更新して保存する（難易度は次のランから）
+    pub fn update_settings(&self, json: &str) -> Result<(), JsValue> {
+        let mut g = self.0.borrow_mut();
+        g.game.settings.update_from_json(json).map_err(|e| JsValue::from_str(&e))?;
+        g.game.save_settings();
+        g.theme = Theme::from_settings(&g.game.settings, &g.config.colors);
+        Ok(())
+    }
+
+    // 音量（0〜1）を変えて保存する。channel は "master" / "music" / "sfx"
+    pub fn set_volume(&self, channel: &str, value: f64) -> Result<(), JsValue> {
+        let game = &mut self.0.borrow_mut().game;
+        let v = value.clamp(0.0, 1.0);
+        match channel {
+            "master" => game.settings.volume = v,
+            "music" => game.settings.music_volume = v,
+            "sfx" => game.settings.sfx_volume = v,
+            _ => return Err(JsValue::from_str(&format!("unknown volume channel: {} (master / music / sfx)", channel))),
+        }
+        game.save_settings();
+        Ok(())
+    }
+
+    // 配色を変えて保存する。組み込みの名前（"default" / "midnight" / "sunset" / "paper"）か、
+    // {"background": "#000", ...} の JSON（書かなかった色は "default" のまま）
+    pub fn set_theme(&self, name_or_json: &str) -> Result<(), JsValue> {
+        let mut g = self.0.borrow_mut();
+        Theme::parse(name_or_json, &g.config.colors)?;
+        g.game.settings.theme = name_or_json.to_string();
+        g.theme = Theme::from_settings(&g.game.settings, &g.config.colors);
+        g.game.save_settings();
+        Ok(())
+    }
+
+    // 進行中のランを丸ごと保存する（localStorage などに置いてページ再読み込み後に load_state）
+    pub fn save_state(&self) -> Result<JsValue, JsValue> {
+        SavedRun::capture(&self.0.borrow().game).to_js()
+    }
+
+    pub fn load_state(&self, state: JsValue) -> Result<(), JsValue> {
+        let saved = SavedRun::from_js(state)?;
+        saved.apply(&mut self.0.borrow_mut().game);
+        Ok(())
+    }
+
+    // 読み取り専用の状態（ホスト側で独自の HUD やロジックを組むため）
+    pub fn score(&self) -> f64 {
+        self.0.borrow().game.score.floor()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.0.borrow().game.over
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.borrow().game.paused
+    }
+
+    pub fn meteor_count(&self) -> u32 {
+        self.0.borrow().game.meteors.len() as u32
+    }
+
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.0.borrow().game.stats.elapsed
+    }
+
+    // 今のランのシード
+    pub fn seed(&self) -> u64 {
+        self.0.borrow().game.seed
+    }
+
+    // 今のランをそのまま遊べる URL（このページに ?seed=...&mode=... を付けたもの）。友だちに送れば同じ隕石列で競える
+    pub fn challenge_url(&self) -> Option<String> {
+        let loc = window()?.location();
+        let base = format!("{}{}", loc.origin().ok()?, loc.pathname().ok()?);
+        Some(Challenge::url(&self.0.borrow().game, &base))
+    }
+
+    // デイリーチャレンジ（日付由来のシードで全員同じ隕石列）
+    pub fn set_daily(&self, on: bool) {
+        let date = on.then(today);
+        self.0.borrow_mut().game.set_daily(date.as_deref());
+    }
+
+    // ゲームのイベントを購読する（start / spawn / hit / gameover / score / nearmiss / wave / achievement / powerup / milestone）。
+    // コールバックには詳細のオブジェクトが1つ渡る
+    pub fn on(&self, event: &str, callback: js_sys::Function) -> Result<(), JsValue> {
+        if !EVENT_NAMES.contains(&event) {
+            return Err(JsValue::from_str(&format!("unknown event: {}", event)));
+        }
+        self.0.borrow_mut().listeners.push((event.to_string(), callback));
+        Ok(())
+    }
+
+    // そのイベントの購読をすべて外す
+    pub fn off(&self, event: &str) {
+        self.0.borrow_mut().listeners.retain(|(n, _)| n != event);
+    }
+
+    // プラグインを足す。hook は "preUpdate" / "postUpdate" / "preDraw" / "postDraw" で、コールバックには
+    // 読み取り用の状態（score, speed, players, meteors など）が渡る。preUpdate が {"timeScale": 0.5} を返すとそのフレームはスロー。
+    // 描画の後に同じ canvas の 2D コンテキストへ描き足せば重ね描きになる（worker 版では使えない）
+    pub fn add_plugin(&self, hook: &str, callback: js_sys::Function) -> Result<(), JsValue> {
+        let h = Hook::parse(hook).ok_or_else(|| JsValue::from_str(&format!("unknown plugin hook: {} (preUpdate / postUpdate / preDraw / postDraw)", hook)))?;
+        self.0.borrow_mut().plugins.push((h, callback));
+        Ok(())
+    }
+
+    pub fn clear_plugins(&self) {
+        self.0.borrow_mut().plugins.clear();
+    }
+
+    // レベル（ウェーブの並び）を読み込む。オブジェクトか、fetch したファイルの中身（JSON、ron 機能付きなら RON も）を渡す。
+    // null で組み込みのステージ表に戻す（どちらもランは最初からやり直し）
+    pub fn set_level(&self, level: JsValue) -> Result<(), JsValue> {
+        let level = if level.is_null() || level.is_undefined() { None } else { Some(level_from_js(level)?) };
+        self.0.borrow_mut().game.set_level(level);
+        Ok(())
+    }
+
+    // 隕石の出し方をページの関数に任せる（null で組み込みのランダムに戻す。ランは最初からやり直し）。
+    // 関数はスポーンの時間が来るたびにプラグインと同じ状態で呼ばれ、[{x: 0..1, size, speed, hitbox}, ...] か
+    // {meteors: [...], next: 次に呼ぶまでの秒数} を返す。この中から GameCell は触らないこと。
+    // 差し替えたランはハイスコアやリプレイに残さない（worker 版では使えない）
+    pub fn set_spawn_director(&self, director: Option<js_sys::Function>) {
+        let director = director.map(|f| Box::new(JsDirector(f)) as Box<dyn SpawnDirector>);
+        self.0.borrow_mut().game.set_director(director);
+    }
+
+    // キー入力・コンテキスト監視・requestAnimationFrame ループをこのインスタンスに付ける。
+    // 複数のゲームを並べるときは、操作しない方を request_exclusive_input(false) にしておく
+    pub fn run(&self) -> Result<(), JsValue> {
+        if self.0.borrow().hooks.is_some() { return Ok(()); }
+        let mut listeners = add_key_listeners(self)?;
+        listeners.extend(add_context_listeners(self)?);
+        // ResizeObserver が無い古いブラウザでは、ウィンドウのリサイズと画面回転で代用する
+        let resize = observe_resize(self).ok();
+        if resize.is_none() {
+            let win = window().ok_or(GameError::NoWindow)?;
+            for name in ["resize", "orientationchange"] {
+                let g = self.clone();
+                listeners.push(listen(&win, name, Closure::new(move |_e: web_sys::Event| g.0.borrow_mut().resize()))?);
+            }
+        }
+        let (frame, raf_id) = start_frame_loop(self.clone())?;
+        self.0.borrow_mut().hooks = Some(Hooks { listeners, resize, _frame: frame, raf_id });
+        Ok(())
+    }
+
+    // run() で付けたリスナーとループを外し、通信や購読も手放す（SPA でアンマウントするとき用）。
+    // 以後このインスタンスは使わない
+    pub fn destroy(&self) {
+        let hooks = {
+            let mut g = self.0.borrow_mut();
+            g.net = None;
+            g.announcer = None;
+            g.clip = None;
+            g.listeners.clear();
+            g.plugins.clear();
+            g.game.events.clear();
+            crash::unregister(&g.canvas.clone().into());
+            g.hooks.take()
+        };
+        // クロージャが持っている GameCell もここで落ちる（借用を外してから）
+        drop(hooks);
+        RUNNING.with(|r| {
+            let mut r = r.borrow_mut();
+            if r.as_ref().is_some_and(|g| Rc::ptr_eq(&g.0, &self.0)) { *r = None; }
+        });
+    }
+
+    pub fn tick(&self) { self.tick_at(now_ms()); }
+}
+
+impl GameCell {
+    // t は rAF のタイムスタンプ（ホストが tick() を呼ぶときは今の時刻）
+    fn tick_at(&self, t: f64) {
+        let (dt, plugins) = {
+            let mut g = self.0.borrow_mut();
+            let cap = power::fps_cap(&g.config, &g.game.settings, g.battery.low());
+            if !g.gate.ready(t, cap) { return; }
+            g.perf.record(t);
+            // rAF の時刻は now_ms より少し前のことがあるので、負なら 0 にする
+            let raw = ((t - g.last_t) / 1000.0).max(0.0);
+            g.last_t = t;
+            (g.smoother.next(raw), g.plugins.clone())
+        };
+        // プラグインからも GameCell を触れるよう、借用を外してから呼ぶ
+        let hook = |h: Hook| {
+            if !plugins.iter().any(|(p, _)| *p == h) { return 1.0; }
+            let view = plugin::view(&self.0.borrow().game, dt);
+            plugin::run(&plugins, h, &view)
+        };
+        let scale = hook(Hook::PreUpdate);
+        let events = self.0.borrow_mut().step(dt * scale);
+        hook(Hook::PostUpdate);
+        hook(Hook::PreDraw);
+        {
+            let mut g = self.0.borrow_mut();
+            if !g.context_lost { g.draw(); }
+        }
+        hook(Hook::PostDraw);
+        if events.is_empty() { return; }
+        let listeners = self.0.borrow().listeners.clone();
+        // コールバックから GameCell を触れるよう、借用を外してから呼ぶ
+        for (name, detail) in events {
+            for (_, f) in listeners.iter().filter(|(n, _)| n == name) {
+                let _ = f.call1(&JsValue::NULL, &detail);
+            }
+        }
+    }
+}
+
+pub(crate) type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+// ページに #game があればそれで1つ起動する（無ければホストが new GameCell(...) で作る）
+pub(crate) fn start() -> Result<(), JsValue> {
+    let game = match GameCell::with_config(GameConfig::default()) {
+        Ok(game) => game,
+        // worker の中（window が無い）でも読み込まれるので、そのときも何もしない
+        Err(GameError::CanvasNotFound(_) | GameError::NoWindow) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    game.run()?;
+    if let Some(meteors) = bench::from_query() { game.0.borrow_mut().bench(meteors, BenchKind::Both); }
+    RUNNING.with(|r| *r.borrow_mut() = Some(game));
+    Ok(())
+}
+
+// requestAnimationFrame ループ。クロージャは自分を弱参照で持ち、Hooks が落ちたら次を予約しない
+fn start_frame_loop(game: GameCell) -> Result<(FrameCallback, Rc<std::cell::Cell<i32>>), GameError> {
+    let f: FrameCallback = Rc::new(RefCell::new(None));
+    let raf_id = Rc::new(std::cell::Cell::new(0));
+    let cb = {
+        let (weak, raf_id) = (Rc::downgrade(&f), raf_id.clone());
+        Closure::wrap(Box::new(move |t: f64| {
+            game.tick_at(t);
+            let (Some(f), Some(window)) = (weak.upgrade(), window()) else { return };
+            let f = f.borrow();
+            let Some(cb) = f.as_ref() else { return };
+            if let Ok(id) = window.request_animation_frame(cb.as_ref().unchecked_ref()) { raf_id.set(id); }
+        }) as Box<dyn FnMut(f64)>)
+    };
+
+    let window = window().ok_or(GameError::NoWindow)?;
+    raf_id.set(window.request_animation_frame(cb.as_ref().unchecked_ref())
+        .map_err(|e| GameError::dom("requestAnimationFrame", e))?);
+    *f.borrow_mut() = Some(cb);
+    Ok((f, raf_id))
+}
@@ -0,0 +1,388 @@
+// WebGL2 での描画。矩形はすべて単位四角形のインスタンス（位置・大きさ・色）として1回で描き、
+// 文字の HUD はオフスクリーンの 2D canvas に描いてテクスチャで重ねる
+use crate::canvas2d::Canvas2dRenderer;
+use crate::collide::Collider;
+use crate::gravity;
+use crate::render::{self, Frame, Renderer};
+use wasm_bindgen::JsCast;
+use web_sys::{
+    window, HtmlCanvasElement, WebGl2RenderingContext as Gl, WebGlBuffer, WebGlProgram, WebGlShader, WebGlTexture,
+    WebGlUniformLocation, WebGlVertexArrayObject,
+};
+
+const VERTEX_SHADER: &str = r#"#version 300 es
+layout(location = 0) in vec2 a_corner;
+layout(location = 1) in vec4 a_rect;
+layout(location = 2) in vec4 a_color;
+uniform vec2 u_size;
+out vec4 v_color;
+out vec2 v_uv;
+void main() {
+    vec2 p = (a_rect.xy + a_corner * a_rect.zw) / u_size * 2.0 - 1.0;
+    v_color = a_color;
+    v_uv = a_corner;
+    gl_Position = vec4(p.x, -p.y, 0.0, 1.0);
+}
+"#;
+
+// アルファは乗算済みで出す（HUD のテクスチャもそう読み込む）
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+in vec4 v_color;
+in vec2 v_uv;
+uniform sampler2D u_tex;
+uniform bool u_textured;
+out vec4 out_color;
+void main() {
+    out_color = u_textured ? texture(u_tex, v_uv) : vec4(v_color.rgb * v_color.a, v_color.a);
+}
+"#;
+
+// インスタンス1個 = x, y, w, h, r, g, b, a
+const FLOATS_PER_QUAD: i32 = 8;
+
+type Color = [f32; 4];
+
+// GPU 側の資源（コンテキストロストで無効になるので丸ごと作り直す）
+struct Gpu {
+    program: WebGlProgram,
+    vao: WebGlVertexArrayObject,
+    instances: WebGlBuffer,
+    hud_tex: WebGlTexture,
+    u_size: Option<WebGlUniformLocation>,
+    u_textured: Option<WebGlUniformLocation>,
+}
+
+pub(crate) struct WebGl2Renderer {
+    gl: Gl,
+    gpu: Option<Gpu>,
+    hud_canvas: HtmlCanvasElement,
+    hud: Canvas2dRenderer,
+    dpr: f64,
+    // 毎フレーム作り直すインスタンスの配列（確保し直さないよう持ち回る）
+    quads: Vec<f32>,
+    // 隕石の形（render::rock_shape / box_shape の書き込み先。同じく持ち回る）
+    shape: Vec<(f64, f64)>,
+    // push() する矩形をずらす量（カメラの揺れ）
+    shift: (f64, f64),
+    // その後にかける寄り（倍率, 中心の x, y）
+    zoom: (f64, f64, f64),
+}
+
+impl WebGl2Renderer {
+    // WebGL2 が使えない・シェーダが通らないときは None（呼び出し側で Canvas2D にする）
+    pub fn new(canvas: &HtmlCanvasElement, dpr: f64) -> Option<Self> {
+        let gl = canvas.get_context("webgl2").ok()??.dyn_into::<Gl>().ok()?;
+        let hud_canvas = window()?.document()?.create_element("canvas").ok()?.dyn_into::<HtmlCanvasElement>().ok()?;
+        hud_canvas.set_width(canvas.width());
+        hud_canvas.set_height(canvas.height());
+        let hud = Canvas2dRenderer::new(&hud_canvas, dpr).ok()?;
+        let gpu = Some(Gpu::new(&gl)?);
+        Some(Self { gl, gpu, hud_canvas, hud, dpr, quads: Vec::new(), shape: Vec::new(), shift: (0.0, 0.0), zoom: (1.0, 0.0, 0.0) })
+    }
+
+    fn push(&mut self, x: f64, y: f64, w: f64, h: f64, c: Color) {
+        let (z, fx, fy) = self.zoom;
+        let (x, y) = (fx + (x + self.shift.0 - fx) * z, fy + (y + self.shift.1 - fy) * z);
+        let (w, h) = (w * z, h * z);
+        self.quads.extend_from_slice(&[x as f32, y as f32, w as f32, h as f32, c[0], c[1], c[2], c[3]]);
+    }
+
+    // 枠線は太さ t の細い矩形4本
+    fn push_outline(&mut self, x: f64, y: f64, w: f64, h: f64, t: f64, c: Color) {
+        self.push(x, y, w, t, c);
+        self.push(x, y + h - t, w, t, c);
+        self.push(x, y, t, h, c);
+        self.push(x + w - t, y, t, h, c);
+    }
+
+    // 線分は 2px おきの点で描く（デバッグ表示用）
+    fn push_segment(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, c: Color) {
+        let n = ((x1 - x0).hypot(y1 - y0) / 2.0).ceil().max(1.0) as usize;
+        for i in 0..=n {
+            let t = i as f64 / n as f64;
+            self.push(x0 + (x1 - x0) * t - 0.5, y0 + (y1 - y0) * t - 0.5, 1.0, 1.0, c);
+        }
+    }
+
+    // 円の輪郭は 2px おきの点で描く（デバッグ表示用）
+    fn push_ring(&mut self, x: f64, y: f64, r: f64, c: Color) {
+        let n = (std::f64::consts::TAU * r / 2.0).ceil().max(8.0) as usize;
+        for i in 0..n {
+            let a = std::f64::consts::TAU * i as f64 / n as f64;
+            self.push(x + r * a.cos() - 0.5, y + r * a.sin() - 0.5, 1.0, 1.0, c);
+        }
+    }
+
+    // 多角形は 2px の横帯を積んで近似する（帯の中央の高さでの左右の端）
+    fn push_polygon(&mut self, pts: &[(f64, f64)], c: Color) {
+        let (top, bottom) = pts.iter().fold((f64::MAX, f64::MIN), |(t, b), p| (t.min(p.1), b.max(p.1)));
+        let mut y = top;
+        while y < bottom {
+            let yc = y + 1.0;
+            let (mut left, mut right) = (f64::MAX, f64::MIN);
+            for (i, &(ax, ay)) in pts.iter().enumerate() {
+                let (bx, by) = pts[(i + 1) % pts.len()];
+                if (ay <= yc) == (by <= yc) { continue; }
+                let x = ax + (yc - ay) / (by - ay) * (bx - ax);
+                left = left.min(x);
+                right = right.max(x);
+            }
+            if left < right { self.push(left, y, right - left, 2.0_f64.min(bottom - y), c); }
+            y += 2.0;
+        }
+    }
+
+    // Canvas2D 版の draw_world と同じ内容を矩形の列にする
+    fn build_world(&mut self, f: &Frame) {
+        self.quads.clear();
+        let g = f.game;
+        let colors = f.theme;
+        let stars = parse_color(&colors.stars);
+        for (x, y) in render::stars(g.width, g.height) { self.push(x, y, 1.0, 1.0, stars); }
+        let pulse = render::milestone_pulse(g);
+        if pulse > 0.0 { self.push(0.0, 0.0, g.width, g.height, with_alpha(parse_color(&colors.accent), pulse)); }
+
+        // 自機から先はカメラの揺れの分だけずらす（天候は画面に固定）
+        self.shift = g.camera.offset();
+        self.zoom = g.camera.zoom();
+        // 矩形は回せないので、自機の傾きは Canvas2D 版だけ
+        for (i, p) in g.players.iter().enumerate().filter(|(_, p)| p.alive) {
+            let x = f.lerp(p.prev_x, p.r.x);
+            if f.shapes() {
+                self.push_polygon(&render::ship_shape(i, x, p.r.y, p.r.w, p.r.h), parse_color(f.player_color(i)));
+                continue;
+            }
+            self.push(x, p.r.y, p.r.w, p.r.h, parse_color(f.player_color(i)));
+            self.push_outline(x, p.r.y, p.r.w, p.r.h, f.line_width(), parse_color(&colors.text));
+        }
+        let player = g.players[0].r;
+
+        if let Some(net) = f.net {
+            for r in net.remotes().values() {
+                let a = if r.alive { 0.5 } else { 0.15 };
+                self.push_outline(r.x * g.width, player.y, player.w, player.h, 1.0, with_alpha(parse_color(&colors.remote), a));
+            }
+        }
+
+        if let (true, false, false, Some(ghost)) = (g.settings.show_ghost, g.over, g.versus, &g.best_ghost) {
+            if let Some(gx) = ghost.position_at(g.stats.elapsed) {
+                self.push(gx * g.width, player.y, player.w, player.h, with_alpha(parse_color(&colors.ghost), 0.3));
+            }
+        }
+
+        for l in &g.lasers {
+            for (x, w, color, a) in render::laser_bands(f, l) { self.push(x, 0.0, w, g.height, with_alpha(parse_color(color), a)); }
+        }
+        for p in &g.pickups {
+            for (pts, color) in render::pickup_shape(f, p) { self.push_polygon(&pts, parse_color(color)); }
+        }
+        let (wall, edge) = (parse_color(render::BARRIER), parse_color(render::BARRIER_EDGE));
+        for b in &g.barriers {
+            for r in render::barrier_walls(f, b).into_iter().filter(|r| r.w > 0.0) {
+                self.push(r.x, r.y, r.w, r.h, wall);
+                self.push(r.x, r.y, r.w, 2.0, edge);
+                self.push(r.x, r.y + r.h - 2.0, r.w, 2.0, edge);
+            }
+        }
+        let (glow, core) = (parse_color(render::WELL_GLOW), parse_color(render::WELL_CORE));
+        for w in &g.wells {
+            for (x, y, s, a) in render::well_swirl(f, w) { self.push(x - s * 0.5, y - s * 0.5, s, s, with_alpha(glow, a)); }
+            let y = f.lerp(w.prev_y, w.y);
+            self.push_polygon(&render::disc(w.x, y, gravity::CORE + f.line_width()), glow);
+            self.push_polygon(&render::disc(w.x, y, gravity::CORE), core);
+        }
+
+        let meteor = parse_color(&colors.meteor);
+        for (x, y, w, h, a) in render::meteor_trails(f) { self.push(x, y, w, h, with_alpha(meteor, a)); }
+        // push_polygon が self を借りるので、形の Vec はループの間だけ取り出しておく
+        let mut shape = std::mem::take(&mut self.shape);
+        for m in &g.meteors {
+            let y = f.lerp(m.prev_y, m.r.y);
+            let (cx, cy) = (m.r.x + m.r.w * 0.5, y + m.r.h * 0.5);
+            if f.shapes() {
+                render::rock_shape(&mut shape, m.id, m.r.x, y, m.r.w, m.r.h);
+            } else {
+                // 高コントラストでは明るい輪郭で囲む（一回り大きく塗ってから上に重ねる）
+                if g.settings.high_contrast {
+                    let t = f.line_width();
+                    render::box_shape(&mut shape, m.r.x - t, y - t, m.r.w + t * 2.0, m.r.h + t * 2.0);
+                    render::rotate(&mut shape, cx, cy, m.angle);
+                    self.push_polygon(&shape, parse_color(&colors.text));
+                }
+                render::box_shape(&mut shape, m.r.x, y, m.r.w, m.r.h);
+            }
+            render::rotate(&mut shape, cx, cy, m.angle);
+            self.push_polygon(&shape, meteor);
+        }
+        self.shape = shape;
+
+        for p in g.particles.iter() {
+            self.push(p.x - p.size * 0.5, p.y - p.size * 0.5, p.size, p.size, with_alpha(parse_color(p.kind.color()), p.alpha()));
+        }
+
+        if f.config.debug {
+            let (hit, sense) = (parse_color(render::DEBUG_HITBOX), parse_color(render::DEBUG_SENSE));
+            for m in &g.meteors {
+                let s = m.sense_rect();
+                self.push_outline(s.x, s.y, s.w, s.h, 1.0, sense);
+            }
+            for h in g.meteors.iter().map(|m| m.hitbox()) {
+                match h {
+                    Collider::Circle(h) => self.push_ring(h.x, h.y, h.r, hit),
+                    Collider::Obb(b) => {
+                        let pts = b.corners();
+                        for i in 0..4 { self.push_segment(pts[i].0, pts[i].1, pts[(i + 1) % 4].0, pts[(i + 1) % 4].1, hit); }
+                    }
+                }
+            }
+            for r in g.players.iter().filter(|p| p.alive).map(|p| p.hitbox(g.hitbox_shrink())) {
+                self.push_outline(r.x, r.y, r.w, r.h, 1.0, hit);
+            }
+        }
+
+        self.shift = (0.0, 0.0);
+        self.zoom = (1.0, 0.0, 0.0);
+        for (y, h, a) in render::fog_bands(g) {
+            self.push(0.0, y, g.width, h, [120.0 / 255.0, 92.0 / 255.0, 60.0 / 255.0, a as f32]);
+        }
+        let streak = parse_color(render::STREAK_COLOR);
+        for (x, y, w, a) in render::wind_streaks(g) { self.push(x, y, w, 1.0, with_alpha(streak, a)); }
+        if let Some((rects, a)) = render::trap_fog(f) {
+            let fog = with_alpha(parse_color(render::FOG_COLOR), a);
+            for r in rects { self.push(r.x, r.y, r.w, r.h, fog); }
+        }
+        let noise = [200.0 / 255.0, 220.0 / 255.0, 1.0, (0.35 * g.weather.static_level()) as f32];
+        for (x, y, w) in render::static_noise(g) { self.push(x, y, w, 1.0, noise); }
+    }
+}
+
+impl Renderer for WebGl2Renderer {
+    fn name(&self) -> &'static str { "webgl2" }
+
+    fn draw(&mut self, f: &Frame) {
+        if self.gpu.is_none() { return; }
+        self.build_world(f);
+        let (w, h) = (f.game.width, f.game.height);
+        self.hud.clear(w, h);
+        self.hud.draw_hud(f);
+
+        let gl = &self.gl;
+        let Some(gpu) = &self.gpu else { return };
+        gl.viewport(0, 0, self.hud_canvas.width() as i32, self.hud_canvas.height() as i32);
+        let bg = parse_color(&f.theme.background);
+        gl.clear_color(bg[0], bg[1], bg[2], 1.0);
+        gl.clear(Gl::COLOR_BUFFER_BIT);
+        gl.use_program(Some(&gpu.program));
+        gl.bind_vertex_array(Some(&gpu.vao));
+        gl.uniform2f(gpu.u_size.as_ref(), w as f32, h as f32);
+
+        // 盤面：全部の矩形をまとめて1回で
+        gl.uniform1i(gpu.u_textured.as_ref(), 0);
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&gpu.instances));
+        gl.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &js_sys::Float32Array::from(&self.quads[..]), Gl::STREAM_DRAW);
+        gl.draw_arrays_instanced(Gl::TRIANGLE_STRIP, 0, 4, self.quads.len() as i32 / FLOATS_PER_QUAD);
+
+        // HUD：2D canvas を転送して画面全体に1枚
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&gpu.hud_tex));
+        let _ = gl.tex_image_2d_with_u32_and_u32_and_html_canvas_element(
+            Gl::TEXTURE_2D, 0, Gl::RGBA as i32, Gl::RGBA, Gl::UNSIGNED_BYTE, &self.hud_canvas,
+        );
+        let full = [0.0, 0.0, w as f32, h as f32, 0.0, 0.0, 0.0, 0.0];
+        gl.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &js_sys::Float32Array::from(&full[..]), Gl::STREAM_DRAW);
+        gl.uniform1i(gpu.u_textured.as_ref(), 1);
+        gl.draw_arrays_instanced(Gl::TRIANGLE_STRIP, 0, 4, 1);
+    }
+
+    // HUD の canvas も同じ解像度に揃える
+    fn resize(&mut self, dpr: f64) {
+        self.dpr = dpr;
+        if let Some(c) = self.gl.canvas().and_then(|c| c.dyn_into::<HtmlCanvasElement>().ok()) {
+            self.hud_canvas.set_width(c.width());
+            self.hud_canvas.set_height(c.height());
+        }
+        self.hud.resize(dpr);
+    }
+
+    fn restore(&mut self) {
+        self.gpu = Gpu::new(&self.gl);
+        self.resize(self.dpr);
+    }
+}
+
+impl Gpu {
+    fn new(gl: &Gl) -> Option<Gpu> {
+        let program = link(gl, &compile(gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?, &compile(gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?)?;
+        let vao = gl.create_vertex_array()?;
+        gl.bind_vertex_array(Some(&vao));
+
+        // 単位四角形の4頂点（TRIANGLE_STRIP）
+        let corners = gl.create_buffer()?;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&corners));
+        let quad: [f32; 8] = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        gl.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &js_sys::Float32Array::from(&quad[..]), Gl::STATIC_DRAW);
+        gl.enable_vertex_attrib_array(0);
+        gl.vertex_attrib_pointer_with_i32(0, 2, Gl::FLOAT, false, 0, 0);
+
+        let instances = gl.create_buffer()?;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&instances));
+        let stride = FLOATS_PER_QUAD * 4;
+        for (loc, offset) in [(1, 0), (2, 16)] {
+            gl.enable_vertex_attrib_array(loc);
+            gl.vertex_attrib_pointer_with_i32(loc, 4, Gl::FLOAT, false, stride, offset);
+            gl.vertex_attrib_divisor(loc, 1);
+        }
+
+        let hud_tex = gl.create_texture()?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&hud_tex));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.pixel_storei(Gl::UNPACK_PREMULTIPLY_ALPHA_WEBGL, 1);
+
+        gl.enable(Gl::BLEND);
+        gl.blend_func(Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
+        let u_size = gl.get_uniform_location(&program, "u_size");
+        let u_textured = gl.get_uniform_location(&program, "u_textured");
+        Some(Gpu { program, vao, instances, hud_tex, u_size, u_textured })
+    }
+}
+
+fn compile(gl: &Gl, kind: u32, src: &str) -> Option<WebGlShader> {
+    let shader = gl.create_shader(kind)?;
+    gl.shader_source(&shader, src);
+    gl.compile_shader(&shader);
+    gl.get_shader_parameter(&shader, Gl::COMPILE_STATUS).as_bool().unwrap_or(false).then_some(shader)
+}
+
+fn link(gl: &Gl, vs: &WebGlShader, fs: &WebGlShader) -> Option<WebGlProgram> {
+    let program = gl.create_program()?;
+    gl.attach_shader(&program, vs);
+    gl.attach_shader(&program, fs);
+    gl.link_program(&program);
+    gl.get_program_parameter(&program, Gl::LINK_STATUS).as_bool().unwrap_or(false).then_some(program)
+}
+
+fn with_alpha(c: Color, a: f64) -> Color { [c[0], c[1], c[2], c[3] * a as f32] }
+
+// 設定の色（"#rgb" / "#rrggbb" / "rgb(...)" / "rgba(...)"）。読めなければ白
+fn parse_color(s: &str) -> Color {
+    let s = s.trim();
+    let byte = |v: u32| v as f32 / 255.0;
+    if let Some(hex) = s.strip_prefix('#') {
+        let Ok(v) = u32::from_str_radix(hex, 16) else { return [1.0; 4] };
+        return match hex.len() {
+            3 => [byte((v >> 8 & 0xF) * 17), byte((v >> 4 & 0xF) * 17), byte((v & 0xF) * 17), 1.0],
+            6 => [byte(v >> 16 & 0xFF), byte(v >> 8 & 0xFF), byte(v & 0xFF), 1.0],
+            _ => [1.0; 4],
+        };
+    }
+    let inner = s.strip_prefix("rgba(").or_else(|| s.strip_prefix("rgb(")).and_then(|r| r.strip_suffix(')'));
+    let Some(parts) = inner.map(|r| r.split(',').map(|p| p.trim().parse::<f32>()).collect::<Result<Vec<_>, _>>()) else { return [1.0; 4] };
+    match parts.as_deref() {
+        Ok([r, g, b]) => [r / 255.0, g / 255.0, b / 255.0, 1.0],
+        Ok([r, g, b, a]) => [r / 255.0, g / 255.0, b / 255.0, *a],
+        _ => [1.0; 4],
+    }
+}
@@ -0,0 +1,63 @@
+// 突風（終盤だけ）。WARN 秒の予告（画面を流れる筋と上の帯で向きを見せる）のあと GUST 秒だけ横に吹き、
+// 自機を押し流して隕石の軌道を曲げる。力は吹き始めと終わりでなめらかに増減する
+use serde::{Deserialize, Serialize};
+
+// このスコアから吹き始める
+pub(crate) const FROM_SCORE: f64 = 4000.0;
+// 予告と吹いている時間（秒）
+const WARN: f64 = 1.5;
+const GUST: f64 = 4.0;
+// いちばん強いときの横向きの加速度（px/秒^2）。自機は流され方の減衰があるので最大 80px/秒ほど
+pub(crate) const PLAYER_PUSH: f64 = 240.0;
+pub(crate) const METEOR_PUSH: f64 = 60.0;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Phase { #[default] Calm, Warn, Gust }
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Wind {
+    pub phase: Phase,
+    // 今の段階に入ってからの秒数
+    pub t: f64,
+    // 吹く向き（-1 が左、1 が右）
+    pub dir: f64,
+    // 凪のあいだ、次の予告までの秒数
+    pub next: f64,
+}
+
+impl Wind {
+    pub fn new(next: f64) -> Self { Self { next, ..Self::default() } }
+
+    // 予告を始める
+    pub fn warn(&mut self, dir: f64) { *self = Self { phase: Phase::Warn, t: 0.0, dir, next: 0.0 }; }
+
+    // 段階を進める。吹き終わったら true（次の間隔は呼ぶ側が乱数で決める）
+    pub fn tick(&mut self, dt: f64) -> bool {
+        self.t += dt;
+        match self.phase {
+            Phase::Calm => self.next -= dt,
+            Phase::Warn if self.t >= WARN => { self.phase = Phase::Gust; self.t = 0.0; }
+            Phase::Gust if self.t >= GUST => { self.phase = Phase::Calm; self.t = 0.0; return true; }
+            _ => {}
+        }
+        false
+    }
+
+    // 横向きの力（-1..1）。吹いている間だけ、正弦の山で増えて減る
+    pub fn force(&self) -> f64 {
+        if self.phase != Phase::Gust { return 0.0; }
+        self.dir * (std::f64::consts::PI * self.t / GUST).sin()
+    }
+
+    // 筋の濃さ（0..1）。予告のあいだに 0.5 まで上がり、吹いている間は力の強さ
+    pub fn visibility(&self) -> f64 {
+        match self.phase {
+            Phase::Calm => 0.0,
+            Phase::Warn => 0.5 * self.t / WARN,
+            Phase::Gust => 0.5 + 0.5 * self.force().abs(),
+        }
+    }
+
+    // 警告の帯を出す向き（予告か吹いている間）
+    pub fn banner(&self) -> Option<f64> { (self.phase != Phase::Calm).then_some(self.dir) }
+}
@@ -0,0 +1,420 @@
+// Web Worker 版：シミュレーションと描画を worker に移し、canvas は OffscreenCanvas として渡す。
+// メインスレッドはキー入力とサイズ変更を postMessage で送り、worker からはイベントと保存データが返ってくる
+//
+// メッセージ（main → worker）: {"t":"init","canvas":OffscreenCanvas,"config":{...},"width","height","dpr","storage":{キー:値},"reducedMotion","debug","lowBattery"}
+//                              {"t":"key","key":"ArrowLeft","down":true}
+//                              {"t":"resize","width","height","dpr"}
+//                              {"t":"tap","x","y"}   canvas 上のタップ（CSS px。一時停止ボタンとメニュー）
+//                              {"t":"power","lowBattery":true}   電池の残りが少なくなった／戻った（worker からは電池が見えない）
+//           （worker → main）: {"t":"ready"}   worker_main() の後。これを受けてから init を送る
+//                              {"t":"event","name":"gameover","detail":{...}}
+//                              {"t":"store","key":"...","value":"..."}   localStorage への書き込みを頼む
+//                              {"t":"announce","text":"..."}   スクリーンリーダー向けの読み上げ
+//                              {"t":"sound","name":"hit","pan":0,"vibrate":true}   効果音と振動（worker では鳴らせない）
+//                              {"t":"music","track":"title"}   BGM の切り替え（変わったときだけ）
+//                              {"t":"mix","master","music","sfx"}   音量（変わったときだけ）
+//                              {"t":"error","message":"..."}
+//                              {"t":"crash","message":"...","location":"file:line:col"}   パニックした（config の onError を呼ぶ）
+use crate::analytics::Analytics;
+use crate::announce::Announcer;
+use crate::assets::Assets;
+use crate::audio::{Audio, Mix, Track};
+use crate::canvas2d::Canvas2dRenderer;
+use crate::config::GameConfig;
+use crate::core::{Cue, Game, Sound};
+use crate::crash;
+use crate::error::GameError;
+use crate::haptics;
+use crate::i18n::Lang;
+use crate::leaderboard::Leaderboard;
+use crate::menu;
+use crate::perf::{DtSmoother, FrameStats};
+use crate::power::{self, Battery, FrameGate};
+use crate::profile;
+use crate::render::{Frame, Renderer};
+use crate::rng::{XorShift, DEFAULT_SEED};
+use crate::storage::{Storage, KEY_PREFIX};
+use crate::theme::Theme;
+use crate::web::{self, js_object, listen, local_storage, now_ms, FrameCallback, Listener, LocalStorage, ResizeWatch, EVENT_NAMES};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    window, DedicatedWorkerGlobalScope, HtmlCanvasElement, KeyboardEvent, MessageEvent, MouseEvent, OffscreenCanvas, Worker, WorkerOptions,
+    WorkerType,
+};
+
+type Listeners = Rc<RefCell<Vec<(String, js_sys::Function)>>>;
+
+// メインスレッド側のハンドル。canvas は渡した時点でこちらからは描けなくなる。
+// 自動起動（#game）と同じ canvas には使えないので、別の id の canvas を渡すこと
+#[wasm_bindgen]
+pub struct GameWorker {
+    worker: Worker,
+    listeners: Listeners,
+    hooks: Vec<Listener>,
+    resize: Option<ResizeWatch>,
+    _battery: Battery,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+#[wasm_bindgen]
+impl GameWorker {
+    // script_url は worker_main() を呼ぶモジュール worker（同梱の worker.js）。config は GameCell と同じ
+    #[wasm_bindgen(constructor)]
+    pub fn new(script_url: &str, config: JsValue) -> Result<GameWorker, JsValue> {
+        let parsed = GameConfig::from_js(config.clone())?;
+        let canvas = parsed.resolve_canvas()?;
+        // worker では AudioContext が使えないので、音はこちらで鳴らす
+        let audio = Audio::load(&parsed.audio);
+        let offscreen = canvas.transfer_control_to_offscreen().map_err(|e| GameError::dom("transferControlToOffscreen", e))?;
+        let opts = WorkerOptions::new();
+        opts.set_type(WorkerType::Module);
+        let worker = Worker::new_with_options(script_url, &opts).map_err(|e| GameError::dom("new Worker", e))?;
+        let listeners: Listeners = Rc::default();
+        let low_battery = Rc::new(Cell::new(false));
+        let battery = {
+            let (worker, low_battery) = (worker.clone(), low_battery.clone());
+            Battery::watch(move |low| {
+                low_battery.set(low);
+                let _ = worker.post_message(&js_object(&[("t", "power".into()), ("lowBattery", low.into())]));
+            })
+        };
+
+        let on_message = {
+            let (worker, canvas, listeners, audio) = (worker.clone(), canvas.clone(), listeners.clone(), audio.clone());
+            let on_error = parsed.on_error();
+            let mut init = Some((offscreen, worker_config(&config)));
+            let announcer = Announcer::new(&canvas);
+            Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+                let msg = e.data();
+                let get = |k: &str| js_sys::Reflect::get(&msg, &k.into()).unwrap_or(JsValue::UNDEFINED);
+                match get("t").as_string().as_deref() {
+                    // 読み込み中に送ると取りこぼすので、準備ができてから今のサイズで始める
+                    Some("ready") => {
+                        let Some((offscreen, config)) = init.take() else { return };
+                        let msg = size_message(&canvas, "init");
+                        for (k, v) in [("canvas", offscreen.clone().into()), ("config", config), ("storage", storage_snapshot()), ("reducedMotion", web::prefers_reduced_motion().into()), ("debug", web::query_flag("debug").into()), ("profile", web::query_flag("profile").into()), ("lowBattery", low_battery.get().into())] {
+                            let _ = js_sys::Reflect::set(&msg, &k.into(), &v);
+                        }
+                        let _ = worker.post_message_with_transfer(&msg, &js_sys::Array::of1(&offscreen));
+                    }
+                    Some("event") => {
+                        let name = get("name").as_string().unwrap_or_default();
+                        // コールバックから on/off を呼べるよう、借用を外してから
+                        let ls = listeners.borrow().clone();
+                        for (_, f) in ls.iter().filter(|(n, _)| *n == name) { let _ = f.call1(&JsValue::NULL, &get("detail")); }
+                    }
+                    Some("store") => {
+                        if let (Some(k), Some(v)) = (get("key").as_string(), get("value").as_string()) { LocalStorage.set(&k, &v); }
+                    }
+                    Some("sound") => {
+                        if let Some(sound) = get("name").as_string().and_then(|n| Sound::parse(&n)) {
+                            audio.play(Cue { sound, pan: get("pan").as_f64().unwrap_or(0.0) });
+                            if get("vibrate").as_bool() == Some(true) { haptics::vibrate(sound); }
+                        }
+                    }
+                    Some("music") => {
+                        if let Some(track) = get("track").as_string().and_then(|n| Track::parse(&n)) { audio.set_music(track); }
+                    }
+                    Some("mix") => {
+                        let num = |k: &str| get(k).as_f64().unwrap_or(1.0);
+                        audio.set_mix(Mix { master: num("master"), music: num("music"), sfx: num("sfx") });
+                    }
+                    Some("announce") => {
+                        if let (Some(a), Some(text)) = (&announcer, get("text").as_string()) { a.say(&text); }
+                    }
+                    Some("error") => web_sys::console::error_1(&get("message")),
+                    Some("crash") => {
+                        if let Some(f) = &on_error { let _ = f.call1(&JsValue::NULL, &js_object(&[("message", get("message")), ("location", get("location"))])); }
+                    }
+                    _ => {}
+                }
+            })
+        };
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let mut hooks = forward_keys(&worker, &audio, &canvas)?;
+        let resize = observe_resize(&worker, &canvas).ok();
+        if resize.is_none() {
+            let win = window().ok_or(GameError::NoWindow)?;
+            for name in ["resize", "orientationchange"] {
+                let (worker, canvas) = (worker.clone(), canvas.clone());
+                hooks.push(listen(&win, name, Closure::new(move |_e: web_sys::Event| { let _ = worker.post_message(&size_message(&canvas, "resize")); }))?);
+            }
+        }
+        Ok(GameWorker { worker, listeners, hooks, resize, _battery: battery, _on_message: on_message })
+    }
+
+    // GameCell::on と同じイベント（start / spawn / hit / gameover / score / nearmiss / wave / achievement / powerup / milestone）。worker から届いたものをメインスレッドで配る
+    pub fn on(&self, event: &str, callback: js_sys::Function) -> Result<(), JsValue> {
+        if !EVENT_NAMES.contains(&event) {
+            return Err(JsValue::from_str(&format!("unknown event: {}", event)));
+        }
+        self.listeners.borrow_mut().push((event.to_string(), callback));
+        Ok(())
+    }
+
+    pub fn off(&self, event: &str) {
+        self.listeners.borrow_mut().retain(|(n, _)| n != event);
+    }
+
+    // worker を止めてリスナーを外す（以後このインスタンスは使えない）
+    pub fn destroy(self) {}
+}
+
+impl Drop for GameWorker {
+    fn drop(&mut self) {
+        for (target, name, cb) in &self.hooks {
+            let _ = target.remove_event_listener_with_callback(name, cb.as_ref().unchecked_ref());
+        }
+        if let Some((observer, _)) = &self.resize { observer.disconnect(); }
+        self.worker.set_onmessage(None);
+        self.worker.terminate();
+    }
+}
+
+// canvas 要素は送れないので外した設定（文字列や要素だけを渡されたときは既定値）
+fn worker_config(config: &JsValue) -> JsValue {
+    if !config.is_object() || config.is_instance_of::<HtmlCanvasElement>() { return JsValue::UNDEFINED; }
+    let copy = js_sys::Object::assign(&js_sys::Object::new(), config.unchecked_ref());
+    // 要素と関数は postMessage で送れない
+    let _ = js_sys::Reflect::delete_property(&copy, &"canvas".into());
+    let _ = js_sys::Reflect::delete_property(&copy, &"onError".into());
+    copy.into()
+}
+
+// worker からは localStorage が見えないので、このゲームのキーだけ写して渡す
+fn storage_snapshot() -> JsValue {
+    let o = js_sys::Object::new();
+    let Some(s) = local_storage() else { return o.into() };
+    for i in 0..s.length().unwrap_or(0) {
+        let Some(key) = s.key(i).ok().flatten().filter(|k| k.starts_with(KEY_PREFIX)) else { continue };
+        if let Ok(Some(v)) = s.get_item(&key) { let _ = js_sys::Reflect::set(&o, &key.into(), &v.into()); }
+    }
+    o.into()
+}
+
+fn size_message(canvas: &HtmlCanvasElement, t: &str) -> JsValue {
+    js_object(&[
+        ("t", t.into()),
+        ("width", canvas.client_width().into()),
+        ("height", canvas.client_height().into()),
+        ("dpr", window().map_or(1.0, |w| w.device_pixel_ratio()).into()),
+    ])
+}
+
+fn forward_keys(worker: &Worker, audio: &Audio, canvas: &HtmlCanvasElement) -> Result<Vec<Listener>, GameError> {
+    let win = window().ok_or(GameError::NoWindow)?;
+    let mut hooks = Vec::new();
+    for (name, down) in [("keydown", true), ("keyup", false)] {
+        let (worker, audio) = (worker.clone(), audio.clone());
+        let cb = Closure::<dyn FnMut(web_sys::Event)>::new(move |e: web_sys::Event| {
+            let Some(e) = e.dyn_ref::<KeyboardEvent>() else { return };
+            if down { audio.unlock(); }
+            if down && ["ArrowLeft", "ArrowRight", "ArrowUp", "ArrowDown", "Space", "F3"].contains(&e.key().as_str()) { e.prevent_default(); }
+            let _ = worker.post_message(&js_object(&[("t", "key".into()), ("key", e.key().into()), ("down", down.into())]));
+        });
+        hooks.push(listen(&win, name, cb)?);
+    }
+    // タップでも音を有効にし、canvas 上の位置を送る
+    let (worker, audio, canvas) = (worker.clone(), audio.clone(), canvas.clone());
+    hooks.push(listen(&win, "pointerdown", Closure::new(move |e: web_sys::Event| {
+        audio.unlock();
+        let Some(e) = e.dyn_ref::<MouseEvent>() else { return };
+        let rect = canvas.get_bounding_client_rect();
+        let (x, y) = (e.client_x() as f64 - rect.left(), e.client_y() as f64 - rect.top());
+        let _ = worker.post_message(&js_object(&[("t", "tap".into()), ("x", x.into()), ("y", y.into())]));
+    }))?);
+    Ok(hooks)
+}
+
+fn observe_resize(worker: &Worker, canvas: &HtmlCanvasElement) -> Result<ResizeWatch, GameError> {
+    let on_resize = {
+        let (worker, canvas) = (worker.clone(), canvas.clone());
+        Closure::<dyn FnMut()>::new(move || { let _ = worker.post_message(&size_message(&canvas, "resize")); })
+    };
+    let observer = web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())
+        .map_err(|e| GameError::dom("ResizeObserver", e))?;
+    observer.observe(canvas);
+    Ok((observer, on_resize))
+}
+
+// worker 側の保存先：起動時に写してもらった値を持ち、書き込みはメインスレッドに頼む
+struct WorkerStorage { items: RefCell<HashMap<String, String>>, scope: DedicatedWorkerGlobalScope }
+
+impl Storage for WorkerStorage {
+    fn get(&self, key: &str) -> Option<String> { self.items.borrow().get(key).cloned() }
+    fn set(&self, key: &str, value: &str) {
+        self.items.borrow_mut().insert(key.to_string(), value.to_string());
+        let _ = self.scope.post_message(&js_object(&[("t", "store".into()), ("key", key.into()), ("value", value.into())]));
+    }
+}
+
+// worker の中で動くゲーム。描画は Canvas2D のみ（renderer の指定は無視する）
+struct WorkerGame {
+    game: Game,
+    config: GameConfig,
+    renderer: Box<dyn Renderer>,
+    canvas: OffscreenCanvas,
+    scope: DedicatedWorkerGlobalScope,
+    leaderboard: Option<Leaderboard>,
+    analytics: Analytics,
+    assets: Assets,
+    theme: Theme,
+    perf: FrameStats,
+    smoother: DtSmoother,
+    gate: FrameGate,
+    low_battery: bool,
+    // 最後にメインスレッドへ頼んだ BGM と音量（変わったときだけ送る）
+    music: Option<Track>,
+    mix: Option<Mix>,
+    last_t: f64,
+}
+
+impl WorkerGame {
+    fn new(scope: &DedicatedWorkerGlobalScope, msg: &JsValue) -> Result<WorkerGame, JsValue> {
+        let get = |k: &str| js_sys::Reflect::get(msg, &k.into()).unwrap_or(JsValue::UNDEFINED);
+        let canvas = get("canvas").dyn_into::<OffscreenCanvas>().map_err(|_| JsValue::from_str("init message has no OffscreenCanvas"))?;
+        let mut config = GameConfig::from_js(get("config"))?;
+        // worker からは matchMedia もページの URL も見えないので、メインスレッドで調べた値を使う
+        config.sim.prefers_reduced_motion = get("reducedMotion").as_bool().unwrap_or(false);
+        config.sim.language = config.sim.language.or_else(|| Some(Lang::detect(web::navigator_language().as_deref())));
+        config.debug |= get("debug").as_bool().unwrap_or(false);
+        config.sim.profile |= get("profile").as_bool().unwrap_or(false);
+        let (w, h, dpr) = (get("width").as_f64().unwrap_or(0.0), get("height").as_f64().unwrap_or(0.0), get("dpr").as_f64().unwrap_or(1.0));
+        canvas.set_width((w * dpr).round() as u32);
+        canvas.set_height((h * dpr).round() as u32);
+        let renderer = Box::new(Canvas2dRenderer::offscreen(&canvas, dpr)?);
+        crash::register(&canvas.clone().into(), config.sim.language.unwrap_or_default(), None);
+
+        let saved = get("storage");
+        let items = saved.dyn_ref::<js_sys::Object>().map(|o| js_sys::Object::keys(o).iter()
+            .filter_map(|k| Some((k.as_string()?, js_sys::Reflect::get(o, &k).ok()?.as_string()?)))
+            .collect())
+            .unwrap_or_default();
+        let storage = WorkerStorage { items: RefCell::new(items), scope: scope.clone() };
+        let seed = config.sim.seed.unwrap_or(DEFAULT_SEED);
+        let mut game = Game::new(w, h, &config.sim, Box::new(storage), Box::new(XorShift::new(seed)));
+        if config.tutorial && game.first_time() { game.start_tutorial(); }
+        let leaderboard = config.leaderboard.as_ref().map(|l| Leaderboard::new(l.endpoint.clone(), l.player_name.clone()));
+        let assets = Assets::load(&config.assets);
+        let theme = Theme::from_settings(&game.settings, &config.colors);
+        let analytics = Analytics::new(config.analytics_endpoint.clone());
+        Ok(WorkerGame { game, config, renderer, canvas, scope: scope.clone(), leaderboard, analytics, assets, theme, perf: FrameStats::default(), smoother: DtSmoother::default(), gate: FrameGate::default(), low_battery: get("lowBattery").as_bool().unwrap_or(false), music: None, mix: None, last_t: now_ms() })
+    }
+
+    fn resize(&mut self, w: f64, h: f64, dpr: f64) {
+        if w <= 0.0 || h <= 0.0 { return; }
+        self.canvas.set_width((w * dpr).round() as u32);
+        self.canvas.set_height((h * dpr).round() as u32);
+        self.renderer.resize(dpr);
+        self.game.resize(w, h);
+    }
+
+    // キーやタップを渡す。メニューで配色が変わったら作り直す
+    fn input(&mut self, f: impl FnOnce(&mut Game)) {
+        let theme = self.game.settings.theme.clone();
+        f(&mut self.game);
+        if self.game.settings.theme != theme { self.theme = Theme::from_settings(&self.game.settings, &self.config.colors); }
+    }
+
+    // t は rAF のタイムスタンプ
+    fn tick(&mut self, t: f64) {
+        if !self.gate.ready(t, power::fps_cap(&self.config, &self.game.settings, self.low_battery)) { return; }
+        self.perf.record(t);
+        let dt = self.smoother.next(((t - self.last_t) / 1000.0).max(0.0));
+        self.last_t = t;
+        let rotate = self.config.orientation.is_some_and(|o| o != self.game.orientation);
+        if !rotate { self.game.step(dt); }
+        let mix = Mix::of(&self.game.settings);
+        if self.mix != Some(mix) {
+            self.mix = Some(mix);
+            let _ = self.scope.post_message(&js_object(&[("t", "mix".into()), ("master", mix.master.into()), ("music", mix.music.into()), ("sfx", mix.sfx.into())]));
+        }
+        for cue in self.game.sounds.drain(..) {
+            let _ = self.scope.post_message(&js_object(&[("t", "sound".into()), ("name", cue.sound.name().into()), ("pan", cue.pan.into()), ("vibrate", self.game.settings.vibration.into())]));
+        }
+        let track = Track::for_game(&self.game);
+        if self.music != Some(track) {
+            self.music = Some(track);
+            let _ = self.scope.post_message(&js_object(&[("t", "music".into()), ("track", track.name().into())]));
+        }
+        // DOM は触れないので、読み上げはメインスレッドに頼む
+        let scope = &self.scope;
+        let say = |text: String| { let _ = scope.post_message(&js_object(&[("t", "announce".into()), ("text", text.into())])); };
+        for (name, detail) in web::drain_events(&mut self.game, self.leaderboard.as_ref(), &mut self.analytics, |_| true, say) {
+            let _ = self.scope.post_message(&js_object(&[("t", "event".into()), ("name", name.into()), ("detail", detail)]));
+        }
+        let frame = Frame { game: &self.game, config: &self.config, net: None, leaderboard: self.leaderboard.as_ref(), assets: &self.assets, theme: &self.theme, perf: &self.perf, rotate };
+        let _span = profile::span(self.config.sim.profile, "draw");
+        self.renderer.draw(&frame);
+    }
+}
+
+// worker スクリプトから init() の後に呼ぶ。以後はメインスレッドからのメッセージで動く
+#[wasm_bindgen]
+pub fn worker_main() -> Result<(), JsValue> {
+    let scope = js_sys::global().dyn_into::<DedicatedWorkerGlobalScope>()
+        .map_err(|_| JsValue::from_str("worker_main() must be called inside a dedicated worker"))?;
+    crash::install();
+    let cell: Rc<RefCell<Option<WorkerGame>>> = Rc::default();
+    let on_message = {
+        let scope = scope.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| handle_message(&scope, &cell, &e.data()))
+    };
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // worker が終わるまで使い続ける
+    on_message.forget();
+    scope.post_message(&js_object(&[("t", "ready".into())]))
+}
+
+fn handle_message(scope: &DedicatedWorkerGlobalScope, cell: &Rc<RefCell<Option<WorkerGame>>>, msg: &JsValue) {
+    let get = |k: &str| js_sys::Reflect::get(msg, &k.into()).unwrap_or(JsValue::UNDEFINED);
+    let t = get("t").as_string();
+    if t.as_deref() == Some("init") {
+        match WorkerGame::new(scope, msg) {
+            Ok(g) => { *cell.borrow_mut() = Some(g); start_loop(scope, cell.clone()); }
+            Err(e) => {
+                let message = js_sys::Reflect::get(&e, &"message".into()).ok().filter(|m| m.is_string()).unwrap_or(e);
+                let _ = scope.post_message(&js_object(&[("t", "error".into()), ("message", message)]));
+            }
+        }
+        return;
+    }
+    let mut g = cell.borrow_mut();
+    let Some(g) = g.as_mut() else { return };
+    match t.as_deref() {
+        Some("key") => {
+            let key = get("key").as_string().unwrap_or_default();
+            let down = get("down").as_bool() == Some(true);
+            g.input(|game| if down { web::key_down(game, &key) } else { game.set_key(&key, false) });
+        }
+        Some("tap") => {
+            let num = |k: &str| get(k).as_f64().unwrap_or(0.0);
+            g.input(|game| menu::tap(game, num("x"), num("y")));
+        }
+        Some("resize") => {
+            let num = |k: &str| get(k).as_f64().unwrap_or(0.0);
+            g.resize(num("width"), num("height"), num("dpr").max(1.0));
+        }
+        Some("power") => g.low_battery = get("lowBattery").as_bool().unwrap_or(false),
+        _ => {}
+    }
+}
+
+// worker の requestAnimationFrame ループ（worker ごと止めるまで回り続ける）
+fn start_loop(scope: &DedicatedWorkerGlobalScope, cell: Rc<RefCell<Option<WorkerGame>>>) {
+    let f: FrameCallback = Rc::default();
+    let cb = {
+        let (f, scope) = (f.clone(), scope.clone());
+        Closure::<dyn FnMut(f64)>::new(move |t: f64| {
+            if let Some(g) = cell.borrow_mut().as_mut() { g.tick(t); }
+            if let Some(cb) = f.borrow().as_ref() { let _ = scope.request_animation_frame(cb.as_ref().unchecked_ref()); }
+        })
+    };
+    let _ = scope.request_animation_frame(cb.as_ref().unchecked_ref());
+    *f.borrow_mut() = Some(cb);
+}
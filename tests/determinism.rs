@@ -0,0 +1,149 @@
+// 決定性のテスト：記録しておいた入力ログ（tests/replays/*.log）をヘッドレス版で固定ステップずつ流し、
+// 最後のスコアと盤面のハッシュが記録と一致するかを見る。シミュレーションの結果が変わる変更をしたら失敗するので、
+// 意図した変更ならログのヘッダー（score / hash / over）を失敗メッセージの値に書き換える。
+//
+// ログの形式：1行目 "seed=N width=W height=H"、2行目 "score=S hash=HEX over=BOOL"、
+// 以降は入力の連続数（L=左 / R=右 / N=なし、"L12" なら左を 12 ステップ）を空白区切りで
+use meteor_dodge::headless::{HeadlessGame, HeadlessInput, FIXED_STEP};
+use std::collections::HashMap;
+
+struct Log { seed: u64, width: f64, height: f64, score: f64, hash: u64, over: bool, inputs: Vec<HeadlessInput> }
+
+fn parse(text: &str) -> Log {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#'));
+    let mut fields = HashMap::new();
+    for line in lines.by_ref().take(2) {
+        for kv in line.split_whitespace() {
+            let (k, v) = kv.split_once('=').expect("header field must be key=value");
+            fields.insert(k.to_string(), v.to_string());
+        }
+    }
+    let get = |k: &str| fields.get(k).unwrap_or_else(|| panic!("missing {} in header", k)).as_str();
+    let mut inputs = Vec::new();
+    for token in lines.flat_map(str::split_whitespace) {
+        let (kind, count) = token.split_at(1);
+        let input = match kind {
+            "L" => HeadlessInput { left: true, right: false },
+            "R" => HeadlessInput { left: false, right: true },
+            "N" => HeadlessInput::default(),
+            _ => panic!("unknown input {}", token),
+        };
+        let count: usize = count.parse().unwrap_or_else(|_| panic!("bad count in {}", token));
+        inputs.extend(std::iter::repeat_n(input, count));
+    }
+    Log {
+        seed: get("seed").parse().unwrap(),
+        width: get("width").parse().unwrap(),
+        height: get("height").parse().unwrap(),
+        score: get("score").parse().unwrap(),
+        hash: u64::from_str_radix(get("hash"), 16).unwrap(),
+        over: get("over").parse().unwrap(),
+        inputs,
+    }
+}
+
+// ログを最後まで（途中でゲームオーバーになればそこまで）流す
+fn run(log: &Log) -> HeadlessGame {
+    let mut g = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for &input in &log.inputs {
+        if g.is_over() { break; }
+        g.step(FIXED_STEP, input);
+    }
+    g
+}
+
+fn check(name: &str, text: &str) {
+    let log = parse(text);
+    let g = run(&log);
+    let (score, hash, over) = (g.snapshot().score, g.state_hash(), g.is_over());
+    assert!(
+        score == log.score && hash == log.hash && over == log.over,
+        "{}: simulation changed (expected score={} hash={:016x} over={}, got score={} hash={:016x} over={})",
+        name, log.score, log.hash, log.over, score, hash, over,
+    );
+}
+
+#[test]
+fn replay_seed_1_until_game_over() { check("seed-1", include_str!("replays/seed-1.log")); }
+
+#[test]
+fn replay_seed_42() { check("seed-42", include_str!("replays/seed-42.log")); }
+
+#[test]
+fn replay_seed_7_portrait() { check("seed-7", include_str!("replays/seed-7.log")); }
+
+// 同じログを2回流したら、途中のどのステップでも盤面が同じ
+#[test]
+fn same_inputs_same_states() {
+    let log = parse(include_str!("replays/seed-42.log"));
+    let (mut a, mut b) = (HeadlessGame::with_size(log.seed, log.width, log.height), HeadlessGame::with_size(log.seed, log.width, log.height));
+    for (i, &input) in log.inputs.iter().enumerate() {
+        a.step(FIXED_STEP, input);
+        b.step(FIXED_STEP, input);
+        assert_eq!(a.state_hash(), b.state_hash(), "diverged at step {}", i);
+    }
+}
+
+// 途中保存を別のゲームに読み込んだ直後の step で、節目（milestone）やスコアのイベントが余計に出ない
+#[test]
+fn load_does_not_refire_milestones() {
+    let log = parse(include_str!("replays/seed-42.log"));
+    let mut a = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for &input in &log.inputs[..1500] { a.step(FIXED_STEP, input); }
+    assert!(a.snapshot().score > 1000.0, "run did not pass the first milestone");
+    let mut b = HeadlessGame::with_size(log.seed, log.width, log.height);
+    b.load(&a.save()).expect("save state should load");
+    let (expected, got) = (a.step(FIXED_STEP, log.inputs[1500]), b.step(FIXED_STEP, log.inputs[1500]));
+    assert!(!got.events.contains(&"milestone"), "milestone fired right after load: {:?}", got.events);
+    assert_eq!(got.events.contains(&"score"), expected.events.contains(&"score"), "score event differs right after load");
+}
+
+// 決着のゆっくりの最中に読み込んでも、ゆっくりは持ち越さない（読み込んだ続きが保存元と同じ速さで進む）
+#[test]
+fn load_clears_death_slowmo() {
+    let log = parse(include_str!("replays/seed-1.log"));
+    let mut a = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for &input in &log.inputs[..500] { a.step(FIXED_STEP, input); }
+    let mut b = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for &input in &log.inputs {
+        if !b.step(FIXED_STEP, input).players[0].alive { break; }
+    }
+    assert!(!b.snapshot().players[0].alive && !b.is_over(), "run did not reach the death slow-motion");
+    b.load(&a.save()).expect("save state should load");
+    a.step(FIXED_STEP, log.inputs[500]);
+    b.step(FIXED_STEP, log.inputs[500]);
+    assert_eq!(a.state_hash(), b.state_hash(), "loaded run did not advance at full speed");
+}
+
+// 途中保存 → 読み込み → 続きの step が、保存しなかった場合とどのステップでも同じ盤面・同じイベントになる
+#[test]
+fn save_load_round_trip() {
+    let log = parse(include_str!("replays/seed-7.log"));
+    let mut a = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for &input in &log.inputs[..1000] { a.step(FIXED_STEP, input); }
+    let mut b = HeadlessGame::with_size(log.seed, log.width, log.height);
+    b.load(&a.save()).expect("save state should load");
+    assert_eq!(a.state_hash(), b.state_hash(), "state changed across save and load");
+    assert_eq!(format!("{:?}", a.snapshot().players), format!("{:?}", b.snapshot().players));
+    for (i, &input) in log.inputs[1000..1600].iter().enumerate() {
+        let (x, y) = (a.step(FIXED_STEP, input), b.step(FIXED_STEP, input));
+        assert_eq!(x.events, y.events, "events differ {} steps after load", i + 1);
+        assert_eq!(a.state_hash(), b.state_hash(), "diverged {} steps after load", i + 1);
+    }
+}
+
+// 一番新しい隕石が（ブラックホールに吸われる・エネルギーで消えるなどで）もう無いときに保存しても、
+// 読み込んだ続きの隕石の番号が保存しなかった場合と同じになる
+#[test]
+fn load_keeps_meteor_ids() {
+    let log = parse(include_str!("replays/seed-7.log"));
+    let mut a = HeadlessGame::with_size(log.seed, log.width, log.height);
+    let mut b = HeadlessGame::with_size(log.seed, log.width, log.height);
+    for (i, &input) in log.inputs.iter().enumerate() {
+        if i % 50 == 0 { b.load(&a.save()).expect("save state should load"); }
+        // 決着のゆっくりは読み込みで持ち越さないので、その手前まで
+        if !a.step(FIXED_STEP, input).players[0].alive { break; }
+        b.step(FIXED_STEP, input);
+        assert_eq!(a.state_hash(), b.state_hash(), "diverged at step {}", i);
+    }
+}
@@ -0,0 +1,53 @@
+// ヘッドレス版の API のテスト（cargo test --features headless）
+use meteor_dodge::headless::{HeadlessGame, HeadlessInput, Rng, FIXED_STEP};
+use std::cell::Cell;
+use std::rc::Rc;
+
+// テストから出す値を決める乱数（いつも同じ値を返す。reseed されても変わらない）
+struct Scripted(Rc<Cell<u64>>);
+
+impl Rng for Scripted {
+    fn next_u64(&mut self) -> u64 { self.0.get() }
+    fn reseed(&mut self, _seed: u64) {}
+    fn state(&self) -> u64 { self.0.get() }
+    fn set_state(&mut self, state: u64) { self.0.set(state); }
+}
+
+fn scripted(value: u64) -> (HeadlessGame, Rc<Cell<u64>>) {
+    let v = Rc::new(Cell::new(value));
+    (HeadlessGame::with_rng(1, 640.0, 400.0, Box::new(Scripted(v.clone()))), v)
+}
+
+// 乱数が 0 しか出さなければ、隕石はみな左端に出る
+#[test]
+fn scripted_rng_drives_spawns() {
+    let (mut g, _) = scripted(0);
+    let mut snap = g.snapshot();
+    for _ in 0..240 { snap = g.step(FIXED_STEP, HeadlessInput::default()); }
+    assert!(!snap.meteors.is_empty(), "no meteors spawned");
+    assert!(snap.meteors.iter().all(|m| m.x == 0.0), "meteor spawned away from the left edge");
+}
+
+// 作ったらすぐ遊べる（ゲームオーバーやデモで止まっていない）
+#[test]
+fn new_game_is_live() {
+    let mut g = HeadlessGame::new(7);
+    assert!(!g.is_over());
+    let snap = g.step(FIXED_STEP, HeadlessInput::default());
+    assert!(snap.elapsed > 0.0, "first step did not advance the run");
+}
+
+// 隕石は左端に寄せたまま、最初のレーザーが出る 40 秒のステップだけ真ん中の値を返して自機の列を狙わせる。
+// 動かなければレーザーで落ち、その原因が残る
+#[test]
+fn laser_death_records_cause() {
+    let (mut g, v) = scripted(0);
+    let mut snap = g.snapshot();
+    while snap.players[0].alive && snap.elapsed < 60.0 {
+        let aim = snap.elapsed < 40.0 && snap.elapsed + FIXED_STEP >= 40.0 - 1e-9;
+        v.set(if aim { u32::MAX as u64 / 2 } else { 0 });
+        snap = g.step(FIXED_STEP, HeadlessInput::default());
+    }
+    assert!(!snap.players[0].alive, "player survived the laser");
+    assert_eq!(snap.cause, Some("laser"));
+}
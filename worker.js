@@ -0,0 +1,5 @@
+// new GameWorker("./worker.js", config) で読み込む worker（type: "module"）
+import init, { worker_main } from "./pkg/meteor_dodge.js";
+
+await init();
+worker_main();